@@ -21,43 +21,55 @@ use{
 
 #[cfg(feature = "serde_support")]
 use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde_support")]
+use {
+    crate::snapshot::*,
+    serde::de::DeserializeOwned,
+    std::io::{Read, Write}
+};
 
 /// Implements a Barabási-Albert Graph ensemble
+/// * `A` is the adjacency container of the graph. For graphs with large hubs use
+///   [`IndexedNodeContainer`](crate::IndexedNodeContainer), which checks for edges in constant time
+///   and create the ensemble with the `*_with_container` constructors, e.g., [`new_with_container`](#method.new_with_container)
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
-pub struct BAensemble<T, R>
+pub struct BAensemble<T, R, A = NodeContainer<T>>
 where T: Node,
       R: rand::Rng {
     source_graph: Graph<T>,
-    ba_graph: Graph<T>,
+    ba_graph: GenericGraph<T, A>,
     rng: R,
     m: usize,
     weights: Vec<usize>,
 }
 
-impl<T, R> AsRef<Graph<T>> for BAensemble<T, R>
+impl<T, R, A> AsRef<GenericGraph<T, A>> for BAensemble<T, R, A>
 where T: Node,
-      R: rand::Rng
+      R: rand::Rng,
+      A: AdjContainer<T>
 {
     #[inline]
-    fn as_ref(&self) -> &Graph<T>{
+    fn as_ref(&self) -> &GenericGraph<T, A>{
         &self.ba_graph
     }
 }
 
-impl<T, R> Borrow<Graph<T>> for BAensemble<T, R>
+impl<T, R, A> Borrow<GenericGraph<T, A>> for BAensemble<T, R, A>
 where T: Node,
-      R: rand::Rng
+      R: rand::Rng,
+      A: AdjContainer<T>
 {
     #[inline]
-    fn borrow(&self) -> &Graph<T> {
+    fn borrow(&self) -> &GenericGraph<T, A> {
         &self.ba_graph
     }
 }
 
-impl<T, R> WithGraph<T, Graph<T>> for BAensemble<T, R>
+impl<T, R, A> WithGraph<T, GenericGraph<T, A>> for BAensemble<T, R, A>
 where   T: Node + SerdeStateConform,
-        R: rand::Rng
+        R: rand::Rng,
+        A: AdjContainer<T>
 {
     fn at(&self, index: usize) -> &T {
         self.ba_graph.at(index)
@@ -67,7 +79,7 @@ where   T: Node + SerdeStateConform,
         self.ba_graph.at_mut(index)
     }
 
-    fn graph(&self) -> &Graph<T> {
+    fn graph(&self) -> &GenericGraph<T, A> {
         self.borrow()
     }
 
@@ -85,12 +97,49 @@ where T: Node + SerdeStateConform,
     /// * `m` = 1
     /// * `n`: Number of nodes, `n > 1` has to be true *panics* otherwise
     /// * `rng`:  Rng to use
-    /// 
+    /// * for other adjacency containers see [`new_with_container`](#method.new_with_container)
     pub fn new(n: usize, rng: R, m: usize, source_n: usize) -> Self {
+        Self::new_with_container(n, rng, m, source_n)
+    }
+
+    /// Generate a new BA graph ensemble with a specified source graph
+    /// * **panics** if `source_graph` contains any vertices with degree 0
+    /// * `m`: how many edges should each newly added vertex have originally
+    /// * `rng`: Random number generator
+    /// * `n`: Number of nodes, `n > source_graph.vertex_count()` has to be true *panics* otherwise
+    pub fn new_from_graph<B>(n:usize, rng: R, m: usize, source_graph: B) -> Self
+    where B: Borrow<Graph<T>>
+    {
+        Self::new_from_graph_with_container(n, rng, m, source_graph)
+    }
+
+    /// Generate a new BA graph ensemble with a specified generic source graph
+    /// * **panics** if `generic_source_graph` contains any vertices with degree 0
+    /// * `m`: how many edges should each newly added vertex have originally
+    /// * `rng`: Random number generator
+    /// * `n`: Number of nodes, `n > source_graph.vertex_count()` has to be true *panics* otherwise
+    pub fn new_from_generic_graph<A2, B>(n:usize, rng: R, m: usize, generic_source_graph: B) -> Self
+    where
+        A2: AdjContainer<T>,
+        B: Borrow<GenericGraph<T, A2>>
+    {
+        Self::new_from_generic_graph_with_container(n, rng, m, generic_source_graph)
+    }
+}
+
+impl<T, R, A> BAensemble<T, R, A>
+where T: Node + SerdeStateConform,
+      R: rand::Rng,
+      A: AdjContainer<T>
+{
+    /// # Initialize with adjacency container `A`
+    /// * same as [`new`](#method.new), but the BA graph uses the adjacency container `A`,
+    ///   e.g., [`IndexedNodeContainer`](crate::IndexedNodeContainer)
+    pub fn new_with_container(n: usize, rng: R, m: usize, source_n: usize) -> Self {
         assert!(source_n >= 2);
         assert!(n > source_n);
         let source_graph: Graph::<T> = Graph::complete_graph(source_n);
-        let ba_graph: Graph<T> = Graph::new(n);
+        let ba_graph: GenericGraph<T, A> = GenericGraph::new(n);
         let mut e = BAensemble {
             ba_graph,
             source_graph,
@@ -102,12 +151,9 @@ where T: Node + SerdeStateConform,
         e
     }
 
-    /// Generate a new BA graph ensemble with a specified source graph
-    /// * **panics** if `source_graph` contains any vertices with degree 0
-    /// * `m`: how many edges should each newly added vertex have originally
-    /// * `rng`: Random number generator
-    /// * `n`: Number of nodes, `n > source_graph.vertex_count()` has to be true *panics* otherwise
-    pub fn new_from_graph<B>(n:usize, rng: R, m: usize, source_graph: B) -> Self
+    /// * same as [`new_from_graph`](#method.new_from_graph),
+    ///   but the BA graph uses the adjacency container `A`
+    pub fn new_from_graph_with_container<B>(n:usize, rng: R, m: usize, source_graph: B) -> Self
     where B: Borrow<Graph<T>>
     {
         assert!(
@@ -115,7 +161,7 @@ where T: Node + SerdeStateConform,
             "Source graph is not allowed to contain any vertices without edges!"
         );
         assert!(n > source_graph.borrow().vertex_count());
-        let mut ba_graph: Graph<T> = Graph::new(n);
+        let mut ba_graph: GenericGraph<T, A> = GenericGraph::new(n);
         for i in 0..source_graph.borrow().vertex_count() {
             *ba_graph.at_mut(i) = (*source_graph.borrow().at(i)).clone();
         }
@@ -130,12 +176,9 @@ where T: Node + SerdeStateConform,
         e
     }
 
-    /// Generate a new BA graph ensemble with a specified generic source graph
-    /// * **panics** if `generic_source_graph` contains any vertices with degree 0
-    /// * `m`: how many edges should each newly added vertex have originally
-    /// * `rng`: Random number generator
-    /// * `n`: Number of nodes, `n > source_graph.vertex_count()` has to be true *panics* otherwise
-    pub fn new_from_generic_graph<A2, B>(n:usize, rng: R, m: usize, generic_source_graph: B) -> Self
+    /// * same as [`new_from_generic_graph`](#method.new_from_generic_graph),
+    ///   but the BA graph uses the adjacency container `A`
+    pub fn new_from_generic_graph_with_container<A2, B>(n:usize, rng: R, m: usize, generic_source_graph: B) -> Self
     where
         A2: AdjContainer<T>,
         B: Borrow<GenericGraph<T, A2>>
@@ -146,7 +189,7 @@ where T: Node + SerdeStateConform,
         );
         assert!(n > generic_source_graph.borrow().vertex_count());
         let source_graph: Graph<T> = generic_source_graph.borrow().into();
-        let mut ba_graph: Graph<T> = Graph::new(n);
+        let mut ba_graph: GenericGraph<T, A> = GenericGraph::new(n);
         for i in 0..source_graph.vertex_count() {
             *ba_graph.at_mut(i) = (*source_graph.at(i)).clone();
         }
@@ -169,30 +212,32 @@ where T: Node + SerdeStateConform,
     }
 }
 
-impl<T, R> GraphIteratorsMut<T, Graph<T>, NodeContainer<T>> for BAensemble<T, R>
+impl<T, R, A> GraphIteratorsMut<T, GenericGraph<T, A>, A> for BAensemble<T, R, A>
 where   T: Node + SerdeStateConform,
-        R: rand::Rng
+        R: rand::Rng,
+        A: AdjContainer<T>
 {
     fn contained_iter_neighbors_mut(&mut self, index: usize) ->
-        NContainedIterMut<T, NodeContainer<T>, IterWrapper>
+        NContainedIterMut<T, A, IterWrapper>
     {
         self.ba_graph.contained_iter_neighbors_mut(index)
     }
 
     fn contained_iter_neighbors_mut_with_index(&mut self, index: usize)
-        -> INContainedIterMut<'_, T, NodeContainer<T>>
+        -> INContainedIterMut<'_, T, A>
     {
         self.ba_graph.contained_iter_neighbors_mut_with_index(index)
     }
 
-    fn contained_iter_mut(&mut self) ->  ContainedIterMut<T, NodeContainer<T>> {
+    fn contained_iter_mut(&mut self) ->  ContainedIterMut<T, A> {
         self.ba_graph.contained_iter_mut()
     }
 }
 
-impl<T, R> SimpleSample for BAensemble<T, R>
+impl<T, R, A> SimpleSample for BAensemble<T, R, A>
 where   T: Node + SerdeStateConform,
         R: rand::Rng,
+        A: AdjContainer<T>,
 {
     /// # Randomizes the Barabási-Albert (BA) graph
     /// * this essentially deletes the BA graph and creates a new one using the initial graph
@@ -229,6 +274,32 @@ where   T: Node + SerdeStateConform,
     }
 }
 
+#[cfg(feature = "serde_support")]
+impl<T, R, A> Snapshot for BAensemble<T, R, A>
+where T: Node + DeserializeOwned,
+      R: Rng + Serialize + DeserializeOwned,
+      A: SnapshotContainer<T>
+{
+    fn save_snapshot<W: Write>(&self, writer: W) -> Result<(), SnapshotError>
+    {
+        let mut snapshot = SnapshotWriter::new(writer, "BAensemble", 1)?;
+        snapshot.graph(&self.source_graph)?;
+        snapshot.graph(&self.ba_graph)?;
+        snapshot.section(&(self.m, &self.weights))?;
+        snapshot.section(&self.rng)
+    }
+
+    fn load_snapshot<Re: Read>(reader: Re) -> Result<Self, SnapshotError>
+    {
+        let mut snapshot = SnapshotReader::new(reader, "BAensemble", 1)?;
+        let source_graph = snapshot.graph()?;
+        let ba_graph = snapshot.graph()?;
+        let (m, weights) = snapshot.section()?;
+        let rng = snapshot.section()?;
+        Ok(Self{source_graph, ba_graph, rng, m, weights})
+    }
+}
+
 #[cfg(test)]
 mod testing {
     use super::*;
@@ -0,0 +1,334 @@
+//! # Cascading overload failures
+//! Implements the load redistribution model by Motter and Lai.
+//!
+//! * the **load** of a vertex (edge) is its betweenness,
+//!   see [`vertex_load(false)`](crate::GenericGraph::vertex_load)
+//!   and [`edge_load`](crate::GenericGraph::edge_load)
+//! * the **capacity** is `(1 + alpha) * initial_load`
+//! * after an initial failure, the loads are recalculated. Every vertex (edge), whose
+//!   load now exceeds its capacity, fails as well. This is repeated until no further
+//!   failures occur
+//!
+//! The cascade size can be used as observable for large-deviation simulations
+//! of the resilience of, e.g., the [spacial ensemble](crate::spacial).
+//!
+//! # Citations
+//! > A. E. Motter and Y.-C. Lai,
+//! > "Cascade-based attacks on complex networks"
+//! > *Phys. Rev. E*&nbsp;**66**, 065102(R)&nbsp;(2002),
+//! > DOI: [10.1103/PhysRevE.66.065102](https://doi.org/10.1103/PhysRevE.66.065102)
+use {
+    crate::{
+        traits::*,
+        graph::Graph,
+        GenericGraph
+    },
+    std::collections::HashMap
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Serialize, Deserialize};
+
+/// loads are compared with this relative tolerance,
+/// otherwise floating point noise could trigger failures for `alpha = 0`
+const LOAD_TOLERANCE: f64 = 1e-10;
+
+#[inline]
+fn is_overloaded(load: f64, capacity: f64) -> bool
+{
+    load > capacity + LOAD_TOLERANCE * capacity.max(1.0)
+}
+
+/// # Result of a cascade
+/// * `E` is `usize` for vertex cascades and `(usize, usize)` for edge cascades
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Cascade<E> {
+    vertex_count: usize,
+    failures: Vec<Vec<E>>,
+    giant_component: usize,
+}
+
+impl<E> Cascade<E> {
+    /// # Failures of each step
+    /// * `failures_per_step()[0]` contains the initial failures
+    /// * `failures_per_step()[k]` contains everything that failed due to overload in step `k`
+    pub fn failures_per_step(&self) -> &[Vec<E>]
+    {
+        &self.failures
+    }
+
+    /// # Iterate over all failed vertices (edges)
+    /// * including the initial failures
+    /// * in the order they failed
+    pub fn failed_iter(&self) -> impl Iterator<Item=&E>
+    {
+        self.failures
+            .iter()
+            .flatten()
+    }
+
+    /// # Number of steps the cascade took
+    /// * number of load redistributions which caused further failures
+    /// * `0` if the initial failure did not cause any overload
+    pub fn steps(&self) -> usize
+    {
+        self.failures.len() - 1
+    }
+
+    /// # Size of the cascade
+    /// * total number of failed vertices (edges), including the initial failures
+    pub fn size(&self) -> usize
+    {
+        self.failures
+            .iter()
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// # Number of failures caused by overload
+    /// * same as `self.size()` minus the number of initial failures
+    pub fn overload_failures(&self) -> usize
+    {
+        self.size() - self.failures[0].len()
+    }
+
+    /// # Size of the largest connected component after the cascade
+    /// * failed vertices are not counted
+    pub fn giant_component(&self) -> usize
+    {
+        self.giant_component
+    }
+
+    /// # Fraction of vertices in the giant component after the cascade
+    /// * `G = N'/N` in the notation of Motter and Lai
+    pub fn giant_component_fraction(&self) -> f64
+    {
+        self.giant_component as f64 / self.vertex_count as f64
+    }
+}
+
+/// largest connected component that does not contain failed vertices
+fn surviving_giant_component<T: Node>(graph: &Graph<T>, failed: &[bool]) -> usize
+{
+    let mut handled = failed.to_vec();
+    let mut stack = Vec::new();
+    let mut max = 0;
+    for i in 0..graph.vertex_count() {
+        if handled[i] {
+            continue;
+        }
+        handled[i] = true;
+        stack.push(i);
+        let mut size = 0;
+        while let Some(index) = stack.pop() {
+            size += 1;
+            for &neighbor in graph.container(index).neighbors() {
+                if !handled[neighbor] {
+                    handled[neighbor] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        max = max.max(size);
+    }
+    max
+}
+
+fn remove_vertex_edges<T: Node>(graph: &mut Graph<T>, index: usize, buf: &mut Vec<usize>)
+{
+    buf.clear();
+    buf.extend(graph.container(index).neighbors());
+    for &neighbor in buf.iter() {
+        graph.remove_edge(index, neighbor).unwrap();
+    }
+}
+
+impl<T, A> GenericGraph<T, A>
+where T: Node,
+      A: AdjContainer<T>
+{
+    /// # Motter-Lai cascade of vertex failures
+    /// * `alpha`: tolerance parameter, capacity of each vertex is `(1 + alpha) * initial_load`
+    /// * `initial_failure`: vertices that fail initially. Duplicates are ignored
+    /// * all vertices that are overloaded after a redistribution of the load fail simultaneously
+    /// * `self` is not changed, the cascade is performed on a copy of the topology
+    /// * **panics** if any index of `initial_failure` is out of bounds
+    /// # Example
+    /// ```
+    /// use net_ensembles::{spacial::SpacialEnsemble, EmptyNode, rand::SeedableRng};
+    /// use rand_pcg::Pcg64;
+    ///
+    /// let rng = Pcg64::seed_from_u64(7829);
+    /// let ensemble = SpacialEnsemble::<EmptyNode, _>::new(50, rng, 0.95, 3.0);
+    ///
+    /// let cascade = ensemble.as_ref()
+    ///     .motter_lai_vertex_cascade(0.2, &[0]);
+    /// assert!(cascade.size() >= 1);
+    /// println!(
+    ///     "{} vertices failed in {} steps, G = {}",
+    ///     cascade.size(),
+    ///     cascade.steps(),
+    ///     cascade.giant_component_fraction()
+    /// );
+    /// ```
+    pub fn motter_lai_vertex_cascade(&self, alpha: f64, initial_failure: &[usize]) -> Cascade<usize>
+    {
+        let mut graph = Graph::from(self);
+        let capacity: Vec<_> = graph.vertex_load(false)
+            .into_iter()
+            .map(|load| (1.0 + alpha) * load)
+            .collect();
+
+        let mut failed = vec![false; graph.vertex_count()];
+        let mut buf = Vec::new();
+        let mut current: Vec<usize> = Vec::with_capacity(initial_failure.len());
+        for &index in initial_failure {
+            if !failed[index] {
+                failed[index] = true;
+                current.push(index);
+            }
+        }
+
+        let mut failures = Vec::new();
+        while !current.is_empty() {
+            for &index in current.iter() {
+                remove_vertex_edges(&mut graph, index, &mut buf);
+            }
+            failures.push(current);
+
+            current = graph.vertex_load(false)
+                .into_iter()
+                .zip(capacity.iter())
+                .enumerate()
+                .filter(|&(index, (load, &cap))| !failed[index] && is_overloaded(load, cap))
+                .map(|(index, _)| index)
+                .collect();
+            for &index in current.iter() {
+                failed[index] = true;
+            }
+        }
+        if failures.is_empty() {
+            failures.push(Vec::new());
+        }
+
+        Cascade{
+            vertex_count: graph.vertex_count(),
+            giant_component: surviving_giant_component(&graph, &failed),
+            failures,
+        }
+    }
+
+    /// # Motter-Lai cascade of edge failures
+    /// * `alpha`: tolerance parameter, capacity of each edge is `(1 + alpha) * initial_load`
+    /// * `initial_failure`: edges that fail initially. Duplicates are ignored.
+    ///   `(i, j)` and `(j, i)` refer to the same edge
+    /// * all edges that are overloaded after a redistribution of the load fail simultaneously
+    /// * failed edges are reported as `(i, j)` with `i < j`
+    /// * `self` is not changed, the cascade is performed on a copy of the topology
+    /// * returns `Err(GraphErrors::EdgeDoesNotExist)` if any edge of `initial_failure` is not part of the graph
+    pub fn motter_lai_edge_cascade(&self, alpha: f64, initial_failure: &[(usize, usize)])
+        -> Result<Cascade<(usize, usize)>, GraphErrors>
+    {
+        let mut graph = Graph::from(self);
+        let capacity: HashMap<_, _> = graph.edge_load()
+            .into_iter()
+            .map(|(edge, load)| (edge, (1.0 + alpha) * load))
+            .collect();
+
+        let mut current = Vec::with_capacity(initial_failure.len());
+        for &(i, j) in initial_failure {
+            let edge = (i.min(j), i.max(j));
+            if !capacity.contains_key(&edge) {
+                return Err(GraphErrors::EdgeDoesNotExist);
+            }
+            if !current.contains(&edge) {
+                current.push(edge);
+            }
+        }
+
+        let mut failures = Vec::new();
+        while !current.is_empty() {
+            for &(i, j) in current.iter() {
+                graph.remove_edge(i, j).unwrap();
+            }
+            failures.push(current);
+
+            current = graph.edge_load()
+                .into_iter()
+                .filter(|(edge, load)| is_overloaded(*load, capacity[edge]))
+                .map(|(edge, _)| edge)
+                .collect();
+        }
+        if failures.is_empty() {
+            failures.push(Vec::new());
+        }
+
+        let no_failures = vec![false; graph.vertex_count()];
+        Ok(
+            Cascade{
+                vertex_count: graph.vertex_count(),
+                giant_component: surviving_giant_component(&graph, &no_failures),
+                failures,
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EmptyNode;
+
+    fn ring(n: usize) -> Graph<EmptyNode>
+    {
+        let mut graph = Graph::new(n);
+        for i in 0..n {
+            graph.add_edge(i, (i + 1) % n).unwrap();
+        }
+        graph
+    }
+
+    #[test]
+    fn vertex_cascade_on_ring()
+    {
+        // removing vertex 0 turns the ring into a path, the center of the path is overloaded
+        let graph = ring(6);
+        let cascade = graph.motter_lai_vertex_cascade(0.6, &[0]);
+        assert_eq!(cascade.failures_per_step(), &[vec![0], vec![3]]);
+        assert_eq!(cascade.steps(), 1);
+        assert_eq!(cascade.size(), 2);
+        assert_eq!(cascade.giant_component(), 2);
+
+        let cascade = graph.motter_lai_vertex_cascade(0.25, &[0, 0]);
+        assert_eq!(cascade.failures_per_step(), &[vec![0], vec![2, 3, 4]]);
+        assert_eq!(cascade.overload_failures(), 3);
+        assert_eq!(cascade.giant_component(), 1);
+
+        let cascade = graph.motter_lai_vertex_cascade(1.5, &[0]);
+        assert_eq!(cascade.steps(), 0);
+        assert_eq!(cascade.giant_component(), 5);
+
+        let cascade = graph.motter_lai_vertex_cascade(0.0, &[]);
+        assert_eq!(cascade.size(), 0);
+        assert_eq!(cascade.giant_component_fraction(), 1.0);
+    }
+
+    #[test]
+    fn edge_cascade()
+    {
+        let graph = ring(6);
+        let cascade = graph.motter_lai_edge_cascade(0.0, &[(1, 0)])
+            .unwrap();
+        assert_eq!(cascade.failures_per_step()[0], vec![(0, 1)]);
+        assert!(cascade.steps() > 0);
+        assert!(cascade.failed_iter().all(|&(i, j)| i < j));
+
+        let cascade = graph.motter_lai_edge_cascade(10.0, &[(0, 1)])
+            .unwrap();
+        assert_eq!(cascade.size(), 1);
+        assert_eq!(cascade.giant_component(), 6);
+
+        assert!(graph.motter_lai_edge_cascade(0.0, &[(0, 3)]).is_err());
+    }
+}
@@ -2,12 +2,20 @@
 //!
 #[cfg(feature = "serde_support")]
 use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde_support")]
+use {
+    crate::snapshot::*,
+    crate::trajectory::RedoStep,
+    serde::de::DeserializeOwned,
+    std::io::{Read, Write}
+};
 
 use{
     crate::{
         traits::*,
         graph::*,
         iter::*,
+        connectivity::EdgeChanges,
         GenericGraph
     },
     std::{
@@ -27,18 +35,22 @@ use{
 /// * the degree of each vertex is fixed (see self.degree_vec),
 ///  while the actual edges will be drawn randomly
 /// * No self loops allowed
-pub struct ConfigurationModel<T, R>
+/// * `A` is the adjacency container of the graph. For large degrees use
+///   [`IndexedNodeContainer`](crate::IndexedNodeContainer), which checks for edges in constant time
+///   and create the ensemble with the `*_with_container` constructors, e.g., [`from_vec_with_container`](#method.from_vec_with_container)
+pub struct ConfigurationModel<T, R, A = NodeContainer<T>>
 where T: Node
 {
-    graph: Graph<T>,
+    graph: GenericGraph<T, A>,
     degree_vec: Vec<usize>,
     rng: R,
     random_edge_halfs: Vec<usize>,  // optimization to lessen the number of required allocations
     random_edge_halfs_backup: Vec<usize>, // optimization to lessen the number of required allocations
 }
 
-impl<T, R> ConfigurationModel<T, R>
+impl<T, R, A> ConfigurationModel<T, R, A>
 where T: Node,
+      A: AdjContainer<T>,
 {
     /// Get reference to the degree vector of the vertices,
     /// faster than `self.graph().degree_vec()`,
@@ -48,6 +60,25 @@ where T: Node,
     {
         &self.degree_vec
     }
+
+    /// initialize or update the edge halfs vectors for later usage
+    fn init_edge_halfs(&mut self)
+    {
+        self.random_edge_halfs_backup.clear();
+        let ptr = self.degree_vec.as_ptr();
+        let len = self.degree_vec.len();
+        self.random_edge_halfs_backup.extend(
+            (0..len)
+            .flat_map(
+                |i| 
+                {
+                    let times: usize = unsafe { *ptr.add(i)};
+                    iter::repeat(i).take(times)
+
+                }
+            )
+        );
+    }
 }
 
 impl<T, R> ConfigurationModel<T, R>
@@ -61,16 +92,7 @@ where T: Node,
     /// * returns `None` if resulting degree vector is invalid
     pub fn from_const(constant: usize, size: usize, rng: R) -> Option<Self>
     {
-        if constant >= size - 1 || size * constant % 2 != 0 {
-            None
-        } else {
-            Some(
-                Self::from_vec_unchecked(
-                    vec![constant; size]
-                    , rng
-                )
-            )
-        }
+        Self::from_const_with_container(constant, size, rng)
     }
 
     /// # create ConfigurationModel from a generic graph
@@ -79,7 +101,7 @@ where T: Node,
     where T1: Node,
         A1: AdjContainer<T1>
     {
-        Self::from_vec_unchecked(generic_graph.degree_vec(), rng)
+        Self::from_generic_graph_with_container(generic_graph, rng)
     }
 
     /// # create ConfigurationModel from a generic graph and clones underlying Data
@@ -89,11 +111,76 @@ where T: Node,
     /// * `model.graph()` will have the same topology as `generic_graph`
     /// after this creation. This will of cause change, if you call `randomize`
     /// or do markov steps
-    pub fn clone_from_generic_graph<A>(generic_graph: &GenericGraph<T, A>, rng: R) -> Self
+    pub fn clone_from_generic_graph<A1>(generic_graph: &GenericGraph<T, A1>, rng: R) -> Self
     where T: Clone,
-        A: AdjContainer<T>
+        A1: AdjContainer<T>
+    {
+        Self::clone_from_generic_graph_with_container(generic_graph, rng)
+    }
+
+    /// # create configuration model from a degree vector
+    /// * drawn graphs will consist of `degree_vec.len()` vertices, where 
+    /// a vertex *i* will have degree `degree_vec[i]`
+    /// * returns `None` if degree vector is invalid
+    pub fn from_vec(degree_vec: Vec<usize>, rng: R) -> Option<Self>
+    {
+        Self::from_vec_with_container(degree_vec, rng)
+    }
+
+    /// # create configuration model from a degree vector
+    /// * same as [`from_vec`](#method.from_vec), but it does not check if the 
+    /// `degree_vec` is valid - that is on you now
+    pub fn from_vec_unchecked(degree_vec: Vec<usize>, rng: R) -> Self
     {
-        let graph = Graph::from(generic_graph);
+        Self::from_vec_unchecked_with_container(degree_vec, rng)
+    }
+}
+
+impl<T, R, A> ConfigurationModel<T, R, A>
+where T: Node,
+    R: rand::Rng,
+    A: AdjContainer<T>
+{
+    /// # create configuration model from a constant degree, with adjacency container `A`
+    /// * same as [`from_const`](#method.from_const), but the graph uses the adjacency container `A`,
+    ///   e.g., [`IndexedNodeContainer`](crate::IndexedNodeContainer)
+    pub fn from_const_with_container(constant: usize, size: usize, rng: R) -> Option<Self>
+    {
+        if constant >= size - 1 || size * constant % 2 != 0 {
+            None
+        } else {
+            Some(
+                Self::from_vec_unchecked_with_container(
+                    vec![constant; size]
+                    , rng
+                )
+            )
+        }
+    }
+
+    /// # create ConfigurationModel from a generic graph, with adjacency container `A`
+    /// * same as [`from_generic_graph`](#method.from_generic_graph), but the graph uses the adjacency container `A`,
+    ///   e.g., [`IndexedNodeContainer`](crate::IndexedNodeContainer)
+    pub fn from_generic_graph_with_container<T1, A1>(generic_graph: &GenericGraph<T1, A1>, rng: R) -> Self
+    where T1: Node,
+        A1: AdjContainer<T1>
+    {
+        Self::from_vec_unchecked_with_container(generic_graph.degree_vec(), rng)
+    }
+
+    /// # create ConfigurationModel from a generic graph and clones underlying Data, with adjacency container `A`
+    /// * same as [`clone_from_generic_graph`](#method.clone_from_generic_graph), but the graph uses the adjacency container `A`,
+    ///   e.g., [`IndexedNodeContainer`](crate::IndexedNodeContainer)
+    pub fn clone_from_generic_graph_with_container<A1>(generic_graph: &GenericGraph<T, A1>, rng: R) -> Self
+    where T: Clone,
+        A1: AdjContainer<T>
+    {
+        let mut graph = GenericGraph::from_vec(
+            generic_graph.container_iter()
+                .map(|container| container.contained().clone())
+                .collect()
+        );
+        graph.reset_from_graph(generic_graph);
         let mut res = Self{
             graph,
             degree_vec: generic_graph.degree_vec(),
@@ -105,26 +192,25 @@ where T: Node,
         res
     }
 
-    /// # create configuration model from a degree vector
-    /// * drawn graphs will consist of `degree_vec.len()` vertices, where 
-    /// a vertex *i* will have degree `degree_vec[i]`
-    /// * returns `None` if degree vector is invalid
-    pub fn from_vec(degree_vec: Vec<usize>, rng: R) -> Option<Self>
+    /// # create configuration model from a degree vector, with adjacency container `A`
+    /// * same as [`from_vec`](#method.from_vec), but the graph uses the adjacency container `A`,
+    ///   e.g., [`IndexedNodeContainer`](crate::IndexedNodeContainer)
+    pub fn from_vec_with_container(degree_vec: Vec<usize>, rng: R) -> Option<Self>
     {
         if Self::degree_vec_is_valid(&degree_vec){
-            Some(Self::from_vec_unchecked(degree_vec, rng))
+            Some(Self::from_vec_unchecked_with_container(degree_vec, rng))
         } else {
             None
         }
        
     }
 
-    /// # create configuration model from a degree vector
-    /// * same as [`from_vec`](#method.from_vec), but it does not check if the 
-    /// `degree_vec` is valid - that is on you now
-    pub fn from_vec_unchecked(degree_vec: Vec<usize>, rng: R) -> Self
+    /// # create configuration model from a degree vector, with adjacency container `A`
+    /// * same as [`from_vec_unchecked`](#method.from_vec_unchecked), but the graph uses the adjacency container `A`,
+    ///   e.g., [`IndexedNodeContainer`](crate::IndexedNodeContainer)
+    pub fn from_vec_unchecked_with_container(degree_vec: Vec<usize>, rng: R) -> Self
     {
-        let graph = Graph::<T>::new(degree_vec.len());
+        let graph = GenericGraph::<T, A>::new(degree_vec.len());
         let mut res = Self{
             graph,
             degree_vec,
@@ -224,50 +310,34 @@ where T: Node,
         self.init_edge_halfs();
         self.randomize();
     }
-
-    /// initialize or update the edge halfs vectors for later usage
-    fn init_edge_halfs(&mut self)
-    {
-        self.random_edge_halfs_backup.clear();
-        let ptr = self.degree_vec.as_ptr();
-        let len = self.degree_vec.len();
-        self.random_edge_halfs_backup.extend(
-            (0..len)
-            .flat_map(
-                |i| 
-                {
-                    let times: usize = unsafe { *ptr.add(i)};
-                    iter::repeat(i).take(times)
-
-                }
-            )
-        );
-    }
 }
 
 
-impl<T, R> AsRef<Graph<T>> for ConfigurationModel<T, R>
-where T: Node
+impl<T, R, A> AsRef<GenericGraph<T, A>> for ConfigurationModel<T, R, A>
+where T: Node,
+      A: AdjContainer<T>
 {
     #[inline]
-    fn as_ref(&self) -> &Graph<T>{
+    fn as_ref(&self) -> &GenericGraph<T, A>{
         &self.graph
     }
 }
 
-impl<T, R> Borrow<Graph<T>> for ConfigurationModel<T, R>
-where T: Node
+impl<T, R, A> Borrow<GenericGraph<T, A>> for ConfigurationModel<T, R, A>
+where T: Node,
+      A: AdjContainer<T>
 {
     #[inline]
-    fn borrow(&self) -> &Graph<T> {
+    fn borrow(&self) -> &GenericGraph<T, A> {
         &self.graph
     }
 }
 
 
-impl<T, R> HasRng<R> for ConfigurationModel<T, R>
+impl<T, R, A> HasRng<R> for ConfigurationModel<T, R, A>
     where   T: Node,
             R: rand::Rng,
+            A: AdjContainer<T>,
 {
     fn rng(&mut self) -> &mut R {
         &mut self.rng
@@ -278,29 +348,31 @@ impl<T, R> HasRng<R> for ConfigurationModel<T, R>
     }
 }
 
-impl<T, R> GraphIteratorsMut<T, Graph<T>, NodeContainer<T>> for ConfigurationModel<T, R>
-where   T: Node
+impl<T, R, A> GraphIteratorsMut<T, GenericGraph<T, A>, A> for ConfigurationModel<T, R, A>
+where   T: Node,
+        A: AdjContainer<T>
 {
     fn contained_iter_neighbors_mut(&mut self, index: usize) ->
-        NContainedIterMut<T, NodeContainer<T>, IterWrapper>
+        NContainedIterMut<T, A, IterWrapper>
     {
         self.graph.contained_iter_neighbors_mut(index)
     }
 
     fn contained_iter_neighbors_mut_with_index(&mut self, index: usize)
-        -> INContainedIterMut<'_, T, NodeContainer<T>>
+        -> INContainedIterMut<'_, T, A>
     {
         self.graph.contained_iter_neighbors_mut_with_index(index)
     }
 
-    fn contained_iter_mut(&mut self) ->  ContainedIterMut<T, NodeContainer<T>> {
+    fn contained_iter_mut(&mut self) ->  ContainedIterMut<T, A> {
         self.graph.contained_iter_mut()
     }
 }
 
 
-impl<T, R> WithGraph<T, Graph<T>> for ConfigurationModel<T, R>
+impl<T, R, A> WithGraph<T, GenericGraph<T, A>> for ConfigurationModel<T, R, A>
 where   T: Node,
+        A: AdjContainer<T>,
 {
     fn at(&self, index: usize) -> &T{
         self.graph.at(index)
@@ -310,7 +382,7 @@ where   T: Node,
         self.graph.at_mut(index)
     }
 
-    fn graph(&self) -> &Graph<T> {
+    fn graph(&self) -> &GenericGraph<T, A> {
         self.borrow()
     }
 
@@ -326,9 +398,10 @@ where   T: Node,
     }
 }
 
-impl<T, R> SimpleSample for ConfigurationModel<T, R>
+impl<T, R, A> SimpleSample for ConfigurationModel<T, R, A>
 where   T: Node,
         R: rand::Rng,
+        A: AdjContainer<T>,
 {
     /// # Randomizes the edges according to the configuration Model
     fn randomize(&mut self) {
@@ -351,9 +424,10 @@ where   T: Node,
 }
 
 
-impl<T, R> ConfigurationModel<T, R>
+impl<T, R, A> ConfigurationModel<T, R, A>
 where T: Node,
     R: rand::Rng,
+    A: AdjContainer<T>,
 {
 
     fn add_multiple_random_edges(&mut self) -> bool
@@ -399,6 +473,17 @@ pub enum ConfigurationModelStep {
 
 }
 
+impl EdgeChanges for ConfigurationModelStep {
+    fn edge_changes(&self, added: &mut Vec<(usize, usize)>, removed: &mut Vec<(usize, usize)>) {
+        if let Self::Added(edge1, edge2) = self {
+            added.push((edge1.0, edge2.0));
+            added.push((edge1.1, edge2.1));
+            removed.push(*edge1);
+            removed.push(*edge2);
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 /// Result of undoing a step via Markov Chain method of ConfigurationModel
@@ -410,9 +495,10 @@ pub enum UndoStepErrorCM {
 
 }
 
-impl<T, R> MarkovChain<ConfigurationModelStep, Result<(), UndoStepErrorCM>> for ConfigurationModel<T, R>
+impl<T, R, A> MarkovChain<ConfigurationModelStep, Result<(), UndoStepErrorCM>> for ConfigurationModel<T, R, A>
     where   T: Node + SerdeStateConform,
             R: rand::Rng,
+            A: AdjContainer<T> + AdjList<usize>,
 {
 
     /// # Markov step
@@ -436,8 +522,8 @@ impl<T, R> MarkovChain<ConfigurationModelStep, Result<(), UndoStepErrorCM>> for
             }
         }
         
-        let edge_1: (usize, usize) = (vertex_list[0], *self.graph.vertices[vertex_list[0]].adj.choose(&mut self.rng).unwrap());
-        let edge_2: (usize, usize) = (vertex_list[1], *self.graph.vertices[vertex_list[1]].adj.choose(&mut self.rng).unwrap());
+        let edge_1: (usize, usize) = (vertex_list[0], *self.graph.vertices[vertex_list[0]].edges().choose(&mut self.rng).unwrap());
+        let edge_2: (usize, usize) = (vertex_list[1], *self.graph.vertices[vertex_list[1]].edges().choose(&mut self.rng).unwrap());
 
         if edge_2.0 == edge_1.0 || edge_1.1 == edge_2.1 {
             return ConfigurationModelStep::Error;
@@ -530,8 +616,9 @@ impl<T, R> MarkovChain<ConfigurationModelStep, Result<(), UndoStepErrorCM>> for
 
 }
 
-impl<T, R> Contained<T> for ConfigurationModel<T, R>
-where T: Node
+impl<T, R, A> Contained<T> for ConfigurationModel<T, R, A>
+where T: Node,
+      A: AdjContainer<T>
 {
     fn get_contained(&self, index: usize) -> Option<&T> {
         self.graph.get_contained(index)
@@ -550,6 +637,82 @@ where T: Node
     }
 }
 
+#[cfg(feature = "serde_support")]
+impl<T, R, A> Snapshot for ConfigurationModel<T, R, A>
+where T: Node + DeserializeOwned,
+      R: Serialize + DeserializeOwned,
+      A: SnapshotContainer<T>
+{
+    fn save_snapshot<W: Write>(&self, writer: W) -> Result<(), SnapshotError>
+    {
+        let mut snapshot = SnapshotWriter::new(writer, "ConfigurationModel", 2)?;
+        snapshot.graph(&self.graph)?;
+        // the order of the edge halfs is shuffled by `randomize` and used by `m_step`,
+        // i.e., it is part of the state of the Markov chain
+        snapshot.section(&(&self.degree_vec, &self.random_edge_halfs_backup))?;
+        snapshot.section(&self.rng)
+    }
+
+    fn load_snapshot<Re: Read>(reader: Re) -> Result<Self, SnapshotError>
+    {
+        let mut snapshot = SnapshotReader::new(reader, "ConfigurationModel", 2)?;
+        let graph: GenericGraph<T, A> = snapshot.graph()?;
+        let (degree_vec, random_edge_halfs_backup): (Vec<usize>, Vec<usize>) = snapshot.section()?;
+        if degree_vec.len() != graph.vertex_count() {
+            return Err(SnapshotError::Corrupt("length of degree vector does not match".into()));
+        }
+        let mut res = Self{
+            graph,
+            degree_vec,
+            rng: snapshot.section()?,
+            random_edge_halfs: Vec::new(),
+            random_edge_halfs_backup: Vec::new(),
+        };
+        res.init_edge_halfs();
+        // stored edge halfs have to be a permutation of the ones belonging to the degree vector
+        let mut sorted = random_edge_halfs_backup.clone();
+        sorted.sort_unstable();
+        if sorted != res.random_edge_halfs_backup {
+            return Err(SnapshotError::Corrupt("edge halfs do not match degree vector".into()));
+        }
+        res.random_edge_halfs_backup = random_edge_halfs_backup;
+        Ok(res)
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<T, R, A> RedoStep<ConfigurationModelStep> for ConfigurationModel<T, R, A>
+where T: Node,
+      A: AdjContainer<T>
+{
+    /// * removes the edges `(edge1.0, edge1.1)` and `(edge2.0, edge2.1)` and
+    ///   adds `(edge1.0, edge2.0)` and `(edge1.1, edge2.1)`, like `m_step` did
+    fn redo_step(&mut self, step: &ConfigurationModelStep) -> bool {
+        let (edge1, edge2) = match *step {
+            ConfigurationModelStep::Error => return true,
+            ConfigurationModelStep::Added(edge1, edge2) => (edge1, edge2)
+        };
+        let n = self.graph.vertex_count();
+        let new1 = (edge1.0, edge2.0);
+        let new2 = (edge1.1, edge2.1);
+        let fits = [edge1, edge2, new1, new2]
+            .iter()
+            .all(|&edge| crate::trajectory::valid_pair(n, edge))
+            && self.graph.container(edge1.0).is_adjacent(edge1.1)
+            && self.graph.container(edge2.0).is_adjacent(edge2.1)
+            && !self.graph.container(new1.0).is_adjacent(new1.1)
+            && !self.graph.container(new2.0).is_adjacent(new2.1);
+        if fits {
+            // same order as in `m_step`
+            self.graph.add_edge(new1.0, new1.1).unwrap();
+            self.graph.add_edge(new2.0, new2.1).unwrap();
+            self.graph.remove_edge(edge1.0, edge1.1).unwrap();
+            self.graph.remove_edge(edge2.0, edge2.1).unwrap();
+        }
+        fits
+    }
+}
+
 #[cfg(test)]
 mod testing {
     use super::*;
@@ -566,6 +729,27 @@ mod testing {
         assert!(ConfigurationModel::<EmptyNode, _>::from_vec(degree_vec, rng).is_none());
     }
 
+    #[test]
+    fn clone_keeps_adjacency_order() {
+        let mut rng = Pcg64::seed_from_u64(812);
+        let er: ErEnsembleC<EmptyNode, _> = ErEnsembleC::new(50, 5.0, Pcg64::from_rng(&mut rng).unwrap());
+        let mut source = er.graph().clone();
+        source.shuffle_adjs(&mut rng);
+        let source = &source;
+
+        let cm = ConfigurationModel::clone_from_generic_graph(source, Pcg64::from_rng(&mut rng).unwrap());
+        assert_eq!(cm.graph().edge_count(), source.edge_count());
+        for (c1, c2) in source.container_iter().zip(cm.graph().container_iter()) {
+            assert!(c1.neighbors().eq(c2.neighbors()));
+        }
+
+        let indexed: ConfigurationModel<EmptyNode, _, IndexedNodeContainer<EmptyNode>>
+            = ConfigurationModel::clone_from_generic_graph_with_container(source, Pcg64::from_rng(&mut rng).unwrap());
+        for (c1, c2) in source.container_iter().zip(indexed.graph().container_iter()) {
+            assert!(c1.neighbors().eq(c2.neighbors()));
+        }
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn assert_degree_vec() {
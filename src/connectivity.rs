@@ -0,0 +1,542 @@
+//! # Incremental connectivity
+//! Keeps track of the connected components of a graph during a Markov chain,
+//! so that checking whether the graph is connected does not require
+//! a traversal of the whole graph after every step.
+//!
+//! * added edges merge components via union-find in amortized `O(α(n))`
+//! * for a removed edge, two breadth first searches starting at its endpoints are run in turns.
+//!   They stop as soon as they meet or as soon as one of them has visited its whole component,
+//!   which is then split off. The costs are therefore bounded by the smaller of the two parts
+//!   if the graph falls apart, and by the explored neighborhood of the endpoints otherwise
+//! * `is_connected` and `component_count` are `O(1)`
+//!
+//! # Example
+//! Markov chain of connected graphs, see also Example 5 in the crate documentation
+//! ```
+//! use net_ensembles::{EmptyNode, ErEnsembleM, traits::*};
+//! use net_ensembles::connectivity::ConnectivityTracker;
+//! use rand_pcg::Pcg64;
+//! use net_ensembles::rand::SeedableRng;
+//!
+//! let rng = Pcg64::seed_from_u64(8745);
+//! let mut e = ErEnsembleM::<EmptyNode, Pcg64>::new(30, 70, rng);
+//! while !e.graph().is_connected().unwrap() {
+//!     e.randomize();
+//! }
+//!
+//! // the tracker needs to know about every change of the topology,
+//! // so the markov steps are performed through it
+//! let mut tracker = ConnectivityTracker::new(e.graph());
+//! let mut steps = Vec::with_capacity(10);
+//! for _ in 0..100 {
+//!     tracker.m_steps(&mut e, 10, &mut steps);
+//!
+//!     // reject, if the resulting graph is not connected
+//!     if !tracker.is_connected().unwrap() {
+//!         tracker.undo_steps_quiet(&mut e, &steps);
+//!     }
+//!     assert_eq!(tracker.is_connected(), e.graph().is_connected());
+//! }
+//! ```
+use {
+    crate::{
+        traits::*,
+        GenericGraph,
+        union_find::UnionFind
+    },
+    std::mem
+};
+
+/// # Edges changed by a Markov step
+/// * implemented by the steps of the ensembles of this crate
+/// * needed to track the connectivity incrementally, see [`ConnectivityTracker`]
+pub trait EdgeChanges {
+    /// * pushes the edges added by the step to `added` and the edges removed by the step to `removed`
+    /// * pushes nothing, if the step did not change the graph
+    fn edge_changes(&self, added: &mut Vec<(usize, usize)>, removed: &mut Vec<(usize, usize)>);
+}
+
+/// # Incrementally maintained connected components
+/// * see [module documentation](crate::connectivity)
+/// * the tracker has to be informed about **every** change of the topology, either by performing
+///   the markov steps through it ([`m_step`](#method.m_step), [`undo_step`](#method.undo_step), ...),
+///   by [`track_step`](#method.track_step) and [`track_undo`](#method.track_undo)
+///   or by [`add_edge`](#method.add_edge) and [`remove_edge`](#method.remove_edge)
+/// * after `randomize`, or any other change, which is not tracked, call [`reset`](#method.reset)
+#[derive(Debug, Clone)]
+pub struct ConnectivityTracker {
+    union_find: UnionFind,
+    // vertex -> element of union_find. Split off vertices get new elements
+    element: Vec<usize>,
+    component_count: usize,
+    added: Vec<(usize, usize)>,
+    removed: Vec<(usize, usize)>,
+    mark: Vec<usize>,
+    epoch: usize,
+    search_u: Vec<usize>,
+    search_v: Vec<usize>,
+}
+
+type Edge = (usize, usize);
+
+enum Expansion {
+    Continue,
+    Met,
+    Exhausted,
+}
+
+impl ConnectivityTracker {
+    /// * create tracker for the current topology of `graph`
+    /// * `O(vertices + edges)`
+    pub fn new<T, A>(graph: &GenericGraph<T, A>) -> Self
+    where A: AdjContainer<T>
+    {
+        let mut tracker = Self{
+            union_find: UnionFind::new(0),
+            element: Vec::new(),
+            component_count: 0,
+            added: Vec::new(),
+            removed: Vec::new(),
+            mark: Vec::new(),
+            epoch: 0,
+            search_u: Vec::new(),
+            search_v: Vec::new(),
+        };
+        tracker.reset(graph);
+        tracker
+    }
+
+    /// * recalculates the components from scratch, e.g., after `randomize`
+    /// * `O(vertices + edges)`
+    pub fn reset<T, A>(&mut self, graph: &GenericGraph<T, A>)
+    where A: AdjContainer<T>
+    {
+        let n = graph.vertex_count();
+        self.union_find = UnionFind::new(n);
+        self.element.clear();
+        self.element.extend(0..n);
+        self.component_count = n;
+        self.mark.clear();
+        self.mark.resize(n, 0);
+        self.epoch = 0;
+        for container in graph.container_iter() {
+            let index = container.id();
+            for &neighbor in container.neighbors() {
+                if index < neighbor {
+                    self.add_edge(index, neighbor);
+                }
+            }
+        }
+    }
+
+    /// number of connected components
+    pub fn component_count(&self) -> usize
+    {
+        self.component_count
+    }
+
+    /// | result       |                          condition                       |
+    /// |--------------|----------------------------------------------------------|
+    /// | `None`       | **if** graph does not contain any vertices               |
+    /// | `Some(true)` | **else if** all vertices are connected by paths of edges |
+    /// | `Some(false)`| **otherwise**                                            |
+    pub fn is_connected(&self) -> Option<bool>
+    {
+        if self.element.is_empty() {
+            None
+        } else {
+            Some(self.component_count == 1)
+        }
+    }
+
+    /// `true` if vertices `index1` and `index2` are in the same connected component
+    pub fn same_component(&mut self, index1: usize, index2: usize) -> bool
+    {
+        self.union_find.find(self.element[index1]) == self.union_find.find(self.element[index2])
+    }
+
+    /// size of the connected component containing vertex `index`
+    pub fn component_size(&mut self, index: usize) -> usize
+    {
+        self.union_find.size(self.element[index])
+    }
+
+    /// # sizes of all *connected components*
+    /// * same result as [`GenericGraph::connected_components`]:
+    ///   sizes in descending order, the length of the vector is the number of components
+    /// * `O(vertices)` as the components are not stored explicitly
+    pub fn connected_components(&mut self) -> Vec<usize>
+    {
+        let mut seen = vec![false; self.union_find.len()];
+        let mut result = Vec::with_capacity(self.component_count);
+        for &element in self.element.iter() {
+            let root = self.union_find.find(element);
+            if !seen[root] {
+                seen[root] = true;
+                result.push(self.union_find.size(root));
+            }
+        }
+        result.sort_unstable_by(|a, b| b.cmp(a));
+        result
+    }
+
+    /// * call **after** the edge `(index1, index2)` was added to the graph
+    pub fn add_edge(&mut self, index1: usize, index2: usize)
+    {
+        let root1 = self.union_find.find(self.element[index1]);
+        let root2 = self.union_find.find(self.element[index2]);
+        if root1 != root2 {
+            self.union_find.union(root1, root2);
+            self.component_count -= 1;
+        }
+    }
+
+    /// * call **after** the edge `(index1, index2)` was removed from the graph
+    pub fn remove_edge<T, A>(&mut self, graph: &GenericGraph<T, A>, index1: usize, index2: usize)
+    where A: AdjContainer<T>
+    {
+        self.split(graph, index1, index2, &[]);
+    }
+
+    /// * call **after** `step` was performed, `graph` is the resulting graph
+    pub fn track_step<T, A, S>(&mut self, graph: &GenericGraph<T, A>, step: &S)
+    where A: AdjContainer<T>,
+          S: EdgeChanges
+    {
+        let (added, removed) = self.edge_changes(step);
+        self.apply(graph, &added, &removed);
+        self.added = added;
+        self.removed = removed;
+    }
+
+    /// * call **after** `step` was undone, `graph` is the resulting graph
+    pub fn track_undo<T, A, S>(&mut self, graph: &GenericGraph<T, A>, step: &S)
+    where A: AdjContainer<T>,
+          S: EdgeChanges
+    {
+        let (added, removed) = self.edge_changes(step);
+        // undoing adds the removed edges and removes the added edges
+        self.apply(graph, &removed, &added);
+        self.added = added;
+        self.removed = removed;
+    }
+
+    /// * performs markov step of `ensemble` and tracks it
+    pub fn m_step<E, S, Res, T, A>(&mut self, ensemble: &mut E) -> S
+    where E: MarkovChain<S, Res> + WithGraph<T, GenericGraph<T, A>>,
+          S: EdgeChanges,
+          A: AdjContainer<T>
+    {
+        let step = ensemble.m_step();
+        self.track_step(ensemble.graph(), &step);
+        step
+    }
+
+    /// * performs `count` markov steps of `ensemble` and tracks them
+    /// * `steps` is cleared and filled with the performed steps
+    pub fn m_steps<E, S, Res, T, A>(&mut self, ensemble: &mut E, count: usize, steps: &mut Vec<S>)
+    where E: MarkovChain<S, Res> + WithGraph<T, GenericGraph<T, A>>,
+          S: EdgeChanges,
+          A: AdjContainer<T>
+    {
+        steps.clear();
+        steps.extend(
+            (0..count).map(|_| self.m_step(ensemble))
+        );
+    }
+
+    /// * undoes markov step of `ensemble` and tracks it
+    /// * if undoing failed, i.e., the topology was not changed, nothing is tracked
+    pub fn undo_step<E, S, Res, T, A>(&mut self, ensemble: &mut E, step: &S) -> Res
+    where E: MarkovChain<S, Res> + WithGraph<T, GenericGraph<T, A>>,
+          S: EdgeChanges,
+          A: AdjContainer<T>
+    {
+        let result = ensemble.undo_step(step);
+        let (added, removed) = self.edge_changes(step);
+        let graph = ensemble.graph();
+        // a successful undo removed the first added edge or restored the first removed edge
+        let undone = match (added.first(), removed.first()) {
+            (Some(&(i, j)), _) => !graph.container(i).is_adjacent(j),
+            (None, Some(&(i, j))) => graph.container(i).is_adjacent(j),
+            (None, None) => false,
+        };
+        if undone {
+            self.apply(graph, &removed, &added);
+        }
+        self.added = added;
+        self.removed = removed;
+        result
+    }
+
+    /// * undoes markov step of `ensemble` and tracks it
+    /// * **panics** if undoing fails, see `MarkovChain::undo_step_quiet`
+    pub fn undo_step_quiet<E, S, Res, T, A>(&mut self, ensemble: &mut E, step: &S)
+    where E: MarkovChain<S, Res> + WithGraph<T, GenericGraph<T, A>>,
+          S: EdgeChanges,
+          A: AdjContainer<T>
+    {
+        ensemble.undo_step_quiet(step);
+        self.track_undo(ensemble.graph(), step);
+    }
+
+    /// * undoes markov steps of `ensemble` in reverse order and tracks them
+    /// * **panics** if undoing fails, see `MarkovChain::undo_step_quiet`
+    pub fn undo_steps_quiet<E, S, Res, T, A>(&mut self, ensemble: &mut E, steps: &[S])
+    where E: MarkovChain<S, Res> + WithGraph<T, GenericGraph<T, A>>,
+          S: EdgeChanges,
+          A: AdjContainer<T>
+    {
+        for step in steps.iter().rev() {
+            self.undo_step_quiet(ensemble, step);
+        }
+    }
+
+    /// takes the buffers and fills them with the changes of `step`
+    fn edge_changes<S: EdgeChanges>(&mut self, step: &S) -> (Vec<Edge>, Vec<Edge>)
+    {
+        let mut added = mem::take(&mut self.added);
+        let mut removed = mem::take(&mut self.removed);
+        added.clear();
+        removed.clear();
+        step.edge_changes(&mut added, &mut removed);
+        (added, removed)
+    }
+
+    /// * `graph` is the graph after all changes
+    /// * the changes are tracked as if all edges were added first and removed afterwards
+    pub(crate) fn apply<T, A>(&mut self, graph: &GenericGraph<T, A>, added: &[(usize, usize)], removed: &[(usize, usize)])
+    where A: AdjContainer<T>
+    {
+        for &(i, j) in added {
+            self.add_edge(i, j);
+        }
+        for (k, &(i, j)) in removed.iter().enumerate() {
+            // edges, which are removed later, still exist at this point
+            self.split(graph, i, j, &removed[k + 1..]);
+        }
+    }
+
+    /// * edge `(u, v)` was removed, `pending` are edges missing in `graph`, which still count as existing
+    /// * searches from `u` and `v` in turns, splits off the component of the search, which finishes first
+    fn split<T, A>(&mut self, graph: &GenericGraph<T, A>, u: usize, v: usize, pending: &[(usize, usize)])
+    where A: AdjContainer<T>
+    {
+        if !self.same_component(u, v) {
+            return;
+        }
+        self.epoch += 1;
+        let mark_u = 2 * self.epoch;
+        let mark_v = mark_u + 1;
+        let mut search_u = mem::take(&mut self.search_u);
+        let mut search_v = mem::take(&mut self.search_v);
+        search_u.clear();
+        search_v.clear();
+        search_u.push(u);
+        search_v.push(v);
+        self.mark[u] = mark_u;
+        self.mark[v] = mark_v;
+        let mut head_u = 0;
+        let mut head_v = 0;
+
+        let split_off = loop {
+            match expand(graph, pending, &mut self.mark, &mut search_u, &mut head_u, mark_u, mark_v) {
+                Expansion::Met => break None,
+                Expansion::Exhausted => break Some(&search_u),
+                Expansion::Continue => {}
+            }
+            match expand(graph, pending, &mut self.mark, &mut search_v, &mut head_v, mark_v, mark_u) {
+                Expansion::Met => break None,
+                Expansion::Exhausted => break Some(&search_v),
+                Expansion::Continue => {}
+            }
+        };
+        if let Some(part) = split_off {
+            self.split_off(part);
+        }
+        self.search_u = search_u;
+        self.search_v = search_v;
+    }
+
+    /// moves the vertices of `part` into a new component
+    fn split_off(&mut self, part: &[usize])
+    {
+        self.union_find.shrink(self.element[part[0]], part.len());
+        let first = self.union_find.push();
+        self.element[part[0]] = first;
+        for &vertex in &part[1..] {
+            let element = self.union_find.push();
+            self.union_find.union(first, element);
+            self.element[vertex] = element;
+        }
+        self.component_count += 1;
+
+        // elements of split off vertices are never used again
+        if self.union_find.len() > 3 * self.element.len() {
+            self.compact();
+        }
+    }
+
+    /// new union-find without unused elements
+    fn compact(&mut self)
+    {
+        let mut union_find = UnionFind::new(self.element.len());
+        let mut representative = vec![None; self.union_find.len()];
+        for vertex in 0..self.element.len() {
+            let root = self.union_find.find(self.element[vertex]);
+            match representative[root] {
+                None => representative[root] = Some(vertex),
+                Some(other) => {
+                    union_find.union(other, vertex);
+                },
+            }
+            self.element[vertex] = vertex;
+        }
+        self.union_find = union_find;
+    }
+}
+
+/// expands the next vertex of a breadth first search
+fn expand<T, A>(
+    graph: &GenericGraph<T, A>,
+    pending: &[(usize, usize)],
+    mark: &mut [usize],
+    search: &mut Vec<usize>,
+    head: &mut usize,
+    own: usize,
+    other: usize
+) -> Expansion
+where A: AdjContainer<T>
+{
+    let vertex = match search.get(*head) {
+        None => return Expansion::Exhausted,
+        Some(&vertex) => vertex,
+    };
+    *head += 1;
+    let pending_neighbors = pending.iter()
+        .filter_map(
+            |&(i, j)|
+            if i == vertex {
+                Some(j)
+            } else if j == vertex {
+                Some(i)
+            } else {
+                None
+            }
+        );
+    for neighbor in graph.container(vertex).neighbors().copied().chain(pending_neighbors) {
+        if mark[neighbor] == other {
+            return Expansion::Met;
+        }
+        if mark[neighbor] != own {
+            mark[neighbor] = own;
+            search.push(neighbor);
+        }
+    }
+    Expansion::Continue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyNode, ErEnsembleC, ErEnsembleM, test_chains::*};
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    fn assert_tracked<T, A>(tracker: &mut ConnectivityTracker, graph: &GenericGraph<T, A>)
+    where T: Node,
+          A: AdjContainer<T>
+    {
+        assert_eq!(tracker.is_connected(), graph.is_connected());
+        assert_eq!(tracker.connected_components(), graph.connected_components());
+        assert_eq!(tracker.component_count(), graph.connected_components().len());
+        let (_, ids) = graph.connected_components_ids();
+        for i in 0..graph.vertex_count() {
+            for j in 0..graph.vertex_count() {
+                assert_eq!(tracker.same_component(i, j), ids[i] == ids[j]);
+            }
+        }
+    }
+
+    impl TrackedObservable for ConnectivityTracker {
+        fn new_tracked<T, A>(graph: &GenericGraph<T, A>) -> Self
+        where A: AdjContainer<T>
+        {
+            Self::new(graph)
+        }
+
+        fn track<T, A, S>(&mut self, graph: &GenericGraph<T, A>, step: &S, undo: bool)
+        where A: AdjContainer<T>,
+              S: EdgeChanges
+        {
+            if undo {
+                self.track_undo(graph, step);
+            } else {
+                self.track_step(graph, step);
+            }
+        }
+
+        fn assert_tracked<T, A>(&mut self, graph: &GenericGraph<T, A>)
+        where T: Node,
+              A: AdjContainer<T>
+        {
+            assert_tracked(self, graph);
+        }
+    }
+
+    #[test]
+    fn tracks_markov_chains() {
+        check_chains::<ConnectivityTracker>(40, 1.5, 300, 1);
+    }
+
+    #[test]
+    fn ensemble_methods() {
+        let mut er_c = ErEnsembleC::<EmptyNode, Pcg64>::new(40, 1.2, Pcg64::seed_from_u64(1));
+        let mut tracker = ConnectivityTracker::new(er_c.graph());
+        let mut steps = Vec::new();
+        for round in 0..100 {
+            tracker.m_steps(&mut er_c, 1 + round % 7, &mut steps);
+            assert_tracked(&mut tracker, er_c.graph());
+            if round % 2 == 0 {
+                tracker.undo_steps_quiet(&mut er_c, &steps);
+            } else {
+                for step in steps.iter().rev() {
+                    tracker.undo_step(&mut er_c, step);
+                }
+            }
+            assert_tracked(&mut tracker, er_c.graph());
+        }
+    }
+
+    #[test]
+    fn compaction() {
+        // many splits and merges, so that the union-find is compacted several times
+        let mut er_m = ErEnsembleM::<EmptyNode, Pcg64>::new(10, 9, Pcg64::seed_from_u64(6));
+        let mut tracker = ConnectivityTracker::new(er_m.graph());
+        for _ in 0..2000 {
+            tracker.m_step(&mut er_m);
+        }
+        assert_tracked(&mut tracker, er_m.graph());
+        assert!(tracker.union_find.len() <= 3 * 10);
+    }
+
+    #[test]
+    fn manual_changes() {
+        let mut graph: crate::Graph<EmptyNode> = crate::Graph::new(6);
+        let mut tracker = ConnectivityTracker::new(&graph);
+        assert_eq!(tracker.component_count(), 6);
+        for i in 0..5 {
+            graph.add_edge(i, i + 1).unwrap();
+            tracker.add_edge(i, i + 1);
+        }
+        assert_eq!(tracker.is_connected(), Some(true));
+        graph.remove_edge(2, 3).unwrap();
+        tracker.remove_edge(&graph, 2, 3);
+        assert_eq!(tracker.connected_components(), vec![3, 3]);
+        assert_eq!(tracker.component_size(4), 3);
+        assert!(!tracker.same_component(0, 5));
+
+        let empty: crate::Graph<EmptyNode> = crate::Graph::new(0);
+        assert_eq!(ConnectivityTracker::new(&empty).is_connected(), None);
+    }
+}
@@ -0,0 +1,859 @@
+//! # Frozen graph in compressed sparse row (CSR) form
+//! * the adjacency lists of all vertices are stored in one contiguous vector,
+//!   which makes traversals cache friendly
+//! * the topology cannot be changed, but the additional data `T` can
+//! * build it from any [`GenericGraph`] in O(vertices + edges), e.g., from the graph of
+//!   an ensemble, to run expensive measurements like
+//!   [`vertex_load`](CsrGraph::vertex_load) or [`diameter`](CsrGraph::diameter)
+//! * the order of the adjacency lists is preserved, i.e., the iterators visit the
+//!   vertices in the same order as the iterators of the original graph
+//!
+//! # Example
+//! ```
+//! use net_ensembles::{ErEnsembleC, EmptyNode, WithGraph, CsrGraph, rand::SeedableRng};
+//! use rand_pcg::Pcg64;
+//!
+//! let rng = Pcg64::seed_from_u64(7567);
+//! let ensemble = ErEnsembleC::<EmptyNode, _>::new(200, 4.0, rng);
+//!
+//! let csr = CsrGraph::from(ensemble.graph());
+//! assert_eq!(csr.edge_count(), ensemble.graph().edge_count());
+//! assert_eq!(csr.vertex_load(true), ensemble.graph().vertex_load(true));
+//! assert_eq!(csr.connected_components(), ensemble.graph().connected_components());
+//!
+//! // and back
+//! let graph = csr.into_graph();
+//! assert_eq!(graph.edge_count(), ensemble.graph().edge_count());
+//! ```
+use {
+    crate::{
+        traits::*,
+        GenericGraph,
+        generic_graph::VertexLoadBuffers,
+        graph::{Graph, NodeContainer}
+    },
+    std::{
+        collections::{HashSet, VecDeque},
+        marker::PhantomData
+    }
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Serialize, Deserialize};
+
+/// # Read-only topology in compressed sparse row form
+/// * see [module documentation](crate::csr_graph)
+/// * deserializing checks, that the data is a valid undirected graph
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde_support", serde(try_from = "CsrGraphData<T>"))]
+pub struct CsrGraph<T> {
+    /// neighbors of vertex `i` are `targets[offsets[i]..offsets[i + 1]]`
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    nodes: Vec<T>,
+}
+
+/// unchecked fields of a deserialized [`CsrGraph`]
+#[cfg(feature = "serde_support")]
+#[derive(Deserialize)]
+struct CsrGraphData<T> {
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    nodes: Vec<T>,
+}
+
+#[cfg(feature = "serde_support")]
+impl<T> TryFrom<CsrGraphData<T>> for CsrGraph<T>
+{
+    type Error = String;
+
+    fn try_from(data: CsrGraphData<T>) -> Result<Self, Self::Error>
+    {
+        let CsrGraphData{offsets, targets, nodes} = data;
+        let n = nodes.len();
+        if offsets.len() != n + 1
+            || offsets[0] != 0
+            || offsets.windows(2).any(|w| w[0] > w[1])
+            || offsets[n] != targets.len()
+        {
+            return Err("invalid offsets".to_owned());
+        }
+        let adj = |i: usize| &targets[offsets[i]..offsets[i + 1]];
+        for i in 0..n {
+            if let Some(j) = adj(i).iter().find(|&&j| j >= n || j == i) {
+                return Err(format!("invalid neighbor {} of vertex {}", j, i));
+            }
+        }
+        crate::snapshot::check_adjacency(n, adj)?;
+        Ok(Self{offsets, targets, nodes})
+    }
+}
+
+impl<T, A> From<&GenericGraph<T, A>> for CsrGraph<T>
+where T: Clone,
+      A: AdjContainer<T>
+{
+    /// clones the additional data of the vertices
+    fn from(graph: &GenericGraph<T, A>) -> Self
+    {
+        let mut offsets = Vec::with_capacity(graph.vertex_count() + 1);
+        let mut targets = Vec::with_capacity(2 * graph.edge_count());
+        offsets.push(0);
+        for container in graph.container_iter() {
+            targets.extend(container.neighbors());
+            offsets.push(targets.len());
+        }
+        let nodes = graph.container_iter()
+            .map(|container| container.contained().clone())
+            .collect();
+        Self{
+            offsets,
+            targets,
+            nodes,
+        }
+    }
+}
+
+impl<T> From<CsrGraph<T>> for Graph<T>
+{
+    fn from(csr: CsrGraph<T>) -> Self
+    {
+        csr.into_graph()
+    }
+}
+
+impl<T> CsrGraph<T>
+{
+    /// # Convert back into a mutable graph
+    /// * the order of the adjacency lists is preserved
+    pub fn into_graph(self) -> Graph<T>
+    {
+        let edge_count = self.edge_count();
+        let vertices: Vec<_> = self.nodes
+            .into_iter()
+            .enumerate()
+            .map(
+                |(id, node)|
+                NodeContainer{
+                    id,
+                    adj: self.targets[self.offsets[id]..self.offsets[id + 1]].to_vec(),
+                    node
+                }
+            ).collect();
+        GenericGraph{
+            next_id: vertices.len(),
+            edge_count,
+            vertices,
+            phantom: PhantomData
+        }
+    }
+
+    /// returns number of vertices present in graph
+    pub fn vertex_count(&self) -> usize
+    {
+        self.nodes.len()
+    }
+
+    /// returns total number of edges in graph
+    pub fn edge_count(&self) -> usize
+    {
+        self.targets.len() / 2
+    }
+
+    /// calculates the average degree of the graph
+    /// * `(2 * edge_count) / vertex_count`
+    pub fn average_degree(&self) -> f32
+    {
+        self.targets.len() as f32 / self.vertex_count() as f32
+    }
+
+    /// * returns number of vertices adjacent to vertex `index`
+    /// * `None` if index out of bounds
+    pub fn degree(&self, index: usize) -> Option<usize>
+    {
+        if index < self.vertex_count() {
+            Some(self.offsets[index + 1] - self.offsets[index])
+        } else {
+            None
+        }
+    }
+
+    /// # Indices of the neighbors of vertex `index`
+    /// * in the order of the adjacency list
+    /// ## panics
+    /// * if `index` out of bounds
+    pub fn neighbors(&self, index: usize) -> &[usize]
+    {
+        &self.targets[self.offsets[index]..self.offsets[index + 1]]
+    }
+
+    /// `true` if vertices `index1` and `index2` are adjacent
+    /// ## panics
+    /// * if `index1` out of bounds
+    pub fn is_adjacent(&self, index1: usize, index2: usize) -> bool
+    {
+        self.neighbors(index1).contains(&index2)
+    }
+
+    /// Returns a reference to the element stored in the specified node
+    /// ## panics
+    /// * if `index` out of bounds
+    pub fn at(&self, index: usize) -> &T
+    {
+        &self.nodes[index]
+    }
+
+    /// Returns a mutable reference to the element stored in the specified node
+    /// ## panics
+    /// * if `index` out of bounds
+    pub fn at_mut(&mut self, index: usize) -> &mut T
+    {
+        &mut self.nodes[index]
+    }
+
+    /// Iterate over the additional data of all vertices
+    pub fn contained_iter(&self) -> std::slice::Iter<T>
+    {
+        self.nodes.iter()
+    }
+
+    /// Iterate over the degrees of each node (in the order of the indices)
+    pub fn degree_iter(&'_ self) -> impl Iterator<Item=usize> + '_
+    {
+        self.offsets
+            .windows(2)
+            .map(|window| window[1] - window[0])
+    }
+
+    /// # returns `Iterator`
+    ///
+    /// * the iterator will iterate over the vertices in depth first search order,
+    /// beginning with vertex `index`.
+    /// * iterator returns `node`
+    ///
+    /// Order
+    ///------------------------
+    /// Order is guaranteed to be in DFS order, however
+    /// if this order is not unambigouse
+    /// adding edges and especially removing edges will shuffle the order.
+    ///
+    /// Note:
+    /// ----------------------
+    /// Will only iterate over vertices within the connected component that contains vertex `index`
+    pub fn dfs(&self, index: usize) -> CsrDfs<T>
+    {
+        CsrDfs{
+            dfs: CsrDfsWithIndex::new(self, index)
+        }
+    }
+
+    /// # returns `Iterator`
+    ///
+    /// * the iterator will iterate over the vertices in depth first search order,
+    /// beginning with vertex `index`.
+    /// * Iterator returns tuple `(index, node)`
+    ///
+    /// Note:
+    /// ----------------------
+    /// Will only iterate over vertices within the connected component that contains vertex `index`
+    pub fn dfs_with_index(&self, index: usize) -> CsrDfsWithIndex<T>
+    {
+        CsrDfsWithIndex::new(self, index)
+    }
+
+    /// # returns `Iterator`
+    ///
+    /// * the iterator will iterate over the vertices in breadth first search order,
+    /// beginning with vertex `index`.
+    /// * Iterator returns tuple `(index, node, depth)`
+    ///
+    /// Note:
+    /// ----------------------
+    /// Will only iterate over vertices within the connected component that contains vertex `index`
+    pub fn bfs_index_depth(&self, index: usize) -> CsrBfs<T>
+    {
+        CsrBfs::new(self, index)
+    }
+
+    /// | result       |                          condition                       |
+    /// |--------------|----------------------------------------------------------|
+    /// | `None`       | **if** graph does not contain any vertices               |
+    /// | `Some(true)` | **else if** all vertices are connected by paths of edges |
+    /// | `Some(false)`| **otherwise**                                            |
+    pub fn is_connected(&self) -> Option<bool>
+    {
+        if self.vertex_count() == 0 {
+            None
+        } else {
+            Some(self.dfs(0).count() == self.vertex_count())
+        }
+    }
+
+    /// # definition
+    /// Calculates the size of the **q-core** (i.e. number of nodes in the biggest possible set of nodes,
+    /// where all nodes from the set are connected with at least `q` other nodes from the set)
+    ///
+    /// returns `None` if impossible to calculate (e.g. `vertex_count == 0` or `q <= 1`)
+    pub fn q_core(&self, q: usize) -> Option<usize>
+    {
+        if q < 2 || self.vertex_count() == 0 {
+            return None;
+        }
+
+        let mut degree: Vec<_> = self.degree_iter().collect();
+
+        // virtually: recursively remove all vertices with less then q neighbors
+        let mut something_changed = true;
+        while something_changed {
+            something_changed = false;
+            for i in 0..self.vertex_count() {
+                if degree[i] == 0 || degree[i] >= q {
+                    continue;
+                }
+                for &n in self.neighbors(i) {
+                    if degree[n] > 0 {
+                        degree[n] -= 1;
+                    }
+                }
+                degree[i] = 0;
+                something_changed = true;
+            }
+        }
+
+        // find biggest component
+        let mut result = 0;
+        let mut stack: Vec<usize> = Vec::with_capacity(self.vertex_count());
+        for i in 0..self.vertex_count() {
+            // skip all nodes that are removed or in a known component
+            if degree[i] == 0 {
+                continue;
+            }
+            let mut counter = 0;
+            stack.push(i);
+            degree[i] = 0;
+
+            while let Some(index) = stack.pop() {
+                counter += 1;
+                for &j in self.neighbors(index) {
+                    if degree[j] != 0 {
+                        degree[j] = 0;
+                        stack.push(j);
+                    }
+                }
+            }
+            result = result.max(counter);
+        }
+        Some(result)
+    }
+
+    /// # compute connected component ids
+    /// * each vertex gets an id, all vertices with the same id are in the same connected component
+    /// * returns (number of components, vector of ids)
+    pub fn connected_components_ids(&self) -> (usize, Vec<isize>)
+    {
+        let mut component_id: Vec<isize> = vec![-1; self.vertex_count()];
+        let mut current_id = 0;
+
+        for i in 0..self.vertex_count() {
+            // already in a component?
+            if component_id[i] != -1 {
+                continue;
+            }
+            for (j, _) in self.dfs_with_index(i) {
+                component_id[j] = current_id;
+            }
+            current_id += 1;
+        }
+        (current_id as usize, component_id)
+    }
+
+    /// # compute sizes of all *connected components*
+    ///
+    /// * the **number** of connected components is the **size** of the returned vector, i.e. `result.len()`
+    /// * returns **empty** vector, if graph does not contain vertices
+    /// * returns (reverse) **ordered vector of sizes** of the connected components,
+    /// i.e. the biggest component is of size `result[0]` and the smallest is of size `result[result.len() - 1]`
+    pub fn connected_components(&self) -> Vec<usize>
+    {
+        let (num_components, component_id) = self.connected_components_ids();
+
+        let mut result = vec![0; num_components];
+        for id in component_id {
+            result[id as usize] += 1;
+        }
+        result.sort_unstable_by(|a, b| b.cmp(a));
+        result
+    }
+
+    /// Count number of leaves in the graph, i.e. vertices with exactly one neighbor
+    pub fn leaf_count(&self) -> usize
+    {
+        self.degree_iter()
+            .filter(|&degree| degree == 1)
+            .count()
+    }
+
+    /// * returns `None` **if** graph not connected **or** does not contain any vertices
+    /// * uses repeated breadth first search
+    pub fn diameter(&self) -> Option<usize>
+    {
+        if !self.is_connected()? {
+            return None;
+        }
+        let mut max = 0;
+        let mut bfs = self.bfs_index_depth(0);
+        for index in 1..self.vertex_count() {
+            bfs.reuse(index);
+            let depth = (&mut bfs).last()
+                .map_or(0, |(.., depth)| depth);
+            max = max.max(depth);
+        }
+        Some(max)
+    }
+
+    /// calculate the size of the longest shortest path **starting from** vertex with **index** `index`
+    /// using breadth first search
+    pub fn longest_shortest_path_from_index(&self, index: usize) -> Option<usize>
+    {
+        let (.., depth) = self.bfs_index_depth(index)
+            .last()?;
+        Some(depth)
+    }
+
+    /// # Calculates transitivity of graph
+    /// * see [`GenericGraph::transitivity`]
+    /// * returns `NaN`, if there are no paths of length two in the graph
+    pub fn transitivity(&self) -> f64
+    {
+        let mut path_count: usize = 0;
+        let mut closed_path_count: usize = 0;
+        // marks the neighbors of the current source vertex
+        let mut is_neighbor = vec![false; self.vertex_count()];
+        for source in 0..self.vertex_count() {
+            let neighbors = self.neighbors(source);
+            for &neighbor in neighbors {
+                is_neighbor[neighbor] = true;
+            }
+            for &neighbor_1 in neighbors {
+                for &neighbor_2 in self.neighbors(neighbor_1) {
+                    // do not use edge we came from
+                    if neighbor_2 == source {
+                        continue;
+                    }
+                    if is_neighbor[neighbor_2] {
+                        closed_path_count += 1;
+                    }
+                    path_count += 1;
+                }
+            }
+            for &neighbor in neighbors {
+                is_neighbor[neighbor] = false;
+            }
+        }
+        closed_path_count as f64 / path_count as f64
+    }
+
+    /// # calculate sizes of all binode connected components
+    /// * see [`GenericGraph::vertex_biconnected_components`] for the definitions
+    /// * in contrast to the `GenericGraph`, the topology is not destroyed, i.e.,
+    ///   `self` is not moved
+    /// * returns (reverse) **ordered vector of sizes**
+    pub fn vertex_biconnected_components(&self, alternative_definition: bool) -> Vec<usize>
+    {
+        let n = self.vertex_count();
+        let mut low: Vec<usize> = vec![0; n];
+        let mut number: Vec<usize> = vec![0; n];
+        let mut handled: Vec<bool> = vec![false; n];
+        // instead of removing edges: mark used half edges and remember,
+        // where to continue in the adjacency list of each vertex
+        let mut used: Vec<bool> = vec![false; self.targets.len()];
+        let mut cursor: Vec<usize> = self.offsets[..n].to_vec();
+        let mut edge_stack: Vec<(usize, usize)> = Vec::with_capacity(n);
+        let mut vertex_stack: Vec<usize> = Vec::with_capacity(n);
+        let mut biconnected_components: Vec<Vec<(usize, usize)>> = Vec::new();
+
+        for pivot in 0..n {
+            if handled[pivot] {
+                continue;
+            }
+            handled[pivot] = true;
+            vertex_stack.push(pivot);
+
+            while let Some(&top_vertex) = vertex_stack.last() {
+                let end = self.offsets[top_vertex + 1];
+                while cursor[top_vertex] < end && used[cursor[top_vertex]] {
+                    cursor[top_vertex] += 1;
+                }
+                if cursor[top_vertex] < end {
+                    // remove edge from graph, put it on stack
+                    let position = cursor[top_vertex];
+                    let next_vertex = self.targets[position];
+                    used[position] = true;
+                    let reverse = (self.offsets[next_vertex]..self.offsets[next_vertex + 1])
+                        .find(|&i| self.targets[i] == top_vertex && !used[i])
+                        .expect("CsrGraph: adjacency lists are not symmetric");
+                    used[reverse] = true;
+                    edge_stack.push((top_vertex, next_vertex));
+
+                    if !handled[next_vertex] {
+                        number[next_vertex] = vertex_stack.len();
+                        vertex_stack.push(next_vertex);
+                        low[next_vertex] = number[top_vertex];
+                        handled[next_vertex] = true;
+                    } else if number[next_vertex] < low[top_vertex] {
+                        low[top_vertex] = number[next_vertex];
+                    }
+                } else {
+                    vertex_stack.pop();
+                    let next_vertex = match vertex_stack.last() {
+                        Some(&next_vertex) => next_vertex,
+                        None => break
+                    };
+                    if low[top_vertex] == number[next_vertex] {
+                        // component consists of the edges up to and including
+                        // the tree edge leading to top_vertex
+                        let mut component: Vec<(usize, usize)> = Vec::new();
+                        while let Some(current_edge) = edge_stack.pop() {
+                            component.push(current_edge);
+                            if current_edge == (next_vertex, top_vertex) {
+                                break;
+                            }
+                        }
+                        biconnected_components.push(component);
+                    } else if low[top_vertex] < low[next_vertex] {
+                        low[next_vertex] = low[top_vertex];
+                    }
+                }
+            }
+        }
+
+        let mut result: Vec<usize> = biconnected_components.into_iter()
+            .map(
+                |component|
+                component.into_iter()
+                    .flat_map(|(i, j)| [i, j])
+                    .collect::<HashSet<_>>()
+                    .len()
+            ).collect();
+
+        if alternative_definition {
+            result.retain(|&val| val > 2);
+        }
+        result.sort_unstable_by(|a, b| b.cmp(a));
+        result
+    }
+
+    /// # Closely related (most of the time equal) to betweeness
+    /// * calculates vertex_load of all vertices in O(edges * vertices)
+    /// * see [`GenericGraph::vertex_load`] for details and citations
+    pub fn vertex_load(&self, include_endpoints: bool) -> Vec<f64>
+    {
+        let n = self.vertex_count();
+        let mut buffers = VertexLoadBuffers::new(n);
+        let mut b = vec![0.0; n];
+        for i in 0..n {
+            buffers.add_load_from(|index| self.neighbors(index), i, include_endpoints, &mut b);
+        }
+        b
+    }
+}
+
+impl<T> MeasurableGraphQuantities<CsrGraph<T>> for CsrGraph<T>
+{
+    fn average_degree(&self) -> f32 {
+        self.average_degree()
+    }
+
+    fn degree(&self, index: usize) -> Option<usize> {
+        self.degree(index)
+    }
+
+    fn connected_components(&self) -> Vec<usize> {
+        self.connected_components()
+    }
+
+    fn diameter(&self) -> Option<usize> {
+        self.diameter()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.edge_count()
+    }
+
+    fn is_connected(&self) -> Option<bool> {
+        self.is_connected()
+    }
+
+    fn leaf_count(&self) -> usize {
+        self.leaf_count()
+    }
+
+    fn longest_shortest_path_from_index(&self, index: usize) -> Option<usize> {
+        self.longest_shortest_path_from_index(index)
+    }
+
+    fn q_core(&self, q: usize) -> Option<usize> {
+        self.q_core(q)
+    }
+
+    fn transitivity(&self) -> f64 {
+        self.transitivity()
+    }
+
+    fn vertex_biconnected_components(&self, alternative_definition: bool) -> Vec<usize> {
+        self.vertex_biconnected_components(alternative_definition)
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.vertex_count()
+    }
+
+    fn vertex_load(&self, include_endpoints: bool) -> Vec<f64> {
+        self.vertex_load(include_endpoints)
+    }
+}
+
+/// Depth first search Iterator with **index** of corresponding nodes
+pub struct CsrDfsWithIndex<'a, T> {
+    graph: &'a CsrGraph<T>,
+    handled: Vec<bool>,
+    stack: Vec<usize>,
+}
+
+impl<'a, T> CsrDfsWithIndex<'a, T> {
+    fn new(graph: &'a CsrGraph<T>, index: usize) -> Self
+    {
+        let mut handled = vec![false; graph.vertex_count()];
+        let mut stack = Vec::with_capacity(graph.vertex_count());
+        if index < handled.len() {
+            stack.push(index);
+            handled[index] = true;
+        }
+        Self{
+            graph,
+            handled,
+            stack,
+        }
+    }
+}
+
+impl<'a, T> Iterator for CsrDfsWithIndex<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.stack.pop()?;
+        for &i in self.graph.neighbors(index) {
+            if !self.handled[i] {
+                self.handled[i] = true;
+                self.stack.push(i);
+            }
+        }
+        Some((index, self.graph.at(index)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.stack.len(), Some(self.handled.len()))
+    }
+}
+
+/// Depth first search Iterator
+pub struct CsrDfs<'a, T> {
+    dfs: CsrDfsWithIndex<'a, T>,
+}
+
+impl<'a, T> Iterator for CsrDfs<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.dfs
+            .next()
+            .map(|(_, node)| node)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.dfs.size_hint()
+    }
+}
+
+/// # Breadth first search Iterator with **index** and **depth** of corresponding nodes
+/// * iterator returns tuple: `(index, node, depth)`
+pub struct CsrBfs<'a, T> {
+    graph: &'a CsrGraph<T>,
+    handled: Vec<bool>,
+    queue0: VecDeque<usize>,
+    queue1: VecDeque<usize>,
+    depth: usize,
+}
+
+impl<'a, T> CsrBfs<'a, T> {
+    fn new(graph: &'a CsrGraph<T>, index: usize) -> Self
+    {
+        let mut bfs = Self{
+            graph,
+            handled: vec![false; graph.vertex_count()],
+            queue0: VecDeque::with_capacity(graph.vertex_count() / 2),
+            queue1: VecDeque::with_capacity(graph.vertex_count() / 2),
+            depth: 0,
+        };
+        bfs.reuse(index);
+        bfs
+    }
+
+    fn reuse(&mut self, index: usize)
+    {
+        self.handled.fill(false);
+        self.queue0.clear();
+        self.queue1.clear();
+        self.depth = 0;
+        if index < self.handled.len() {
+            self.queue0.push_back(index);
+            self.handled[index] = true;
+        }
+    }
+}
+
+impl<'a, T> Iterator for CsrBfs<'a, T> {
+    type Item = (usize, &'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(index) = self.queue0.pop_front() {
+                for &i in self.graph.neighbors(index) {
+                    if !self.handled[i] {
+                        self.handled[i] = true;
+                        self.queue1.push_back(i);
+                    }
+                }
+                return Some((index, self.graph.at(index), self.depth));
+            } else if self.queue1.is_empty() {
+                return None;
+            }
+            std::mem::swap(&mut self.queue0, &mut self.queue1);
+            self.depth += 1;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.queue0.len() + self.queue1.len(), Some(self.handled.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyNode, CountingNode, ErEnsembleC, SwEnsemble, WithGraph, rand::SeedableRng};
+    use rand_pcg::Pcg64;
+
+    fn compare<T, A>(graph: &GenericGraph<T, A>)
+    where T: Node,
+          A: AdjContainer<T>,
+          GenericGraph<T, A>: Clone
+    {
+        let csr = CsrGraph::from(graph);
+        assert_eq!(csr.vertex_count(), graph.vertex_count());
+        assert_eq!(csr.edge_count(), graph.edge_count());
+        if graph.vertex_count() > 0 {
+            assert_eq!(csr.average_degree(), graph.average_degree());
+        }
+        assert_eq!(csr.connected_components(), graph.connected_components());
+        assert_eq!(csr.is_connected(), graph.is_connected());
+        assert_eq!(csr.diameter(), graph.diameter());
+        assert_eq!(csr.leaf_count(), graph.leaf_count());
+        for q in 0..5 {
+            assert_eq!(csr.q_core(q), graph.q_core(q));
+        }
+        assert_eq!(csr.vertex_load(true), graph.vertex_load(true));
+        assert_eq!(csr.vertex_load(false), graph.vertex_load(false));
+        let transitivity = graph.transitivity();
+        assert!(transitivity.is_nan() && csr.transitivity().is_nan() || transitivity == csr.transitivity());
+        for alternative in [false, true] {
+            assert_eq!(
+                csr.vertex_biconnected_components(alternative),
+                graph.clone().vertex_biconnected_components(alternative)
+            );
+        }
+        for index in 0..graph.vertex_count() {
+            assert_eq!(csr.degree(index), graph.degree(index));
+            let neighbors: Vec<_> = graph.container(index).neighbors().copied().collect();
+            assert_eq!(csr.neighbors(index), neighbors.as_slice());
+            assert_eq!(csr.longest_shortest_path_from_index(index), graph.longest_shortest_path_from_index(index));
+            let bfs: Vec<_> = csr.bfs_index_depth(index)
+                .map(|(i, _, depth)| (i, depth))
+                .collect();
+            let expected: Vec<_> = graph.bfs_index_depth(index)
+                .map(|(i, _, depth)| (i, depth))
+                .collect();
+            assert_eq!(bfs, expected);
+            let dfs: Vec<_> = csr.dfs_with_index(index)
+                .map(|(i, _)| i)
+                .collect();
+            let expected: Vec<_> = graph.dfs_with_index(index)
+                .map(|(i, _)| i)
+                .collect();
+            assert_eq!(dfs, expected);
+        }
+        assert_eq!(csr.degree(graph.vertex_count()), None);
+    }
+
+    #[test]
+    fn same_measurements()
+    {
+        let mut rng = Pcg64::seed_from_u64(92374);
+        for c in [0.5, 1.5, 4.0] {
+            let ensemble = ErEnsembleC::<EmptyNode, _>::new(80, c, Pcg64::from_rng(&mut rng).unwrap());
+            compare(ensemble.graph());
+        }
+        let sw = SwEnsemble::<EmptyNode, _>::new(60, 0.1, rng);
+        compare(sw.graph());
+        compare(&Graph::<EmptyNode>::new(0));
+        compare(&Graph::<EmptyNode>::complete_graph(7));
+    }
+
+    #[test]
+    fn round_trip()
+    {
+        let rng = Pcg64::seed_from_u64(2341);
+        let ensemble = ErEnsembleC::<CountingNode, _>::new(50, 3.0, rng);
+        let mut csr = CsrGraph::from(ensemble.graph());
+        csr.at_mut(3).index = 100;
+        let graph = Graph::from(csr);
+        assert_eq!(graph.edge_count(), ensemble.graph().edge_count());
+        for (index, container) in graph.container_iter().enumerate() {
+            let expected = ensemble.graph().container(index);
+            assert_eq!(container.id(), index);
+            assert!(container.neighbors().eq(expected.neighbors()));
+            let expected_index = if index == 3 {100} else {index};
+            assert_eq!(container.contained().index, expected_index);
+        }
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn deserialize_checks()
+    {
+        let mut graph = Graph::<EmptyNode>::new(4);
+        graph.add_edge(0, 1).unwrap();
+        graph.add_edge(1, 2).unwrap();
+        graph.add_edge(3, 1).unwrap();
+        let csr = CsrGraph::from(&graph);
+        let value = serde_json::to_value(&csr).unwrap();
+        let loaded: CsrGraph<EmptyNode> = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(loaded.neighbors(1), csr.neighbors(1));
+
+        let load = |field: &str, data: serde_json::Value| {
+            let mut value = value.clone();
+            value[field] = data;
+            serde_json::from_value::<CsrGraph<EmptyNode>>(value)
+        };
+        // offsets
+        assert!(load("offsets", serde_json::json!([0, 1, 4, 5])).is_err());
+        assert!(load("offsets", serde_json::json!([0, 1, 4, 5, 6, 6])).is_err());
+        // neighbor out of range, self loop, asymmetric, duplicate
+        assert!(load("targets", serde_json::json!([1, 0, 2, 4, 1, 1])).is_err());
+        assert!(load("targets", serde_json::json!([1, 0, 2, 3, 2, 1])).is_err());
+        assert!(load("targets", serde_json::json!([1, 0, 2, 3, 1, 0])).is_err());
+        assert!(load("targets", serde_json::json!([1, 0, 0, 3, 1, 1])).is_err());
+        assert!(load("targets", serde_json::json!([1, 0, 2, 3, 1, 1])).is_ok());
+    }
+}
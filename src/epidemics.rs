@@ -0,0 +1,604 @@
+//! # Epidemic spreading on networks
+//! Simulate SIR and SIS processes on the topology of any graph or ensemble.
+//!
+//! * vertices are either **S**usceptible, **I**nfected or **R**ecovered, see [`InfectionState`]
+//! * an infected vertex infects each of its susceptible neighbors with the infection rate `lambda`
+//! * an infected vertex recovers with the recovery rate `mu`.
+//!   In the SIR model it becomes `Recovered` and immune, in the SIS model it becomes
+//!   `Susceptible` again
+//!
+//! The state of the epidemic is stored in the result, not in the graph, therefore
+//! the node data `T` is never touched.
+//!
+//! Two kinds of dynamics are implemented:
+//! * [`Epidemic::discrete`]: synchronous updates in discrete time. `lambda` and `mu` are
+//!   interpreted as probabilities per time step
+//! * [`Epidemic::gillespie`]: exact continuous-time simulation
+//!
+//! # Large deviations
+//! To use, e.g., the outbreak size as observable in a Markov chain, it has to be a
+//! function of the graph alone. Use a new random number generator with fixed seed for each
+//! evaluation, or make the random numbers part of the Markov chain.
+//! # Example
+//! ```
+//! use net_ensembles::{ErEnsembleC, EmptyNode, Graph, rand::SeedableRng};
+//! use net_ensembles::traits::WithGraph;
+//! use net_ensembles::epidemics::Epidemic;
+//! use rand_pcg::Pcg64;
+//!
+//! let rng = Pcg64::seed_from_u64(8232);
+//! let ensemble = ErEnsembleC::<EmptyNode, _>::new(200, 4.0, rng);
+//!
+//! let sir = Epidemic::sir(0.6, 1.0);
+//! let energy = |graph: &Graph<EmptyNode>| {
+//!     let mut rng = Pcg64::seed_from_u64(1239);
+//!     sir.gillespie(graph, &[0], f64::INFINITY, &mut rng)
+//!         .outbreak_size()
+//! };
+//! let size = energy(ensemble.graph());
+//! // same graph, same outbreak
+//! assert_eq!(size, energy(ensemble.graph()));
+//! ```
+//! # Citations
+//! The Gillespie algorithm uses phantom processes, as described in
+//! > W. Cota and S. C. Ferreira,
+//! > "Optimized Gillespie algorithms for the simulation of Markovian epidemic processes on large and heterogeneous networks"
+//! > *Comput. Phys. Commun.*&nbsp;**219**, 303-312&nbsp;(2017),
+//! > DOI: [10.1016/j.cpc.2017.06.007](https://doi.org/10.1016/j.cpc.2017.06.007)
+use {
+    crate::{
+        traits::*,
+        GenericGraph
+    },
+    rand::{Rng, SeedableRng},
+    rand_distr::Exp1
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Serialize, Deserialize};
+
+/// # State of a vertex
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum InfectionState {
+    /// can be infected
+    Susceptible,
+    /// currently infected
+    Infected,
+    /// immune, only used by the SIR model
+    Recovered,
+}
+
+/// # Which epidemic model to simulate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum EpidemicModel {
+    /// Susceptible - Infected - Recovered
+    Sir,
+    /// Susceptible - Infected - Susceptible
+    Sis,
+}
+
+/// # Parameters of an epidemic
+/// * use [`Epidemic::sir`] or [`Epidemic::sis`] to create
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Epidemic {
+    model: EpidemicModel,
+    infection_rate: f64,
+    recovery_rate: f64,
+}
+
+/// # Time series and final state of a simulated epidemic
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct EpidemicResult {
+    time: Vec<f64>,
+    susceptible: Vec<usize>,
+    infected: Vec<usize>,
+    recovered: Vec<usize>,
+    states: Vec<InfectionState>,
+    ever_infected: Vec<bool>,
+}
+
+impl EpidemicResult {
+    fn new(states: Vec<InfectionState>) -> Self
+    {
+        let ever_infected = states.iter()
+            .map(|&s| s == InfectionState::Infected)
+            .collect();
+        let mut res = Self{
+            time: Vec::new(),
+            susceptible: Vec::new(),
+            infected: Vec::new(),
+            recovered: Vec::new(),
+            states,
+            ever_infected,
+        };
+        let mut counts = [0; 3];
+        for &state in res.states.iter() {
+            counts[state as usize] += 1;
+        }
+        res.time.push(0.0);
+        res.susceptible.push(counts[0]);
+        res.infected.push(counts[1]);
+        res.recovered.push(counts[2]);
+        res
+    }
+
+    fn record(&mut self, time: f64)
+    {
+        self.time.push(time);
+        let last = self.time.len() - 2;
+        self.susceptible.push(self.susceptible[last]);
+        self.infected.push(self.infected[last]);
+        self.recovered.push(self.recovered[last]);
+    }
+
+    /// change state of `index` and update the last entry of the time series
+    fn set(&mut self, index: usize, state: InfectionState)
+    {
+        let last = self.time.len() - 1;
+        let counter = |s| match s {
+            InfectionState::Susceptible => 0,
+            InfectionState::Infected => 1,
+            InfectionState::Recovered => 2,
+        };
+        let series = [&mut self.susceptible, &mut self.infected, &mut self.recovered];
+        series[counter(self.states[index])][last] -= 1;
+        series[counter(state)][last] += 1;
+        if state == InfectionState::Infected {
+            self.ever_infected[index] = true;
+        }
+        self.states[index] = state;
+    }
+
+    /// # Times at which the time series was recorded
+    /// * starts with `0.0`
+    /// * discrete dynamics: `0.0, 1.0, 2.0, ...`
+    /// * Gillespie: time after each event that changed a state
+    pub fn time(&self) -> &[f64]
+    {
+        &self.time
+    }
+
+    /// # Number of susceptible vertices at each `self.time()`
+    pub fn susceptible(&self) -> &[usize]
+    {
+        &self.susceptible
+    }
+
+    /// # Number of infected vertices at each `self.time()`
+    pub fn infected(&self) -> &[usize]
+    {
+        &self.infected
+    }
+
+    /// # Number of recovered vertices at each `self.time()`
+    /// * always `0` for the SIS model
+    pub fn recovered(&self) -> &[usize]
+    {
+        &self.recovered
+    }
+
+    /// # State of each vertex at the end of the simulation
+    pub fn final_states(&self) -> &[InfectionState]
+    {
+        &self.states
+    }
+
+    /// # Time at which the simulation stopped
+    pub fn duration(&self) -> f64
+    {
+        *self.time.last().unwrap()
+    }
+
+    /// # `true` if no vertex is infected at the end of the simulation
+    pub fn is_extinct(&self) -> bool
+    {
+        *self.infected.last().unwrap() == 0
+    }
+
+    /// # Number of vertices that were infected at some point
+    /// * including the initially infected vertices
+    /// * for the SIR model this is the number of recovered vertices,
+    ///   once the epidemic went extinct
+    pub fn outbreak_size(&self) -> usize
+    {
+        self.ever_infected
+            .iter()
+            .filter(|&&infected| infected)
+            .count()
+    }
+
+    /// # Fraction of vertices that were infected at some point
+    pub fn outbreak_fraction(&self) -> f64
+    {
+        self.outbreak_size() as f64 / self.states.len() as f64
+    }
+}
+
+impl Epidemic {
+    /// # Create SIR model
+    /// * `infection_rate`: rate with which an infected vertex infects each susceptible neighbor
+    /// * `recovery_rate`: rate with which an infected vertex recovers
+    pub fn sir(infection_rate: f64, recovery_rate: f64) -> Self
+    {
+        Self{
+            model: EpidemicModel::Sir,
+            infection_rate,
+            recovery_rate,
+        }
+    }
+
+    /// # Create SIS model
+    /// * `infection_rate`: rate with which an infected vertex infects each susceptible neighbor
+    /// * `recovery_rate`: rate with which an infected vertex becomes susceptible again
+    pub fn sis(infection_rate: f64, recovery_rate: f64) -> Self
+    {
+        Self{
+            model: EpidemicModel::Sis,
+            infection_rate,
+            recovery_rate,
+        }
+    }
+
+    /// # Which model is simulated
+    pub fn model(&self) -> EpidemicModel
+    {
+        self.model
+    }
+
+    /// # Infection rate
+    pub fn infection_rate(&self) -> f64
+    {
+        self.infection_rate
+    }
+
+    /// # Recovery rate
+    pub fn recovery_rate(&self) -> f64
+    {
+        self.recovery_rate
+    }
+
+    fn recovered_state(&self) -> InfectionState
+    {
+        match self.model {
+            EpidemicModel::Sir => InfectionState::Recovered,
+            EpidemicModel::Sis => InfectionState::Susceptible,
+        }
+    }
+
+    fn initial_states(n: usize, initial_infected: &[usize]) -> Vec<InfectionState>
+    {
+        let mut states = vec![InfectionState::Susceptible; n];
+        for &index in initial_infected {
+            states[index] = InfectionState::Infected;
+        }
+        states
+    }
+
+    /// # Synchronous updates in discrete time
+    /// * in each time step, every infected vertex infects each susceptible neighbor with
+    ///   probability `infection_rate` and recovers with probability `recovery_rate`
+    /// * all vertices are updated simultaneously, i.e., a vertex infected in a time step
+    ///   can recover at the earliest in the next time step
+    /// * stops, when no infected vertices are left or after `max_steps` time steps
+    /// * **panics** if any index of `initial_infected` is out of bounds, or if the rates
+    ///   are not within `[0, 1]`
+    pub fn discrete<T, A, R>(
+        &self,
+        graph: &GenericGraph<T, A>,
+        initial_infected: &[usize],
+        max_steps: usize,
+        rng: &mut R
+    ) -> EpidemicResult
+    where T: Node,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        assert!(
+            (0.0..=1.0).contains(&self.infection_rate) && (0.0..=1.0).contains(&self.recovery_rate),
+            "discrete dynamics: rates have to be probabilities"
+        );
+        let mut result = EpidemicResult::new(
+            Self::initial_states(graph.vertex_count(), initial_infected)
+        );
+        let recovered = self.recovered_state();
+        let mut infected: Vec<_> = (0..graph.vertex_count())
+            .filter(|&i| result.states[i] == InfectionState::Infected)
+            .collect();
+        let mut newly_infected = Vec::new();
+        let mut still_infected = Vec::new();
+
+        for step in 1..=max_steps {
+            if infected.is_empty() {
+                break;
+            }
+            result.record(step as f64);
+
+            // states of the last time step are used for all infections
+            for &index in infected.iter() {
+                for &neighbor in graph.container(index).neighbors() {
+                    if result.states[neighbor] == InfectionState::Susceptible
+                        && rng.gen::<f64>() < self.infection_rate
+                    {
+                        result.set(neighbor, InfectionState::Infected);
+                        newly_infected.push(neighbor);
+                    }
+                }
+            }
+
+            still_infected.clear();
+            for &index in infected.iter() {
+                if rng.gen::<f64>() < self.recovery_rate {
+                    result.set(index, recovered);
+                } else {
+                    still_infected.push(index);
+                }
+            }
+            std::mem::swap(&mut infected, &mut still_infected);
+            infected.append(&mut newly_infected);
+        }
+        result
+    }
+
+    /// # Exact continuous-time simulation
+    /// * Gillespie algorithm with phantom processes
+    /// * the time series is recorded after each event that changed the state of a vertex
+    /// * stops, when no infected vertices are left, when no further event can change
+    ///   the state of a vertex, e.g., for SI dynamics, or when the time exceeds `max_time`.
+    ///   Use `f64::INFINITY` for SIR, but note that SIS may survive for a very long time
+    /// * **panics** if any index of `initial_infected` is out of bounds
+    pub fn gillespie<T, A, R>(
+        &self,
+        graph: &GenericGraph<T, A>,
+        initial_infected: &[usize],
+        max_time: f64,
+        rng: &mut R
+    ) -> EpidemicResult
+    where T: Node,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        let mut result = EpidemicResult::new(
+            Self::initial_states(graph.vertex_count(), initial_infected)
+        );
+        let recovered = self.recovered_state();
+
+        // infected vertices and their position in `infected`
+        let mut infected: Vec<usize> = Vec::new();
+        let mut position = vec![usize::MAX; graph.vertex_count()];
+        let mut degree_sum = 0;
+        for (index, &state) in result.states.iter().enumerate() {
+            if state == InfectionState::Infected {
+                position[index] = infected.len();
+                infected.push(index);
+                degree_sum += graph.degree(index).unwrap();
+            }
+        }
+        let max_degree = graph.degree_iter()
+            .max()
+            .unwrap_or(0);
+        // number of edges between susceptible and infected vertices.
+        // Without recovery, only these edges can change the state
+        let mut si_edges: usize = infected.iter()
+            .map(
+                |&index| graph.container(index)
+                    .neighbors()
+                    .filter(|&&neighbor| result.states[neighbor] == InfectionState::Susceptible)
+                    .count()
+            ).sum();
+        // change of `si_edges`, if the neighbors of `index` see a new state of `index`
+        let update_si_edges = |
+            si_edges: &mut usize,
+            states: &[InfectionState],
+            index: usize,
+            old: InfectionState,
+            new: InfectionState
+        |
+        {
+            for &neighbor in graph.container(index).neighbors() {
+                let is_si = |state| matches!(
+                    (state, states[neighbor]),
+                    (InfectionState::Susceptible, InfectionState::Infected)
+                    | (InfectionState::Infected, InfectionState::Susceptible)
+                );
+                if is_si(old) {
+                    *si_edges -= 1;
+                }
+                if is_si(new) {
+                    *si_edges += 1;
+                }
+            }
+        };
+
+        let mut time = 0.0;
+        while !infected.is_empty() {
+            let infection_total = self.infection_rate * degree_sum as f64;
+            let recovery_total = self.recovery_rate * infected.len() as f64;
+            let total = infection_total + recovery_total;
+            if total <= 0.0 || (recovery_total == 0.0 && si_edges == 0) {
+                break;
+            }
+            let dt: f64 = rng.sample(Exp1);
+            time += dt / total;
+            if time > max_time {
+                break;
+            }
+
+            if rng.gen::<f64>() * total < recovery_total {
+                let index = infected[rng.gen_range(0..infected.len())];
+                infected.swap_remove(position[index]);
+                if let Some(&moved) = infected.get(position[index]) {
+                    position[moved] = position[index];
+                }
+                position[index] = usize::MAX;
+                degree_sum -= graph.degree(index).unwrap();
+                update_si_edges(&mut si_edges, &result.states, index, InfectionState::Infected, recovered);
+                result.record(time);
+                result.set(index, recovered);
+            } else {
+                // choose infected vertex proportional to its degree
+                let index = loop {
+                    let candidate = infected[rng.gen_range(0..infected.len())];
+                    let degree = graph.degree(candidate).unwrap();
+                    if rng.gen_range(0..max_degree) < degree {
+                        break candidate;
+                    }
+                };
+                let which = rng.gen_range(0..graph.degree(index).unwrap());
+                let neighbor = *graph.container(index)
+                    .neighbors()
+                    .nth(which)
+                    .unwrap();
+                // phantom process, if neighbor is not susceptible
+                if result.states[neighbor] == InfectionState::Susceptible {
+                    position[neighbor] = infected.len();
+                    infected.push(neighbor);
+                    degree_sum += graph.degree(neighbor).unwrap();
+                    update_si_edges(&mut si_edges, &result.states, neighbor, InfectionState::Susceptible, InfectionState::Infected);
+                    result.record(time);
+                    result.set(neighbor, InfectionState::Infected);
+                }
+            }
+        }
+        result
+    }
+
+    /// # Discrete dynamics using the rng of the ensemble
+    /// * same as [`self.discrete`](Self::discrete), but uses the random number generator of `ensemble`
+    pub fn discrete_with_ensemble<E, T, A, R>(
+        &self,
+        ensemble: &mut E,
+        initial_infected: &[usize],
+        max_steps: usize
+    ) -> EpidemicResult
+    where T: Node,
+          A: AdjContainer<T>,
+          R: Rng + SeedableRng,
+          E: HasRng<R> + AsRef<GenericGraph<T, A>>
+    {
+        with_ensemble_rng(
+            ensemble,
+            |ensemble, rng| self.discrete(ensemble.as_ref(), initial_infected, max_steps, rng)
+        )
+    }
+
+    /// # Gillespie dynamics using the rng of the ensemble
+    /// * same as [`self.gillespie`](Self::gillespie), but uses the random number generator of `ensemble`
+    pub fn gillespie_with_ensemble<E, T, A, R>(
+        &self,
+        ensemble: &mut E,
+        initial_infected: &[usize],
+        max_time: f64
+    ) -> EpidemicResult
+    where T: Node,
+          A: AdjContainer<T>,
+          R: Rng + SeedableRng,
+          E: HasRng<R> + AsRef<GenericGraph<T, A>>
+    {
+        with_ensemble_rng(
+            ensemble,
+            |ensemble, rng| self.gillespie(ensemble.as_ref(), initial_infected, max_time, rng)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, EmptyNode, ErEnsembleC};
+    use rand_pcg::Pcg64;
+
+    fn path(n: usize) -> Graph<EmptyNode>
+    {
+        let mut graph = Graph::new(n);
+        for i in 1..n {
+            graph.add_edge(i - 1, i).unwrap();
+        }
+        graph
+    }
+
+    fn check_consistency(result: &EpidemicResult, n: usize)
+    {
+        let len = result.time().len();
+        assert_eq!(result.susceptible().len(), len);
+        assert_eq!(result.infected().len(), len);
+        assert_eq!(result.recovered().len(), len);
+        for i in 0..len {
+            assert_eq!(result.susceptible()[i] + result.infected()[i] + result.recovered()[i], n);
+        }
+        assert!(result.time().windows(2).all(|w| w[0] <= w[1]));
+        let last = len - 1;
+        let count = |state| result.final_states()
+            .iter()
+            .filter(|&&s| s == state)
+            .count();
+        assert_eq!(count(InfectionState::Susceptible), result.susceptible()[last]);
+        assert_eq!(count(InfectionState::Infected), result.infected()[last]);
+        assert_eq!(count(InfectionState::Recovered), result.recovered()[last]);
+    }
+
+    #[test]
+    fn deterministic_discrete_sir()
+    {
+        let graph = path(5);
+        let mut rng = Pcg64::seed_from_u64(2389);
+        let result = Epidemic::sir(1.0, 1.0)
+            .discrete(&graph, &[2], 100, &mut rng);
+        assert_eq!(result.time(), &[0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(result.infected(), &[1, 2, 2, 0]);
+        assert_eq!(result.recovered(), &[0, 1, 3, 5]);
+        assert_eq!(result.outbreak_size(), 5);
+        assert!(result.is_extinct());
+        check_consistency(&result, 5);
+
+        let result = Epidemic::sir(0.0, 1.0)
+            .discrete(&graph, &[2, 2], 100, &mut rng);
+        assert_eq!(result.outbreak_size(), 1);
+        assert_eq!(result.duration(), 1.0);
+    }
+
+    #[test]
+    fn gillespie()
+    {
+        let rng = Pcg64::seed_from_u64(1232);
+        let mut ensemble = ErEnsembleC::<EmptyNode, _>::new(300, 4.0, rng);
+        let sir = Epidemic::sir(1.0, 1.0);
+        let result = sir.gillespie_with_ensemble(&mut ensemble, &[0, 1, 2], f64::INFINITY);
+        check_consistency(&result, 300);
+        assert!(result.is_extinct());
+        assert_eq!(result.outbreak_size(), *result.recovered().last().unwrap());
+        // every event changes exactly one vertex
+        for w in result.infected().windows(2) {
+            assert_eq!((w[0] as isize - w[1] as isize).abs(), 1);
+        }
+
+        let sis = Epidemic::sis(2.0, 1.0);
+        let result = sis.gillespie_with_ensemble(&mut ensemble, &[0], 5.0);
+        check_consistency(&result, 300);
+        assert!(result.duration() <= 5.0);
+        assert!(result.recovered().iter().all(|&r| r == 0));
+
+        let result = Epidemic::sis(0.5, 0.5)
+            .discrete_with_ensemble(&mut ensemble, &[0], 20);
+        check_consistency(&result, 300);
+    }
+
+    #[test]
+    fn gillespie_si_terminates()
+    {
+        let graph = path(6);
+        let mut rng = Pcg64::seed_from_u64(912);
+        let result = Epidemic::sir(1.0, 0.0)
+            .gillespie(&graph, &[2], f64::INFINITY, &mut rng);
+        check_consistency(&result, 6);
+        assert_eq!(result.infected().last(), Some(&6));
+        assert_eq!(result.time().len(), 6);
+
+        let result = Epidemic::sis(1.0, 0.0)
+            .gillespie(&graph, &[0, 5], f64::INFINITY, &mut rng);
+        assert_eq!(result.infected().last(), Some(&6));
+    }
+}
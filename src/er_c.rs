@@ -11,7 +11,9 @@ use{
     crate::{
         traits::*,
         iter::*,
-        graph::*
+        graph::*,
+        connectivity::EdgeChanges,
+        GenericGraph
     },
     std::{
         borrow::Borrow,
@@ -22,6 +24,13 @@ use{
 
 #[cfg(feature = "serde_support")]
 use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde_support")]
+use {
+    crate::snapshot::*,
+    crate::trajectory::RedoStep,
+    serde::de::DeserializeOwned,
+    std::io::Read
+};
 
 /// # Returned by markov steps
 #[derive(Debug, Clone, Copy)]
@@ -58,6 +67,16 @@ impl ErStepC {
     }
 }
 
+impl EdgeChanges for ErStepC {
+    fn edge_changes(&self, added: &mut Vec<(usize, usize)>, removed: &mut Vec<(usize, usize)>) {
+        match self {
+            Self::AddedEdge(edge) => added.push(*edge),
+            Self::RemovedEdge(edge) => removed.push(*edge),
+            Self::Nothing | Self::GError(..) => {},
+        }
+    }
+}
+
 /// # Implements Erdős-Rényi graph ensemble
 /// * variable number of edges
 /// * targets a connectivity
@@ -68,41 +87,47 @@ impl ErStepC {
 /// * for topology functions look at [`GenericGraph`](../generic_graph/struct.GenericGraph.html)
 /// * to access underlying topology or manipulate additional data look at [```WithGraph``` trait](../traits/trait.WithGraph.html)
 /// * to use or swap the random number generator, look at [```HasRng``` trait](../traits/trait.HasRng.html)
+/// * `A` is the adjacency container of the graph. For dense graphs use
+///   [`IndexedNodeContainer`](crate::IndexedNodeContainer), which checks for edges in constant time
+///   and create the ensemble with [`new_with_container`](#method.new_with_container)
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
-pub struct ErEnsembleC<T, R>
+pub struct ErEnsembleC<T, R, A = NodeContainer<T>>
 where T: Node
 {
-    graph: Graph<T>,
+    graph: GenericGraph<T, A>,
     prob: f64,
     c_target: f64,
     rng: R,
 }
 
-impl<T, R> AsRef<Graph<T>> for ErEnsembleC<T, R>
+impl<T, R, A> AsRef<GenericGraph<T, A>> for ErEnsembleC<T, R, A>
 where T: Node,
-      R: rand::Rng
+      R: rand::Rng,
+      A: AdjContainer<T>
 {
     #[inline]
-    fn as_ref(&self) -> &Graph<T>{
+    fn as_ref(&self) -> &GenericGraph<T, A>{
         &self.graph
     }
 }
 
-impl<T, R> Borrow<Graph<T>> for ErEnsembleC<T, R>
+impl<T, R, A> Borrow<GenericGraph<T, A>> for ErEnsembleC<T, R, A>
 where T: Node,
-      R: rand::Rng
+      R: rand::Rng,
+      A: AdjContainer<T>
 {
     #[inline]
-    fn borrow(&self) -> &Graph<T> {
+    fn borrow(&self) -> &GenericGraph<T, A> {
         &self.graph
     }
 }
 
 
-impl<T, R> HasRng<R> for ErEnsembleC<T, R>
+impl<T, R, A> HasRng<R> for ErEnsembleC<T, R, A>
     where   T: Node,
             R: rand::Rng,
+            A: AdjContainer<T>,
 {
     /// # Access RNG
     /// If, for some reason, you want access to the internal random number generator: Here you go
@@ -117,35 +142,62 @@ impl<T, R> HasRng<R> for ErEnsembleC<T, R>
     }
 }
 
-impl<T, R> SimpleSample for ErEnsembleC<T, R>
+impl<T, R, A> SimpleSample for ErEnsembleC<T, R, A>
 where   T: Node + SerdeStateConform,
         R: rand::Rng,
+        A: AdjContainer<T>,
 {
     /// # Randomizes the edges according to Er probabilities
     /// * this is used by `ErEnsembleC::new` to create the initial topology
     /// * you can use this for sampling the ensemble
-    /// * runs in `O(vertices * vertices)`
+    /// * runs in `O(vertices + edges)` on average: instead of drawing a random number for
+    ///   every possible edge, the number of skipped vertex pairs until the next edge is drawn
+    ///   from the geometric distribution
+    /// * the adjacency lists are sorted afterwards
+    /// # Citations
+    /// > V. Batagelj and U. Brandes, "Efficient generation of large random networks",
+    /// > Phys. Rev. E **71**, 036113, 2005, DOI: [10.1103/PhysRevE.71.036113](https://doi.org/10.1103/PhysRevE.71.036113)
     fn randomize(&mut self) {
         self.graph.clear_edges();
-        // iterate over all possible edges once
-        for i in 0..self.graph.vertex_count() {
-            for j in i+1..self.graph.vertex_count() {
-                if self.rng.gen::<f64>() <= self.prob {
-                    // in these circumstances equivalent to 
-                    // self.graph.add_edge(i, j).unwrap();
-                    // but without checking for existing edges and other errors -> a bit more efficient
-                    self.graph.vertices[i].adj.push(j);
-                    self.graph.vertices[j].adj.push(i);
-                    self.graph.edge_count += 1;
+        let n = self.graph.vertex_count();
+        if self.prob <= 0.0 || n < 2 {
+            return;
+        }
+        // number of vertex pairs, used to stop early for very long skips
+        let pairs = (n as f64) * (n as f64 - 1.0) / 2.0;
+        let log_q = (-self.prob).ln_1p();
+        // the vertex pairs (v, w) with w < v are visited in lexicographic order
+        let mut v = 1;
+        let mut w = 0;
+        loop {
+            // prob >= 1: no pair is skipped
+            if self.prob < 1.0 {
+                let skip = (1.0 - self.rng.gen::<f64>()).ln() / log_q;
+                if skip >= pairs {
+                    return;
                 }
+                w += skip as usize;
+            }
+            while w >= v {
+                w -= v;
+                v += 1;
             }
+            if v >= n {
+                return;
+            }
+            // in these circumstances equivalent to
+            // self.graph.add_edge(v, w).unwrap();
+            // but without checking for existing edges and other errors -> a bit more efficient
+            self.graph.add_edge_unchecked(v, w);
+            w += 1;
         }
     }
 }
 
-impl<T, R> MarkovChain<ErStepC, ErStepC> for ErEnsembleC<T, R>
+impl<T, R, A> MarkovChain<ErStepC, ErStepC> for ErEnsembleC<T, R, A>
     where   T: Node + SerdeStateConform,
             R: rand::Rng,
+            A: AdjContainer<T>,
 {
 
     /// # Markov step
@@ -236,9 +288,24 @@ where T: Node + SerdeStateConform,
     /// * `rng` is consumed and used as random number generator in the following
     /// * internally uses `Graph<T>::new(n)`
     /// * generates random edges according to ER model
+    /// * for other adjacency containers see [`new_with_container`](#method.new_with_container)
     pub fn new(n: usize, c_target: f64, rng: R) -> Self {
+        Self::new_with_container(n, c_target, rng)
+    }
+}
+
+impl<T, R, A> ErEnsembleC<T, R, A>
+where T: Node + SerdeStateConform,
+      R: rand::Rng,
+      A: AdjContainer<T>
+{
+    /// # Initialize with adjacency container `A`
+    /// * same as [`new`](#method.new), but the graph uses the adjacency container `A`,
+    ///   e.g., [`IndexedNodeContainer`](crate::IndexedNodeContainer)
+    /// * internally uses `GenericGraph::<T, A>::new(n)`
+    pub fn new_with_container(n: usize, c_target: f64, rng: R) -> Self {
         let prob = c_target / (n - 1) as f64;
-        let graph: Graph<T> = Graph::new(n);
+        let graph: GenericGraph<T, A> = GenericGraph::new(n);
         let mut e = ErEnsembleC {
             graph,
             c_target,
@@ -289,36 +356,38 @@ where T: Node + SerdeStateConform,
         self.c_target = c_target;
     }
 
-    fn graph_mut(&mut self) -> &mut Graph<T> {
+    fn graph_mut(&mut self) -> &mut GenericGraph<T, A> {
         &mut self.graph
     }
 }
 
-impl<T, R> GraphIteratorsMut<T, Graph<T>, NodeContainer<T>> for ErEnsembleC<T, R>
+impl<T, R, A> GraphIteratorsMut<T, GenericGraph<T, A>, A> for ErEnsembleC<T, R, A>
 where   T: Node + SerdeStateConform,
-        R: rand::Rng
+        R: rand::Rng,
+        A: AdjContainer<T>
 {
     fn contained_iter_neighbors_mut(&mut self, index: usize) ->
-        NContainedIterMut<T, NodeContainer<T>, IterWrapper>
+        NContainedIterMut<T, A, IterWrapper>
     {
         self.graph.contained_iter_neighbors_mut(index)
     }
 
     fn contained_iter_neighbors_mut_with_index(&mut self, index: usize)
-        -> INContainedIterMut<'_, T, NodeContainer<T>>
+        -> INContainedIterMut<'_, T, A>
     {
         self.graph.contained_iter_neighbors_mut_with_index(index)
     }
 
-    fn contained_iter_mut(&mut self) ->  ContainedIterMut<T, NodeContainer<T>> {
+    fn contained_iter_mut(&mut self) ->  ContainedIterMut<T, A> {
         self.graph.contained_iter_mut()
     }
 }
 
 
-impl<T, R> WithGraph<T, Graph<T>> for ErEnsembleC<T, R>
+impl<T, R, A> WithGraph<T, GenericGraph<T, A>> for ErEnsembleC<T, R, A>
 where   T: Node + SerdeStateConform,
-        R: rand::Rng
+        R: rand::Rng,
+        A: AdjContainer<T>
 {
     fn at(&self, index: usize) -> &T{
         self.graph.at(index)
@@ -328,7 +397,7 @@ where   T: Node + SerdeStateConform,
         self.graph.at_mut(index)
     }
 
-    fn graph(&self) -> &Graph<T> {
+    fn graph(&self) -> &GenericGraph<T, A> {
         self.borrow()
     }
 
@@ -357,6 +426,48 @@ pub(crate) fn draw_two_from_range<T: rand::Rng>(rng: &mut T, high: usize) -> (us
     }
 }
 
+#[cfg(feature = "serde_support")]
+impl<T, R, A> Snapshot for ErEnsembleC<T, R, A>
+where T: Node + DeserializeOwned,
+      R: Serialize + DeserializeOwned,
+      A: SnapshotContainer<T>
+{
+    fn save_snapshot<W: Write>(&self, writer: W) -> Result<(), SnapshotError>
+    {
+        let mut snapshot = SnapshotWriter::new(writer, "ErEnsembleC", 1)?;
+        snapshot.graph(&self.graph)?;
+        snapshot.section(&(self.prob, self.c_target))?;
+        snapshot.section(&self.rng)
+    }
+
+    fn load_snapshot<Re: Read>(reader: Re) -> Result<Self, SnapshotError>
+    {
+        let mut snapshot = SnapshotReader::new(reader, "ErEnsembleC", 1)?;
+        let graph = snapshot.graph()?;
+        let (prob, c_target) = snapshot.section()?;
+        let rng = snapshot.section()?;
+        Ok(Self{graph, prob, c_target, rng})
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<T, R, A> RedoStep<ErStepC> for ErEnsembleC<T, R, A>
+where T: Node,
+      A: AdjContainer<T>
+{
+    fn redo_step(&mut self, step: &ErStepC) -> bool {
+        let n = self.graph.vertex_count();
+        match *step {
+            ErStepC::AddedEdge(edge) => crate::trajectory::valid_pair(n, edge)
+                && self.graph.add_edge(edge.0, edge.1).is_ok(),
+            ErStepC::RemovedEdge(edge) => crate::trajectory::valid_pair(n, edge)
+                && self.graph.remove_edge(edge.0, edge.1).is_ok(),
+            ErStepC::Nothing |
+            ErStepC::GError(_) => true,
+        }
+    }
+}
+
 #[cfg(test)]
 mod testing {
     use super::*;
@@ -402,6 +513,57 @@ mod testing {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn randomize_distribution() {
+        let n = 40;
+        let samples = 2000;
+        let rng = Pcg64::seed_from_u64(2390);
+        let mut e = ErEnsembleC::<EmptyNode, Pcg64>::new(n, 3.0, rng);
+        let p = e.prob;
+        let mut hits = vec![vec![0usize; n]; n];
+        for _ in 0..samples {
+            e.randomize();
+            let mut degree_sum = 0;
+            for (i, container) in e.graph().container_iter().enumerate() {
+                let neighbors: Vec<_> = container.neighbors().copied().collect();
+                // sorted without duplicates or self loops
+                assert!(neighbors.windows(2).all(|w| w[0] < w[1]));
+                assert!(!neighbors.contains(&i));
+                for j in neighbors {
+                    hits[i][j] += 1;
+                }
+                degree_sum += container.degree();
+            }
+            assert_eq!(degree_sum, 2 * e.graph().edge_count());
+        }
+        // every pair is an edge with probability p
+        let mean = samples as f64 * p;
+        let sigma = (mean * (1.0 - p)).sqrt();
+        for (i, row) in hits.iter().enumerate() {
+            for (j, &count) in row.iter().enumerate().skip(i + 1) {
+                assert_eq!(count, hits[j][i]);
+                assert!((count as f64 - mean).abs() < 5.0 * sigma);
+            }
+        }
+        let total: usize = hits.iter().flatten().sum();
+        let expected = mean * (n * (n - 1)) as f64;
+        assert!((total as f64 - expected).abs() < 0.02 * expected);
+    }
+
+    #[test]
+    fn randomize_large_sparse() {
+        let rng = Pcg64::seed_from_u64(8123);
+        let n = 200_000;
+        let e = ErEnsembleC::<EmptyNode, Pcg64>::new(n, 3.0, rng);
+        let average = e.graph().average_degree();
+        assert!((average - 3.0).abs() < 0.05);
+
+        // probability larger than 1
+        let rng = Pcg64::seed_from_u64(8124);
+        let e = ErEnsembleC::<EmptyNode, Pcg64>::new(10, 20.0, rng);
+        assert_eq!(e.graph().edge_count(), 45);
+    }
+
     #[test]
     fn draw_2(){
         let mut rng = Pcg64::seed_from_u64(762132);
@@ -417,8 +579,9 @@ mod testing {
 }
 
 
-impl<T, R> Dot for ErEnsembleC<T, R>
-where T: Node
+impl<T, R, A> Dot for ErEnsembleC<T, R, A>
+where T: Node,
+      A: AdjContainer<T>
 {
     fn dot_from_indices<F, W, S1, S2>(&self, writer: W, dot_options: S1, f: F)
         -> Result<(), std::io::Error>
@@ -473,8 +636,9 @@ where T: Node
     }
 }
 
-impl<T, R> Contained<T> for ErEnsembleC<T, R>
-where T: Node
+impl<T, R, A> Contained<T> for ErEnsembleC<T, R, A>
+where T: Node,
+      A: AdjContainer<T>
 {
     fn get_contained(&self, index: usize) -> Option<&T> {
         self.graph.get_contained(index)
@@ -8,13 +8,20 @@
 //!   Publ. Math. Inst. Hungar. Acad. Sci. **5**, 17-61 (1960)
 //!
 use {
-    crate::{graph::*, iter::*, traits::*},
-    std::{borrow::Borrow, convert::AsRef, io::Write},
-    rand::seq::SliceRandom
+    crate::{graph::*, iter::*, traits::*, GenericGraph, connectivity::EdgeChanges},
+    crate::er_c::draw_two_from_range,
+    std::{borrow::Borrow, collections::HashSet, convert::AsRef, io::Write},
 };
 
 #[cfg(feature = "serde_support")]
 use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde_support")]
+use {
+    crate::snapshot::*,
+    crate::trajectory::RedoStep,
+    serde::de::DeserializeOwned,
+    std::io::Read
+};
 
 /// Storing the information about which edges were deleted or added
 #[derive(Debug, Clone, Copy)]
@@ -24,7 +31,6 @@ pub struct ErStepM{
     pub(crate) removed: (usize, usize),
     pub(crate) i_removed: usize,
     pub(crate) inserted: (usize, usize),
-    pub(crate) i_inserted: usize,
 }
 
 impl ErStepM{
@@ -40,23 +46,30 @@ impl ErStepM{
         Self{
             removed: self.inserted,
             inserted: self.removed,
-            i_inserted: self.i_inserted,
             i_removed: self.i_removed,
         }
     }
 }
 
+impl EdgeChanges for ErStepM {
+    fn edge_changes(&self, added: &mut Vec<(usize, usize)>, removed: &mut Vec<(usize, usize)>) {
+        added.push(self.inserted);
+        removed.push(self.removed);
+    }
+}
+
 /// # Implements Erdős-Rényi graph ensemble
 /// Constant number of edges
-/// * **Note** simple sampling of this ensemble is somewhat inefficient right now -
-///   I might change it in the future, though that will change the results of the simple sampling
-///   (Not on average of cause)
+/// * only the current edges are stored, i.e., memory is `O(n + m)`
 /// * for *simple sampling* look at [```SimpleSample``` trait](./sampling/traits/trait.SimpleSample.html)
 /// * for *markov steps* look at [```MarkovChain``` trait](../sampling/traits/trait.MarkovChain.html)
 /// ## Other
 /// * for topology functions look at [`GenericGraph`](../generic_graph/struct.GenericGraph.html)
 /// * to access underlying topology or manipulate additional data look at [```WithGraph``` trait](../traits/trait.WithGraph.html)
 /// * to use or swap the random number generator, look at [```HasRng``` trait](../traits/trait.HasRng.html)
+/// * `A` is the adjacency container of the graph. For dense graphs use
+///   [`IndexedNodeContainer`](crate::IndexedNodeContainer), which checks for edges in constant time
+///   and create the ensemble with [`new_with_container`](#method.new_with_container)
 ///
 /// # Save and load example
 /// * only works if feature ```"serde_support"``` is enabled
@@ -98,40 +111,41 @@ impl ErStepM{
 ///
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
-pub struct ErEnsembleM<T: Node, R>
+pub struct ErEnsembleM<T: Node, R, A = NodeContainer<T>>
 {
-    graph: Graph<T>,
+    graph: GenericGraph<T, A>,
     m: usize,
     rng: R,
-    all_edges: Vec<(usize, usize)>,
-    possible_edges: Vec<(usize, usize)>,
     current_edges: Vec<(usize, usize)>,
 }
 
 
-impl<T, R> AsRef<Graph<T>> for ErEnsembleM<T, R>
+impl<T, R, A> AsRef<GenericGraph<T, A>> for ErEnsembleM<T, R, A>
 where T: Node,
-      R: rand::Rng
+      R: rand::Rng,
+      A: AdjContainer<T>
 {
     #[inline]
-    fn as_ref(&self) -> &Graph<T>{
+    fn as_ref(&self) -> &GenericGraph<T, A>{
         &self.graph
     }
 }
 
-impl<T, R> Borrow<Graph<T>> for ErEnsembleM<T, R>
+impl<T, R, A> Borrow<GenericGraph<T, A>> for ErEnsembleM<T, R, A>
 where T: Node,
-      R: rand::Rng
+      R: rand::Rng,
+      A: AdjContainer<T>
 {
     #[inline]
-    fn borrow(&self) -> &Graph<T> {
+    fn borrow(&self) -> &GenericGraph<T, A> {
         &self.graph
     }
 }
 
-impl<T, R> HasRng<R> for ErEnsembleM<T, R>
+impl<T, R, A> HasRng<R> for ErEnsembleM<T, R, A>
     where   T: Node,
             R: rand::Rng,
+            A: AdjContainer<T>,
 {
     /// # Access RNG
     /// If, for some reason, you want access to the internal random number generator: Here you go
@@ -146,39 +160,54 @@ impl<T, R> HasRng<R> for ErEnsembleM<T, R>
     }
 }
 
-impl<T, R> SimpleSample for ErEnsembleM<T, R>
+impl<T, R, A> SimpleSample for ErEnsembleM<T, R, A>
     where   T: Node + SerdeStateConform,
             R: rand::Rng,
+            A: AdjContainer<T>,
 {
     /// # Randomizes self according to  model
     /// * this is intended for creation of initial sample
     /// * used in [`simple_sample`](#method.simple_sample)
     /// and [`simple_sample_vec`](#method.simple_sample_vec)
+    /// # Performance
+    /// * draws `m` distinct edges by rejection, using a hash set: `O(n + m)` on average
+    /// * if more than half of all possible edges are requested,
+    ///   the missing edges are drawn instead, i.e., `O(n + n(n-1)/2 - m)` draws
     fn randomize(&mut self){
         self.graph.clear_edges();
+        self.current_edges.clear();
+
+        let n = self.graph.vertex_count();
+        let p_edges = possible_edges(n);
+
+        if self.m <= p_edges / 2 {
+            self.current_edges = self.draw_distinct_pairs(self.m);
+        } else {
+            let missing: HashSet<_> = self.draw_distinct_pairs(p_edges - self.m)
+                .into_iter()
+                .collect();
+            for i in 0..n {
+                for j in i+1..n {
+                    if !missing.contains(&(i, j)) {
+                        self.current_edges.push((i, j));
+                    }
+                }
+            }
+        }
 
-        self.shuffle_all_edges();
-
-        // uses mem-copy
-        self.current_edges
-            .copy_from_slice(&self.all_edges[..self.m]);
-
-        for edge in self.current_edges.iter()
+        for &(i, j) in self.current_edges.iter()
         {
             self.graph
-                .add_edge(edge.0, edge.1)
+                .add_edge(i, j)
                 .unwrap();
         }
-
-        // uses mem-copy
-        self.possible_edges
-            .copy_from_slice(&self.all_edges[self.m..]);
     }
 }
 
-impl <T, R> MarkovChain<ErStepM, ErStepM> for ErEnsembleM<T, R>
+impl <T, R, A> MarkovChain<ErStepM, ErStepM> for ErEnsembleM<T, R, A>
     where   T: Node + SerdeStateConform,
             R: rand::Rng,
+            A: AdjContainer<T>,
 {
     /// * undo a markov step, return result-state
     /// * if you want to undo more than one step
@@ -198,16 +227,31 @@ impl <T, R> MarkovChain<ErStepM, ErStepM> for ErEnsembleM<T, R>
 
     /// # Markov step
     /// * use this to perform a markov step
+    /// * removes a random edge and inserts a random non-edge, which is drawn by rejection
     /// * for doing multiple mc steps at once, use [`m_steps`](#method.m_steps)
+    /// # Panics
+    /// * if the graph has no edges or is complete, as no step is possible then
     fn m_step(&mut self) -> ErStepM{
-        let index_current   = self.rng.gen_range(0..self.current_edges.len());
-        let index_possible  = self.rng.gen_range(0..self.possible_edges.len());
+        let n = self.graph.vertex_count();
+        assert!(
+            self.m > 0 && self.m < possible_edges(n),
+            "ErEnsembleM with {} vertices and {} edges has no valid markov step",
+            n,
+            self.m
+        );
+        let index_current = self.rng.gen_range(0..self.current_edges.len());
+
+        let inserted = loop {
+            let (i, j) = ordered(draw_two_from_range(&mut self.rng, n));
+            if !self.graph.container(i).is_adjacent(j) {
+                break (i, j);
+            }
+        };
 
         let step = ErStepM{
             removed:  self.current_edges[index_current],
             i_removed: index_current,
-            inserted: self.possible_edges[index_possible],
-            i_inserted: index_possible
+            inserted,
         };
 
         self.step(&step);
@@ -219,6 +263,24 @@ impl <T, R> MarkovChain<ErStepM, ErStepM> for ErEnsembleM<T, R>
 impl<T, R> ErEnsembleM<T, R>
 where T: Node + SerdeStateConform,
       R: rand::Rng
+{
+    /// # Initialize
+    /// create new ErEnsembleM graph with:
+    /// * `n` vertices
+    /// * `m` edges
+    /// * `rng` is consumed and used as random number generator in the following
+    /// * internally uses `Graph<T>::new(n)`
+    /// * generates random edges according to ER model
+    /// * for other adjacency containers see [`new_with_container`](#method.new_with_container)
+    pub fn new(n: usize, m: usize, rng: R) -> Self {
+        Self::new_with_container(n, m, rng)
+    }
+}
+
+impl<T, R, A> ErEnsembleM<T, R, A>
+where T: Node + SerdeStateConform,
+      R: rand::Rng,
+      A: AdjContainer<T>
 {
     fn step(&mut self, step: &ErStepM){
         self.graph
@@ -229,23 +291,32 @@ where T: Node + SerdeStateConform,
             .add_edge(step.inserted.0, step.inserted.1)
             .unwrap();
 
-        std::mem::swap(
-            &mut self.current_edges[step.i_removed],
-            &mut self.possible_edges[step.i_inserted]
-        );
+        self.current_edges[step.i_removed] = step.inserted;
+    }
+
+    /// draws `amount` distinct pairs `(i, j)` with `i < j`, in order of drawing
+    fn draw_distinct_pairs(&mut self, amount: usize) -> Vec<(usize, usize)> {
+        let n = self.graph.vertex_count();
+        let mut seen = HashSet::with_capacity(amount);
+        let mut drawn = Vec::with_capacity(amount);
+        while drawn.len() < amount {
+            let pair = ordered(draw_two_from_range(&mut self.rng, n));
+            // the order of the hash set is not reproducible, the order of drawing is
+            if seen.insert(pair) {
+                drawn.push(pair);
+            }
+        }
+        drawn
     }
     
-    /// # Initialize
-    /// create new ErEnsembleM graph with:
-    /// * `n` vertices
-    /// * `m` edges
-    /// * `rng` is consumed and used as random number generator in the following
-    /// * internally uses `Graph<T>::new(n)`
-    /// * generates random edges according to ER model
-    pub fn new(n: usize, m: usize, rng: R) -> Self {
-        let graph: Graph<T> = Graph::new(n);
+    /// # Initialize with adjacency container `A`
+    /// * same as [`new`](#method.new), but the graph uses the adjacency container `A`,
+    ///   e.g., [`IndexedNodeContainer`](crate::IndexedNodeContainer)
+    /// * internally uses `GenericGraph::<T, A>::new(n)`
+    pub fn new_with_container(n: usize, m: usize, rng: R) -> Self {
+        let graph: GenericGraph<T, A> = GenericGraph::new(n);
 
-        let p_edges = (n * (n - 1)) / 2;
+        let p_edges = possible_edges(n);
 
         // panic, if you try to create a graph with to many edges
         assert!(
@@ -258,26 +329,17 @@ where T: Node + SerdeStateConform,
             std::any::type_name::<Self>()
         );
 
-        let mut vec = Vec::with_capacity(p_edges);
-        for i in 0..n {
-            for j in i+1..n {
-                vec.push((i, j));
-            }
-        }
-
         let mut e = ErEnsembleM {
             graph,
             m,
             rng,
-            all_edges: vec,
-            possible_edges: vec![(0, 0); p_edges - m],   // randomize will mem_copy - slice needs to be big enough
-            current_edges: vec![(0, 0); m], // randomize will mem_copy - slice needs to be big enough
+            current_edges: Vec::with_capacity(m),
         };
         e.randomize();
         e
     }
 
-    fn graph_mut(&mut self) -> &mut Graph<T> {
+    fn graph_mut(&mut self) -> &mut GenericGraph<T, A> {
         &mut self.graph
     }
 
@@ -285,38 +347,44 @@ where T: Node + SerdeStateConform,
     pub fn get_m(&self) -> usize {
         self.m
     }
+}
 
-    fn shuffle_all_edges(&mut self) {
-        self.all_edges
-            .shuffle(&mut self.rng);
-    }
+#[inline]
+fn possible_edges(n: usize) -> usize {
+    n * n.saturating_sub(1) / 2
+}
 
+#[inline]
+fn ordered((i, j): (usize, usize)) -> (usize, usize) {
+    if i < j { (i, j) } else { (j, i) }
 }
 
-impl<T, R> GraphIteratorsMut<T, Graph<T>, NodeContainer<T>> for ErEnsembleM<T, R>
+impl<T, R, A> GraphIteratorsMut<T, GenericGraph<T, A>, A> for ErEnsembleM<T, R, A>
 where   T: Node + SerdeStateConform,
-        R: rand::Rng
+        R: rand::Rng,
+        A: AdjContainer<T>
 {
     fn contained_iter_neighbors_mut(&mut self, index: usize) ->
-        NContainedIterMut<T, NodeContainer<T>, IterWrapper>
+        NContainedIterMut<T, A, IterWrapper>
     {
         self.graph.contained_iter_neighbors_mut(index)
     }
 
     fn contained_iter_neighbors_mut_with_index(&mut self, index: usize)
-        -> INContainedIterMut<'_, T, NodeContainer<T>>
+        -> INContainedIterMut<'_, T, A>
     {
         self.graph.contained_iter_neighbors_mut_with_index(index)
     }
 
-    fn contained_iter_mut(&mut self) ->  ContainedIterMut<T, NodeContainer<T>> {
+    fn contained_iter_mut(&mut self) ->  ContainedIterMut<T, A> {
         self.graph.contained_iter_mut()
     }
 }
 
-impl<T, R> WithGraph<T, Graph<T>> for ErEnsembleM<T, R>
+impl<T, R, A> WithGraph<T, GenericGraph<T, A>> for ErEnsembleM<T, R, A>
 where   T: Node + SerdeStateConform,
-        R: rand::Rng
+        R: rand::Rng,
+        A: AdjContainer<T>
 {
     fn at(&self, index: usize) -> &T{
         self.graph.at(index)
@@ -326,7 +394,7 @@ where   T: Node + SerdeStateConform,
         self.graph.at_mut(index)
     }
 
-    fn graph(&self) -> &Graph<T> {
+    fn graph(&self) -> &GenericGraph<T, A> {
         self.borrow()
     }
 
@@ -342,8 +410,9 @@ where   T: Node + SerdeStateConform,
     }
 }
 
-impl<T, R> Dot for ErEnsembleM<T, R>
-where T: Node
+impl<T, R, A> Dot for ErEnsembleM<T, R, A>
+where T: Node,
+      A: AdjContainer<T>
 {
     fn dot_from_indices<F, W, S1, S2>(&self, writer: W, dot_options: S1, f: F)
         -> Result<(), std::io::Error>
@@ -398,8 +467,9 @@ where T: Node
     }
 }
 
-impl<T, R> Contained<T> for ErEnsembleM<T, R>
-where T: Node
+impl<T, R, A> Contained<T> for ErEnsembleM<T, R, A>
+where T: Node,
+      A: AdjContainer<T>
 {
     fn get_contained(&self, index: usize) -> Option<&T> {
         self.graph.get_contained(index)
@@ -416,4 +486,60 @@ where T: Node
     unsafe fn get_contained_unchecked_mut(&mut self, index: usize) -> &mut T {
         self.graph.get_contained_unchecked_mut(index)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "serde_support")]
+impl<T, R, A> Snapshot for ErEnsembleM<T, R, A>
+where T: Node + DeserializeOwned,
+      R: Serialize + DeserializeOwned,
+      A: SnapshotContainer<T>
+{
+    fn save_snapshot<W: Write>(&self, writer: W) -> Result<(), SnapshotError>
+    {
+        let mut snapshot = SnapshotWriter::new(writer, "ErEnsembleM", 2)?;
+        snapshot.graph(&self.graph)?;
+        snapshot.section(&(self.m, &self.current_edges))?;
+        snapshot.section(&self.rng)
+    }
+
+    fn load_snapshot<Re: Read>(reader: Re) -> Result<Self, SnapshotError>
+    {
+        let mut snapshot = SnapshotReader::new(reader, "ErEnsembleM", 2)?;
+        let graph: GenericGraph<T, A> = snapshot.graph()?;
+        let (m, current_edges): (usize, Vec<(usize, usize)>) = snapshot.section()?;
+        if current_edges.len() != m || graph.edge_count() != m {
+            return Err(SnapshotError::Corrupt("number of edges does not match".into()));
+        }
+        // as the numbers match, the stored edges are the edges of the graph,
+        // if they are valid, unique and contained in the graph
+        let n = graph.vertex_count();
+        let valid = current_edges.iter()
+            .all(|&(i, j)| i < j && j < n && graph.container(i).is_adjacent(j));
+        let mut sorted = current_edges.clone();
+        sorted.sort_unstable();
+        if !valid || sorted.windows(2).any(|w| w[0] == w[1]) {
+            return Err(SnapshotError::Corrupt("stored edges do not match the graph".into()));
+        }
+        let rng = snapshot.section()?;
+        Ok(Self{graph, m, rng, current_edges})
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<T, R, A> RedoStep<ErStepM> for ErEnsembleM<T, R, A>
+where T: Node + SerdeStateConform,
+      R: rand::Rng,
+      A: AdjContainer<T>
+{
+    fn redo_step(&mut self, step: &ErStepM) -> bool {
+        let (i, j) = step.inserted;
+        let fits = self.current_edges.get(step.i_removed) == Some(&step.removed)
+            && i < j
+            && j < self.graph.vertex_count()
+            && !self.graph.container(i).is_adjacent(j);
+        if fits {
+            self.step(step);
+        }
+        fits
+    }
+}
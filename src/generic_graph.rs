@@ -9,6 +9,14 @@ pub use iterators::*;
 mod generic_graph;
 pub use generic_graph::*;
 
+mod edge_index;
+pub use edge_index::*;
+
+mod operations;
+
+#[cfg(feature = "parallel")]
+mod parallel;
+
 #[cfg(test)]
 mod tests{
     use super::*;
@@ -102,6 +110,105 @@ mod tests{
         assert!(graph.cloned_subgraph(vec![199,199,329029]).is_none());
     }
 
+    #[test]
+    fn edge_load()
+    {
+        let mut graph = Graph::<EmptyNode>::new(4);
+        for i in 1..4 {
+            graph.add_edge(i - 1, i).unwrap();
+        }
+        let load = graph.edge_load();
+        assert_eq!(load, vec![((0, 1), 6.0), ((1, 2), 8.0), ((2, 3), 6.0)]);
+
+        // every edge of a complete graph is the only shortest path between its endpoints
+        let graph = Graph::<EmptyNode>::complete_graph(6);
+        assert!(graph.edge_load().iter().all(|&(_, l)| l == 2.0));
+    }
+
+    #[test]
+    fn random_walks()
+    {
+        use rand::SeedableRng;
+        use rand_pcg::Pcg64;
+
+        let mut rng = Pcg64::seed_from_u64(8923);
+        let mut graph = Graph::<EmptyNode>::new(3);
+        graph.add_edge(0, 1).unwrap();
+        graph.add_edge(1, 2).unwrap();
+        assert_eq!(graph.stationary_distribution(), vec![0.25, 0.5, 0.25]);
+
+        // path of length 3 is bipartite: simple walk does not mix, lazy walk does
+        assert_eq!(graph.random_walk_distribution(0, 3, 0.0), vec![0.0, 1.0, 0.0]);
+        let lazy = graph.random_walk_distribution(0, 100, 0.5);
+        assert!(lazy.iter().zip(graph.stationary_distribution()).all(|(a, b)| (a - b).abs() < 1e-12));
+        assert!(graph.mixing_time(0.01, 100).is_some());
+
+        let walk: Vec<_> = graph.lazy_random_walk(2, 0.5, &mut rng)
+            .take(100)
+            .collect();
+        assert!(
+            walk.windows(2)
+                .all(|w| w[0] == w[1] || graph.container(w[0]).is_adjacent(w[1]))
+        );
+        assert_eq!(graph.random_walk(3, &mut rng).next(), None);
+
+        // complete graph: mean first passage time n - 1, cover time (n-1) H_{n-1}
+        let graph = Graph::<EmptyNode>::complete_graph(3);
+        let mfpt = graph.mean_first_passage_time(0, 1, 20000, &mut rng).unwrap();
+        assert!((mfpt - 2.0).abs() < 0.1);
+        let cover = graph.mean_cover_time(0, 20000, &mut rng).unwrap();
+        assert!((cover - 3.0).abs() < 0.1);
+
+        let graph = Graph::<EmptyNode>::new(2);
+        assert_eq!(graph.mean_first_passage_time(0, 1, 10, &mut rng), None);
+        assert_eq!(graph.mean_cover_time(0, 10, &mut rng), None);
+        assert_eq!(graph.mixing_time(0.1, 100), None);
+        assert_eq!(graph.random_walk(1, &mut rng).take(3).collect::<Vec<_>>(), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn traversal_workspace()
+    {
+        use rand::SeedableRng;
+        use rand_pcg::Pcg64;
+        use crate::{ErEnsembleC, WithGraph};
+
+        // one workspace for graphs of different sizes
+        let mut workspace = TraversalWorkspace::new();
+        for (seed, &n) in [50, 10, 80, 1, 30].iter().enumerate() {
+            let e = ErEnsembleC::<EmptyNode, Pcg64>::new(n, 1.5, Pcg64::seed_from_u64(seed as u64));
+            let graph = e.graph();
+            assert_eq!(graph.is_connected_in(&mut workspace), graph.is_connected());
+            assert_eq!(graph.connected_components_in(&mut workspace), graph.connected_components());
+            assert_eq!(graph.connected_components_ids_in(&mut workspace), graph.connected_components_ids());
+            assert_eq!(graph.diameter_in(&mut workspace), graph.diameter());
+            assert_eq!(
+                graph.longest_shortest_path_from_index_in(n - 1, &mut workspace),
+                graph.longest_shortest_path_from_index(n - 1)
+            );
+            let dfs: Vec<_> = graph.dfs_with_index_in(0, &mut workspace).map(|(i, _)| i).collect();
+            assert_eq!(dfs, graph.dfs_with_index(0).map(|(i, _)| i).collect::<Vec<_>>());
+            assert_eq!(graph.dfs_in(0, &mut workspace).count(), graph.dfs(0).count());
+            let bfs: Vec<_> = graph.bfs_index_depth_in(0, &mut workspace).map(|(i, _, d)| (i, d)).collect();
+            assert_eq!(bfs, graph.bfs_index_depth(0).map(|(i, _, d)| (i, d)).collect::<Vec<_>>());
+            let filtered: Vec<_> = graph.bfs_filtered_in(0, |_, i| i % 3 != 1, &mut workspace)
+                .unwrap()
+                .map(|(i, _, d)| (i, d))
+                .collect();
+            let expected: Vec<_> = graph.bfs_filtered(0, |_, i| i % 3 != 1)
+                .unwrap()
+                .map(|(i, _, d)| (i, d))
+                .collect();
+            assert_eq!(filtered, expected);
+        }
+
+        let graph = Graph::<EmptyNode>::complete_graph(5);
+        let closeness = graph.closeness_centrality_in(&mut workspace);
+        assert_eq!(closeness, graph.closeness_centrality());
+        assert_eq!(closeness, vec![1.0; 5]);
+        assert!(graph.dfs_in(7, &mut workspace).next().is_none());
+    }
+
     #[test]
     fn test_dfs_mut_magic()
     {
@@ -0,0 +1,233 @@
+use{
+    super::generic_graph::GenericGraph,
+    crate::traits::*,
+    std::collections::HashMap
+};
+
+/// # Stable indices for undirected edges
+/// * maps every edge `(i, j)` to an index in `0..len()`, so that data
+///   belonging to an edge (weights, loads, flags, ...) can be stored in a parallel `Vec`
+/// * edges are stored as `(min, max)`, lookup does not depend on the order of `i` and `j`
+/// * indices only change through [`swap_remove`](#method.swap_remove), which has the same
+///   semantics as [`Vec::swap_remove`], i.e., call `swap_remove` on your parallel `Vec`
+///   with the returned index to keep both consistent
+/// # Example
+/// ```
+/// use net_ensembles::{Graph, EmptyNode, EdgeIndex};
+///
+/// let mut graph = Graph::<EmptyNode>::new(4);
+/// graph.add_edge(0, 1).unwrap();
+/// graph.add_edge(1, 2).unwrap();
+/// graph.add_edge(3, 1).unwrap();
+///
+/// let mut index = graph.edge_index();
+/// let mut weights: Vec<f64> = index.iter()
+///     .map(|&(i, j)| (i + j) as f64)
+///     .collect();
+///
+/// assert_eq!(index.len(), 3);
+/// assert_eq!(weights[index.get(3, 1).unwrap()], 4.0);
+///
+/// // remove edge from graph and index
+/// graph.remove_edge(1, 2).unwrap();
+/// let removed = index.swap_remove(2, 1).unwrap();
+/// weights.swap_remove(removed);
+///
+/// assert!(!index.contains(1, 2));
+/// assert_eq!(weights[index.get(1, 3).unwrap()], 4.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EdgeIndex {
+    edges: Vec<(usize, usize)>,
+    index: HashMap<(usize, usize), usize>,
+}
+
+#[inline]
+fn ordered(i: usize, j: usize) -> (usize, usize)
+{
+    if i <= j {
+        (i, j)
+    } else {
+        (j, i)
+    }
+}
+
+impl EdgeIndex {
+    /// # Create empty index
+    pub fn new_empty() -> Self
+    {
+        Self::default()
+    }
+
+    /// # Index all edges of `graph`
+    /// * indices follow the order of [`GenericGraph::edges`]
+    pub fn new<T, A>(graph: &GenericGraph<T, A>) -> Self
+    where A: AdjContainer<T>
+    {
+        let mut edges = Vec::with_capacity(graph.edge_count());
+        let mut index = HashMap::with_capacity(graph.edge_count());
+        for edge in graph.edges() {
+            index.insert(edge, edges.len());
+            edges.push(edge);
+        }
+        Self{
+            edges,
+            index
+        }
+    }
+
+    /// # Number of indexed edges
+    pub fn len(&self) -> usize
+    {
+        self.edges.len()
+    }
+
+    /// # `true` if no edge is indexed
+    pub fn is_empty(&self) -> bool
+    {
+        self.edges.is_empty()
+    }
+
+    /// # Edge belonging to index
+    /// * `None` if `index >= self.len()`
+    /// * returned edge `(i, j)` always fulfills `i <= j`
+    pub fn edge(&self, index: usize) -> Option<(usize, usize)>
+    {
+        self.edges.get(index).copied()
+    }
+
+    /// # Index of edge `(i, j)`
+    /// * same as index of edge `(j, i)`
+    /// * `None` if edge is not indexed
+    pub fn get(&self, i: usize, j: usize) -> Option<usize>
+    {
+        self.index.get(&ordered(i, j)).copied()
+    }
+
+    /// # Is edge `(i, j)` indexed?
+    pub fn contains(&self, i: usize, j: usize) -> bool
+    {
+        self.index.contains_key(&ordered(i, j))
+    }
+
+    /// # All indexed edges, position in slice is the index
+    pub fn edges(&self) -> &[(usize, usize)]
+    {
+        &self.edges
+    }
+
+    /// # Iterate over edges in order of their indices
+    pub fn iter(&self) -> std::slice::Iter<'_, (usize, usize)>
+    {
+        self.edges.iter()
+    }
+
+    /// # Add edge `(i, j)` to the index
+    /// * new edges get the index `self.len()`, i.e., `push` the data of
+    ///   the edge to your parallel `Vec`
+    /// * returns `None` if the edge was already indexed
+    pub fn insert(&mut self, i: usize, j: usize) -> Option<usize>
+    {
+        let edge = ordered(i, j);
+        if self.index.contains_key(&edge) {
+            return None;
+        }
+        let idx = self.edges.len();
+        self.index.insert(edge, idx);
+        self.edges.push(edge);
+        Some(idx)
+    }
+
+    /// # Remove edge `(i, j)` from the index
+    /// * returns the index the edge had, or `None` if it was not indexed
+    /// * the last edge is moved to the returned index,
+    ///   just like [`Vec::swap_remove`]
+    pub fn swap_remove(&mut self, i: usize, j: usize) -> Option<usize>
+    {
+        let idx = self.index.remove(&ordered(i, j))?;
+        self.edges.swap_remove(idx);
+        if let Some(&moved) = self.edges.get(idx) {
+            self.index.insert(moved, idx);
+        }
+        Some(idx)
+    }
+}
+
+impl<'a> IntoIterator for &'a EdgeIndex {
+    type Item = &'a (usize, usize);
+    type IntoIter = std::slice::Iter<'a, (usize, usize)>;
+
+    fn into_iter(self) -> Self::IntoIter
+    {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EmptyNode, ErEnsembleC, Graph, IndexedGraph, SwEnsemble, traits::*};
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    fn check_edges<T, A>(graph: &crate::GenericGraph<T, A>)
+    where A: AdjContainer<T>
+    {
+        let edges: Vec<_> = graph.edges().collect();
+        assert_eq!(edges.len(), graph.edge_count());
+        let mut sorted = edges.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), edges.len());
+        for &(i, j) in edges.iter() {
+            assert!(i < j);
+            assert!(graph.container(i).is_adjacent(j));
+        }
+
+        let index = graph.edge_index();
+        assert_eq!(index.edges(), edges.as_slice());
+        for (idx, &(i, j)) in edges.iter().enumerate() {
+            assert_eq!(index.get(j, i), Some(idx));
+        }
+    }
+
+    #[test]
+    fn edges_once() {
+        let e = ErEnsembleC::<EmptyNode, Pcg64>::new(100, 4.0, Pcg64::seed_from_u64(1));
+        check_edges(e.graph());
+
+        let mut indexed: IndexedGraph<EmptyNode> = IndexedGraph::new(e.graph().vertex_count());
+        for (i, j) in e.graph().edges() {
+            indexed.add_edge(i, j).unwrap();
+        }
+        check_edges(&indexed);
+
+        let sw = SwEnsemble::<EmptyNode, Pcg64>::new(100, 0.2, Pcg64::seed_from_u64(2));
+        check_edges(sw.graph());
+        assert_eq!(sw.graph().root_edges().count(), sw.graph().edge_count());
+    }
+
+    #[test]
+    fn swap_remove_consistent() {
+        let mut graph = Graph::<EmptyNode>::new(6);
+        graph.init_ring(std::num::NonZeroUsize::new(2).unwrap()).unwrap();
+        let mut index = graph.edge_index();
+        let mut data: Vec<_> = index.iter().copied().collect();
+
+        for &(i, j) in &[(1, 0), (2, 4), (5, 4), (0, 4)] {
+            let idx = index.swap_remove(i, j).unwrap();
+            data.swap_remove(idx);
+            assert!(!index.contains(i, j));
+            assert_eq!(index.swap_remove(i, j), None);
+        }
+        assert_eq!(index.len(), graph.edge_count() - 4);
+        for (idx, &edge) in data.iter().enumerate() {
+            assert_eq!(index.edge(idx), Some(edge));
+            assert_eq!(index.get(edge.1, edge.0), Some(idx));
+        }
+
+        let idx = index.insert(4, 2).unwrap();
+        assert_eq!(idx, data.len());
+        assert_eq!(index.edge(idx), Some((2, 4)));
+        assert_eq!(index.insert(2, 4), None);
+    }
+}
@@ -1,5 +1,6 @@
 use{
     super::iterators::*,
+    super::edge_index::EdgeIndex,
     crate::{
         traits::*, 
         iter::*, 
@@ -145,6 +146,45 @@ where A: AdjContainer<T>
         }
     }
 
+    /// * replaces the edges of `self` by the edges of `other`
+    /// * `other` is not allowed to have more vertices than `self`
+    /// * the order of the adjacency lists is kept, if `A` supports it
+    pub(crate) fn reset_from_graph<A2>(&mut self, other: &GenericGraph<T, A2>)
+    where A2: AdjContainer<T>
+    {
+        assert!(other.vertex_count() <= self.vertex_count());
+        self.clear_edges();
+        let copied = self.vertices
+            .iter_mut()
+            .zip(other.container_iter())
+            .all(
+                |(container, source)|
+                unsafe { container.set_adj_unchecked(source.neighbors().copied()) }
+            );
+        if copied {
+            self.edge_count = other.edge_count();
+            return;
+        }
+        // not supported by the container: nothing was changed, as all containers are of the same type
+        for container in other.container_iter() {
+            let index = container.id();
+            for &neighbor in container.neighbors() {
+                if index < neighbor {
+                    self.add_edge_unchecked(index, neighbor);
+                }
+            }
+        }
+    }
+
+    /// * adds edge between `index1` and `index2`, without checking if it exists already
+    /// * only call this, if you know, that the edge does not exist
+    pub(crate) fn add_edge_unchecked(&mut self, index1: usize, index2: usize)
+    {
+        let (r1, r2) = self.get_2_mut(index1, index2);
+        unsafe{ r1.push_unchecked(r2); }
+        self.edge_count += 1;
+    }
+
     /// # initialize Ring
     /// * every node is connected with its neighbors, which are
     /// not more than distance away
@@ -523,6 +563,30 @@ where A: AdjContainer<T>
         )
     }
 
+    /// # Iterator over all edges
+    /// * every edge is returned exactly once as `(i, j)` with `i < j`
+    /// * order: ascending `i`, then order of the adjacency list of `i`
+    /// * to store data for every edge, see [`EdgeIndex`](crate::EdgeIndex)
+    pub fn edges(&'_ self) -> impl Iterator<Item=(usize, usize)> + '_
+    {
+        self.vertices
+            .iter()
+            .enumerate()
+            .flat_map(
+                |(i, container)|
+                container.neighbors()
+                    .filter(move |&&j| i < j)
+                    .map(move |&j| (i, j))
+            )
+    }
+
+    /// * indices for all edges in the order of [`edges`](#method.edges)
+    /// * see [`EdgeIndex`](crate::EdgeIndex)
+    pub fn edge_index(&self) -> EdgeIndex
+    {
+        EdgeIndex::new(self)
+    }
+
     /// # Iterator
     /// Iterate over the degrees of each node (in the order of the indices)
     pub fn degree_iter(&'_ self) -> impl Iterator<Item=usize> + '_
@@ -606,6 +670,11 @@ where A: AdjContainer<T>
         Dfs::new(self, index)
     }
 
+    /// * same as [`dfs`](#method.dfs), but uses the buffers of `workspace` instead of allocating
+    pub fn dfs_in<'a>(&'a self, index: usize, workspace: &'a mut TraversalWorkspace) -> Dfs<'a, T, A> {
+        Dfs::with_workspace(self, index, Workspace::Borrowed(workspace))
+    }
+
     /// # returns `Iterator`
     ///
     /// * the iterator will iterate over the vertices in depth first search order,
@@ -647,6 +716,11 @@ where A: AdjContainer<T>
         DfsWithIndex::new(self, index)
     }
 
+    /// * same as [`dfs_with_index`](#method.dfs_with_index), but uses the buffers of `workspace` instead of allocating
+    pub fn dfs_with_index_in<'a>(&'a self, index: usize, workspace: &'a mut TraversalWorkspace) -> DfsWithIndex<'a, T, A> {
+        DfsWithIndex::with_workspace(self, index, Workspace::Borrowed(workspace))
+    }
+
     /// # returns `Iterator`
     ///
     /// * the iterator will iterate over the vertices in breadth first search order,
@@ -672,6 +746,11 @@ where A: AdjContainer<T>
         Bfs::new(self, index)
     }
 
+    /// * same as [`bfs_index_depth`](#method.bfs_index_depth), but uses the buffers of `workspace` instead of allocating
+    pub fn bfs_index_depth_in<'a>(&'a self, index: usize, workspace: &'a mut TraversalWorkspace) -> Bfs<'a, T, A> {
+        Bfs::with_workspace(self, index, Workspace::Borrowed(workspace))
+    }
+
     /// # returns `Iterator`
     ///
     /// * the iterator will iterate over the vertices in breadth first search order,
@@ -727,6 +806,47 @@ where A: AdjContainer<T>
         BfsFiltered::new(self, index, filter)
     }
 
+    /// * same as [`bfs_filtered`](#method.bfs_filtered), but uses the buffers of `workspace` instead of allocating
+    pub fn bfs_filtered_in<'a, F>(&'a self, index: usize, filter: F, workspace: &'a mut TraversalWorkspace) -> Option<BfsFiltered<'a, T, A>>
+    where F: FnMut(&T, usize) -> bool,
+    {
+        BfsFiltered::with_workspace(self, index, filter, Workspace::Borrowed(workspace))
+    }
+
+    /// # Simple random walk
+    /// * returns iterator over the indices of the visited vertices,
+    ///   beginning with vertex `index`
+    /// * in each step, the walker moves to a neighbor drawn uniformly at random
+    /// * the walk stays forever at isolated vertices
+    /// * iterator always returns None if index out of bounds
+    /// * you can pass `&mut rng` to keep using your random number generator afterwards
+    /// # Example
+    /// ```
+    /// use net_ensembles::{Graph, EmptyNode, AdjContainer, rand::SeedableRng};
+    /// use rand_pcg::Pcg64;
+    ///
+    /// let graph = Graph::<EmptyNode>::complete_graph(5);
+    /// let mut rng = Pcg64::seed_from_u64(2389);
+    /// let walk: Vec<_> = graph.random_walk(0, &mut rng)
+    ///     .take(10)
+    ///     .collect();
+    /// assert_eq!(walk[0], 0);
+    /// assert!(walk.windows(2).all(|w| graph.container(w[0]).is_adjacent(w[1])));
+    /// ```
+    pub fn random_walk<R: Rng>(&self, index: usize, rng: R) -> RandomWalk<'_, T, A, R>
+    {
+        RandomWalk::new(self, index, 0.0, rng)
+    }
+
+    /// # Lazy random walk
+    /// * same as [`random_walk`](Self::random_walk), but in each step the walker
+    ///   stays at its current vertex with probability `stay_probability`
+    /// * the usual lazy random walk uses `stay_probability = 0.5`
+    pub fn lazy_random_walk<R: Rng>(&self, index: usize, stay_probability: f64, rng: R) -> RandomWalk<'_, T, A, R>
+    {
+        RandomWalk::new(self, index, stay_probability, rng)
+    }
+
     /// | result       |                          condition                       |
     /// |--------------|----------------------------------------------------------|
     /// | `None`       | **if** graph does not contain any vertices               |
@@ -740,6 +860,15 @@ where A: AdjContainer<T>
         }
     }
 
+    /// * same as [`is_connected`](#method.is_connected), but uses the buffers of `workspace` instead of allocating
+    pub fn is_connected_in(&self, workspace: &mut TraversalWorkspace) -> Option<bool> {
+        if self.vertex_count() == 0 {
+            None
+        } else {
+            Some(self.dfs_in(0, workspace).count() == self.vertex_count())
+        }
+    }
+
     /// # definition
     /// Calculates the size of the **q-core** (i.e. number of nodes in the biggest possible set of nodes,
     /// where all nodes from the set are connected with at least `q` other nodes from the set)
@@ -853,6 +982,13 @@ where A: AdjContainer<T>
     /// * each vertex gets an id, all vertices with the same id are in the same connected component
     /// * returns (number of components, vector of ids)
     pub fn connected_components_ids(&self) -> (usize, Vec<isize>)
+    {
+        self.connected_components_ids_in(&mut TraversalWorkspace::for_dfs(self.vertex_count()))
+    }
+
+    /// * same as [`connected_components_ids`](#method.connected_components_ids),
+    ///   but uses the buffers of `workspace` instead of allocating
+    pub fn connected_components_ids_in(&self, workspace: &mut TraversalWorkspace) -> (usize, Vec<isize>)
     {
         let mut component_id : Vec<isize> = vec![-1; self.vertex_count()];
         let mut current_id = 0;
@@ -864,7 +1000,7 @@ where A: AdjContainer<T>
             }
 
             // start depth first search over indices of vertices connected with vertex i
-            for (j, _) in self.dfs_with_index(i) {
+            for (j, _) in self.dfs_with_index_in(i, workspace) {
                 component_id[j] = current_id;
             }
             current_id += 1;
@@ -884,8 +1020,14 @@ where A: AdjContainer<T>
     /// * returns (reverse) **ordered vector of sizes** of the connected components,
     /// i.e. the biggest component is of size `result[0]` and the smallest is of size `result[result.len() - 1]`
     pub fn connected_components(&self) -> Vec<usize> {
+        self.connected_components_in(&mut TraversalWorkspace::for_dfs(self.vertex_count()))
+    }
+
+    /// * same as [`connected_components`](#method.connected_components), but uses the buffers of `workspace`
+    ///   for the traversals
+    pub fn connected_components_in(&self, workspace: &mut TraversalWorkspace) -> Vec<usize> {
 
-        let (num_components, component_id) = self.connected_components_ids();
+        let (num_components, component_id) = self.connected_components_ids_in(workspace);
 
         let mut result = vec![0; num_components];
 
@@ -1114,14 +1256,21 @@ where A: AdjContainer<T>
     /// * returns `None` **if** graph not connected **or** does not contain any vertices
     /// * uses repeated breadth first search
     pub fn diameter(&self) -> Option<usize> {
-        if !self.is_connected()? {
+        // depth first search for connectivity, then breadth first searches
+        let n = self.vertex_count();
+        self.diameter_in(&mut TraversalWorkspace::sized(n, n, n / 2))
+    }
+
+    /// * same as [`diameter`](#method.diameter), but uses the buffers of `workspace` instead of allocating
+    pub fn diameter_in(&self, workspace: &mut TraversalWorkspace) -> Option<usize> {
+        if !self.is_connected_in(workspace)? {
             None
         } else {
             // well, then calculate from every node
             // (except 1 node) and use maximum found
             
             let mut max = 0;
-            let mut bfs = self.bfs_index_depth(0);
+            let mut bfs = self.bfs_index_depth_in(0, workspace);
             for index in 1..self.vertex_count() {
                 let mut depth = 0;
                 bfs.reuse(index);
@@ -1143,6 +1292,14 @@ where A: AdjContainer<T>
         Some(depth)
     }
 
+    /// * same as [`longest_shortest_path_from_index`](#method.longest_shortest_path_from_index),
+    ///   but uses the buffers of `workspace` instead of allocating
+    pub fn longest_shortest_path_from_index_in(&self, index: usize, workspace: &mut TraversalWorkspace) -> Option<usize> {
+        let (.., depth) = self.bfs_index_depth_in(index, workspace)
+                            .last()?;
+        Some(depth)
+    }
+
     /// # calculate sizes of all binode connected components
     /// * returns (reverse) **ordered vector of sizes**
     /// i.e. the biggest component is of size `result[0]` and the smallest is of size `result[result.len() - 1]`
@@ -1235,14 +1392,14 @@ where A: AdjContainer<T>
                             if low[top_vertex] == number[next_vertex]{
                                 let mut tmp_component: Vec<(usize, usize)> = Vec::new();
 
-                                while let Some(current_edge) = edge_stack.last() {
-                                    if number[current_edge.1] < number[next_vertex]
-                                    || number[current_edge.0] < number[next_vertex]
-                                    {
+                                // pop edges up to and including the tree edge leading to top_vertex.
+                                // Comparing the numbers of the vertices instead would also remove
+                                // edges of earlier subtrees of next_vertex
+                                while let Some(current_edge) = edge_stack.pop() {
+                                    tmp_component.push(current_edge);
+                                    if current_edge == (next_vertex, top_vertex) {
                                         break;
                                     }
-                                    tmp_component.push(*current_edge);
-                                    edge_stack.pop();
                                 }
                                 // add to biconnected_components
                                 if !tmp_component.is_empty(){
@@ -1308,87 +1465,255 @@ where A: AdjContainer<T>
     /// > M. E. J. Newman, "Erratum: Scientific collaboration networks. II. Shortest paths, weighted networks, and centrality",
     /// > Phys. Rev. E **73**, 039906, 2006, DOI: [10.1103/PhysRevE.73.039906](https://doi.org/10.1103/PhysRevE.73.039906)
     pub fn vertex_load(&self, include_endpoints: bool) -> Vec<f64> {
+        let mut buffers = VertexLoadBuffers::new(self.vertex_count());
+        let mut b = vec![0.0; self.vertex_count()];
+        for i in 0..self.vertex_count() {
+            self.vertex_load_from(i, include_endpoints, &mut buffers, &mut b);
+        }
+        b
+    }
 
-        let mut queue0 = VecDeque::with_capacity(self.vertex_count());
-        let mut queue1 = VecDeque::with_capacity(self.vertex_count());
+    /// adds the vertex_load resulting from the shortest paths starting at vertex `i` to `b`
+    pub(crate) fn vertex_load_from(
+        &self,
+        i: usize,
+        include_endpoints: bool,
+        buffers: &mut VertexLoadBuffers,
+        b: &mut [f64]
+    )
+    {
+        buffers.add_load_from(
+            |index| self.container(index).neighbors(),
+            i,
+            include_endpoints,
+            b
+        );
+    }
+
+    /// # Edge betweenness
+    /// ## calculates the load of every edge in O(edges * vertices)
+    /// * defined as how many shortest paths pass through each edge.
+    ///   If there are multiple shortest paths between two vertices, each of them
+    ///   is weighted with `1/(number of shortest paths)`
+    /// * every pair of vertices is counted in both directions, analogous to
+    ///   [`vertex_load`](`Self::vertex_load`)
+    /// * returns vector of `((i, j), load)` with `i < j`. Each edge is contained exactly once.
+    ///   The order is: ascending `i`, then order of the adjacency list of `i`
+    /// # Citations
+    /// I used the algorithm described in
+    /// > U. Brandes, "A faster algorithm for betweenness centrality",
+    /// > J. Math. Sociol. **25**, 163-177, 2001, DOI: [10.1080/0022250X.2001.9990249](https://doi.org/10.1080/0022250X.2001.9990249)
+    pub fn edge_load(&self) -> Vec<((usize, usize), f64)> {
+        let edge_index = self.edge_index();
+        let mut load: Vec<_> = edge_index.iter()
+            .map(|&edge| (edge, 0.0))
+            .collect();
+
+        let mut queue = VecDeque::with_capacity(self.vertex_count());
         let mut ordering: Vec<usize> = Vec::with_capacity(self.vertex_count());
-        let mut b = vec![0.0; self.vertex_count()];
-        let mut b_k = vec![1f64; self.vertex_count()];
+        let mut sigma = vec![0.0; self.vertex_count()];
+        let mut delta = vec![0.0; self.vertex_count()];
         let mut distance: Vec<Option<usize>> = vec![None; self.vertex_count()];
         let mut predecessor: Vec<Vec<usize>> = vec![Vec::new(); self.vertex_count()];
-        
 
-        for i in 0..self.vertex_count() {
-            
-            // initialize without allocation
-            if i > 0 {
-                for j in 0..self.vertex_count()
-                {
-                    b_k[j] = 1.0;
-                    distance[j] = None;
-                    // clear predecessors, way more efficient then new allocation
-                    predecessor[j].clear();
-                }
+        for source in 0..self.vertex_count() {
+            for j in 0..self.vertex_count() {
+                sigma[j] = 0.0;
+                delta[j] = 0.0;
+                distance[j] = None;
+                predecessor[j].clear();
             }
-            
-
-            let mut depth = 0;
-            queue0.push_back(i);
-            distance[i] = Some(depth);
 
-
-            // build up predecessor and ordering information
-            while let Some(index) = queue0.pop_front() {
-                ordering.push(index); // to get indices in reverse order of distance
-                let container = self.container(index);
-                for &neighbor in container.neighbors() {
-                    if let Some(d) = distance[neighbor] {
-                        if d == depth + 1 {
-                            predecessor[neighbor].push(index);
-                        }
-                    }
-                    // None
-                    else {
-                        distance[neighbor] = Some(depth + 1);
-                        queue1.push_back(neighbor);
-                        predecessor[neighbor].push(index);
+            sigma[source] = 1.0;
+            distance[source] = Some(0);
+            queue.push_back(source);
+
+            // count shortest paths and remember predecessors
+            while let Some(index) = queue.pop_front() {
+                ordering.push(index);
+                let depth = distance[index].unwrap() + 1;
+                for &neighbor in self.container(index).neighbors() {
+                    match distance[neighbor] {
+                        None => {
+                            distance[neighbor] = Some(depth);
+                            queue.push_back(neighbor);
+                        },
+                        Some(d) if d != depth => continue,
+                        _ => ()
                     }
-                }
-                if queue0.is_empty() {
-                    std::mem::swap(&mut queue0, &mut queue1);
-                    depth += 1;
+                    sigma[neighbor] += sigma[index];
+                    predecessor[neighbor].push(index);
                 }
             }
 
-            // calculate vertex_load resulting from the shortest paths starting at vertex i
+            // accumulate dependencies in reverse order of distance
             while let Some(index) = ordering.pop() {
-                // skip last vertex
-                if ordering.is_empty(){
-                    break;
+                for &pred in predecessor[index].iter() {
+                    let fraction = sigma[pred] / sigma[index] * (1.0 + delta[index]);
+                    load[edge_index.get(pred, index).unwrap()].1 += fraction;
+                    delta[pred] += fraction;
                 }
-                // add number of shortest path to total count
+            }
+        }
+        load
+    }
 
-                b[index] += b_k[index];
-                if !include_endpoints {
-                    b[index] -= 1.0;
-                }
+    /// # Stationary distribution of the simple random walk
+    /// * `pi_i = k_i / (2 * edge_count)`, where `k_i` is the degree of vertex `i`
+    /// * also the stationary distribution of every lazy random walk
+    /// * for disconnected graphs, this is one of many stationary distributions
+    /// * returns vector of `NaN` if the graph has no edges
+    pub fn stationary_distribution(&self) -> Vec<f64>
+    {
+        let norm = 2.0 * self.edge_count() as f64;
+        self.vertices
+            .iter()
+            .map(|container| container.degree() as f64 / norm)
+            .collect()
+    }
 
+    /// # Exact distribution of a random walk
+    /// * probability to find a walker, which started at vertex `index`, at each vertex after `steps` steps
+    /// * `stay_probability`: probability to stay at the current vertex in each step,
+    ///   see [`lazy_random_walk`](Self::lazy_random_walk)
+    /// * complexity `O(steps * (vertex_count + edge_count))`
+    /// * **panics** if `index` is out of bounds
+    pub fn random_walk_distribution(&self, index: usize, steps: usize, stay_probability: f64) -> Vec<f64>
+    {
+        let mut probability = vec![0.0; self.vertex_count()];
+        probability[index] = 1.0;
+        let mut next = vec![0.0; self.vertex_count()];
+        for _ in 0..steps {
+            self.random_walk_transition(&probability, &mut next, stay_probability);
+            std::mem::swap(&mut probability, &mut next);
+        }
+        probability
+    }
+
+    /// applies transition matrix of the (lazy) random walk
+    fn random_walk_transition(&self, probability: &[f64], next: &mut [f64], stay_probability: f64)
+    {
+        next.iter_mut().for_each(|p| *p = 0.0);
+        for (index, container) in self.vertices.iter().enumerate() {
+            let p = probability[index];
+            if p == 0.0 {
+                continue;
+            }
+            let degree = container.degree();
+            if degree == 0 {
+                next[index] += p;
+                continue;
+            }
+            next[index] += stay_probability * p;
+            let move_p = (1.0 - stay_probability) * p / degree as f64;
+            for &neighbor in container.neighbors() {
+                next[neighbor] += move_p;
+            }
+        }
+    }
 
-                let fraction = b_k[index] / predecessor[index].len() as f64;
-                for pred in predecessor[index].iter() {
-                    b_k[*pred] += fraction;
+    /// # Mixing time of the lazy random walk
+    /// * smallest number of steps `t`, such that the total variation distance between the
+    ///   distribution of a lazy random walk (`stay_probability = 0.5`) after `t` steps and the
+    ///   [stationary distribution](Self::stationary_distribution) is at most `epsilon`
+    ///   for every start vertex
+    /// * the distributions are calculated exactly, complexity
+    ///   `O(vertex_count * t * (vertex_count + edge_count))`
+    /// * returns `None` if the mixing time is larger than `max_steps`, which is always
+    ///   the case for disconnected graphs, or if the graph has no edges
+    pub fn mixing_time(&self, epsilon: f64, max_steps: usize) -> Option<usize>
+    {
+        if self.edge_count() == 0 {
+            return None;
+        }
+        let stationary = self.stationary_distribution();
+        let mut probability = vec![0.0; self.vertex_count()];
+        let mut next = vec![0.0; self.vertex_count()];
+        let mut mixing_time = 0;
+        for start in 0..self.vertex_count() {
+            probability.iter_mut().for_each(|p| *p = 0.0);
+            probability[start] = 1.0;
+            for t in 0.. {
+                let distance = 0.5 * probability.iter()
+                    .zip(stationary.iter())
+                    .map(|(p, pi)| (p - pi).abs())
+                    .sum::<f64>();
+                if distance <= epsilon {
+                    mixing_time = mixing_time.max(t);
+                    break;
                 }
+                if t == max_steps {
+                    return None;
+                }
+                self.random_walk_transition(&probability, &mut next, 0.5);
+                std::mem::swap(&mut probability, &mut next);
             }
+        }
+        Some(mixing_time)
+    }
 
+    /// # Monte Carlo estimate of the mean first-passage time
+    /// * average number of steps a simple random walk, starting at `from`, needs to reach `to`
+    /// * averaged over `samples` random walks
+    /// * returns `None` if `to` cannot be reached from `from`, or if `samples == 0`
+    /// * **panics** if `from` or `to` are out of bounds
+    pub fn mean_first_passage_time<R: Rng>(&self, from: usize, to: usize, samples: usize, rng: &mut R) -> Option<f64>
+    {
+        assert!(from < self.vertex_count() && to < self.vertex_count(), "index out of bounds");
+        if samples == 0 || !self.dfs_with_index(from).any(|(index, _)| index == to) {
+            return None;
         }
-        b
+        let mut sum = 0_usize;
+        for _ in 0..samples {
+            sum += self.random_walk(from, &mut *rng)
+                .position(|index| index == to)
+                .unwrap();
+        }
+        Some(sum as f64 / samples as f64)
+    }
+
+    /// # Monte Carlo estimate of the mean cover time
+    /// * average number of steps a simple random walk, starting at `index`, needs to visit every vertex
+    /// * averaged over `samples` random walks
+    /// * returns `None` if the graph is not connected, or if `samples == 0`
+    /// * **panics** if `index` is out of bounds
+    pub fn mean_cover_time<R: Rng>(&self, index: usize, samples: usize, rng: &mut R) -> Option<f64>
+    {
+        assert!(index < self.vertex_count(), "index out of bounds");
+        if samples == 0 || self.is_connected() != Some(true) {
+            return None;
+        }
+        let mut visited = vec![false; self.vertex_count()];
+        let mut sum = 0_usize;
+        for _ in 0..samples {
+            visited.iter_mut().for_each(|v| *v = false);
+            let mut remaining = self.vertex_count();
+            for (step, vertex) in self.random_walk(index, &mut *rng).enumerate() {
+                if !visited[vertex] {
+                    visited[vertex] = true;
+                    remaining -= 1;
+                    if remaining == 0 {
+                        sum += step;
+                        break;
+                    }
+                }
+            }
+        }
+        Some(sum as f64 / samples as f64)
     }
 
     pub fn closeness_centrality(&self) -> Vec<f64>
+    {
+        self.closeness_centrality_in(&mut TraversalWorkspace::for_bfs(self.vertex_count()))
+    }
+
+    /// * same as [`closeness_centrality`](#method.closeness_centrality),
+    ///   but uses the buffers of `workspace` instead of allocating
+    pub fn closeness_centrality_in(&self, workspace: &mut TraversalWorkspace) -> Vec<f64>
     {
         let mut count = vec![0; self.vertex_count()];
 
-        let mut bfs = Bfs::new(self, 0);
+        let mut bfs = self.bfs_index_depth_in(0, workspace);
         for i in 0..self.vertex_count()
         {
             bfs.reuse(i);
@@ -1600,4 +1925,100 @@ where T: Node,
         }
         write!(writer, "}}")
     }
-}
\ No newline at end of file
+}
+
+/// reusable buffers for the breadth first searches of `vertex_load`
+pub(crate) struct VertexLoadBuffers {
+    queue0: VecDeque<usize>,
+    queue1: VecDeque<usize>,
+    ordering: Vec<usize>,
+    b_k: Vec<f64>,
+    distance: Vec<Option<usize>>,
+    predecessor: Vec<Vec<usize>>,
+}
+
+impl VertexLoadBuffers {
+    pub(crate) fn new(vertex_count: usize) -> Self
+    {
+        Self{
+            queue0: VecDeque::with_capacity(vertex_count),
+            queue1: VecDeque::with_capacity(vertex_count),
+            ordering: Vec::with_capacity(vertex_count),
+            b_k: vec![1f64; vertex_count],
+            distance: vec![None; vertex_count],
+            predecessor: vec![Vec::new(); vertex_count],
+        }
+    }
+
+    /// adds the vertex_load resulting from the shortest paths starting at vertex `i` to `b`
+    /// * `neighbors` returns the neighbors of a vertex, which makes this usable
+    ///   for every graph representation
+    pub(crate) fn add_load_from<'a, F, I>(
+        &mut self,
+        neighbors: F,
+        i: usize,
+        include_endpoints: bool,
+        b: &mut [f64]
+    )
+    where F: Fn(usize) -> I,
+          I: IntoIterator<Item = &'a usize>
+    {
+        let VertexLoadBuffers{queue0, queue1, ordering, b_k, distance, predecessor} = self;
+
+        // initialize without allocation
+        for j in 0..b_k.len()
+        {
+            b_k[j] = 1.0;
+            distance[j] = None;
+            // clear predecessors, way more efficient then new allocation
+            predecessor[j].clear();
+        }
+
+        let mut depth = 0;
+        queue0.push_back(i);
+        distance[i] = Some(depth);
+
+
+        // build up predecessor and ordering information
+        while let Some(index) = queue0.pop_front() {
+            ordering.push(index); // to get indices in reverse order of distance
+            for &neighbor in neighbors(index) {
+                if let Some(d) = distance[neighbor] {
+                    if d == depth + 1 {
+                        predecessor[neighbor].push(index);
+                    }
+                }
+                // None
+                else {
+                    distance[neighbor] = Some(depth + 1);
+                    queue1.push_back(neighbor);
+                    predecessor[neighbor].push(index);
+                }
+            }
+            if queue0.is_empty() {
+                std::mem::swap(queue0, queue1);
+                depth += 1;
+            }
+        }
+
+        // calculate vertex_load resulting from the shortest paths starting at vertex i
+        while let Some(index) = ordering.pop() {
+            // skip last vertex
+            if ordering.is_empty(){
+                break;
+            }
+            // add number of shortest path to total count
+
+            b[index] += b_k[index];
+            if !include_endpoints {
+                b[index] -= 1.0;
+            }
+
+
+            let fraction = b_k[index] / predecessor[index].len() as f64;
+            for pred in predecessor[index].iter() {
+                b_k[*pred] += fraction;
+            }
+        }
+    }
+}
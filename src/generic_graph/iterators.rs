@@ -5,10 +5,133 @@ use {
     },
     std::{
         marker::PhantomData,
-        collections::VecDeque
-    }
+        collections::VecDeque,
+        ops::{Deref, DerefMut}
+    },
+    rand::Rng
 };
 
+/// # Reusable buffers for graph traversals
+/// * breadth and depth first searches need to mark visited vertices and
+///   need a queue or a stack. Usually these are allocated for every traversal
+/// * if you perform many traversals, e.g., after every step of a Markov chain,
+///   keep one workspace and pass it to the `*_in` variants of the traversals and measurements,
+///   e.g., [`dfs_in`](crate::GenericGraph::dfs_in), [`bfs_index_depth_in`](crate::GenericGraph::bfs_index_depth_in),
+///   [`is_connected_in`](crate::GenericGraph::is_connected_in) or [`diameter_in`](crate::GenericGraph::diameter_in)
+/// * the workspace grows to the size of the largest graph it was used for
+/// ```
+/// use net_ensembles::{Graph, EmptyNode, TraversalWorkspace};
+///
+/// let graph = Graph::<EmptyNode>::complete_graph(10);
+/// let mut workspace = TraversalWorkspace::new();
+/// for _ in 0..100 {
+///     assert_eq!(graph.is_connected_in(&mut workspace), Some(true));
+///     assert_eq!(graph.diameter_in(&mut workspace), Some(1));
+/// }
+/// assert_eq!(graph.bfs_index_depth_in(0, &mut workspace).count(), 10);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TraversalWorkspace {
+    handled: Vec<bool>,
+    queue0: VecDeque<usize>,
+    queue1: VecDeque<usize>,
+    stack: Vec<usize>,
+}
+
+impl TraversalWorkspace {
+    /// create empty workspace, it allocates on first use
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// create workspace, which does not need to allocate for graphs with up to `vertex_count` vertices
+    pub fn with_capacity(vertex_count: usize) -> Self
+    {
+        Self::sized(vertex_count, vertex_count, vertex_count)
+    }
+
+    /// create workspace with capacity `stack` for the stack and `queue` for each of the queues.
+    /// Used for the traversals, that allocate their own workspace,
+    /// as these only need the buffers of one kind of traversal
+    pub(crate) fn sized(vertex_count: usize, stack: usize, queue: usize) -> Self
+    {
+        Self{
+            handled: Vec::with_capacity(vertex_count),
+            queue0: VecDeque::with_capacity(queue),
+            queue1: VecDeque::with_capacity(queue),
+            stack: Vec::with_capacity(stack),
+        }
+    }
+
+    /// buffers of a depth first search
+    pub(crate) fn for_dfs(vertex_count: usize) -> Self
+    {
+        Self::sized(vertex_count, vertex_count, 0)
+    }
+
+    /// buffers of a breadth first search, the queues grow if needed
+    pub(crate) fn for_bfs(vertex_count: usize) -> Self
+    {
+        Self::sized(vertex_count, 0, vertex_count / 2)
+    }
+
+    /// prepare for a new traversal of a graph with `vertex_count` vertices
+    fn reset(&mut self, vertex_count: usize)
+    {
+        self.handled.clear();
+        self.handled.resize(vertex_count, false);
+        self.queue0.clear();
+        self.queue1.clear();
+        self.stack.clear();
+    }
+
+    /// marks first vertex of the traversal as handled, returns `false` if `index` is out of bounds
+    fn start(&mut self, index: usize) -> bool
+    {
+        let valid = index < self.handled.len();
+        if valid {
+            self.handled[index] = true;
+        }
+        valid
+    }
+}
+
+/// workspace of a traversal, either owned by the iterator or borrowed
+pub(crate) enum Workspace<'a> {
+    Owned(TraversalWorkspace),
+    Borrowed(&'a mut TraversalWorkspace),
+}
+
+impl<'a> Workspace<'a> {
+    fn owned(workspace: TraversalWorkspace) -> Self
+    {
+        Workspace::Owned(workspace)
+    }
+}
+
+impl<'a> Deref for Workspace<'a> {
+    type Target = TraversalWorkspace;
+
+    fn deref(&self) -> &TraversalWorkspace
+    {
+        match self {
+            Workspace::Owned(workspace) => workspace,
+            Workspace::Borrowed(workspace) => workspace,
+        }
+    }
+}
+
+impl<'a> DerefMut for Workspace<'a> {
+    fn deref_mut(&mut self) -> &mut TraversalWorkspace
+    {
+        match self {
+            Workspace::Owned(workspace) => workspace,
+            Workspace::Borrowed(workspace) => workspace,
+        }
+    }
+}
+
 
 /// Depth first search Iterator
 pub struct Dfs<'a, T, A>
@@ -16,8 +139,7 @@ where   T: 'a,
         A: AdjContainer<T>
 {
         vertices: &'a [A],
-        handled: Vec<bool>,
-        stack: Vec<usize>,
+        workspace: Workspace<'a>,
         marker: PhantomData<T>
 }
 
@@ -27,19 +149,18 @@ where   T: 'a,
         A: AdjContainer<T>
 {
     pub(crate) fn new(graph: &'a GenericGraph<T, A>, index: usize) -> Self {
-        let mut handled: Vec<bool> = vec![false; graph.vertex_count()];
-        let mut stack: Vec<usize> = Vec::with_capacity(graph.vertex_count());
-        
-        if index < handled.len()
-        {
-            stack.push(index);
-            handled[index] = true;
+        Self::with_workspace(graph, index, Workspace::owned(TraversalWorkspace::for_dfs(graph.vertex_count())))
+    }
+
+    pub(crate) fn with_workspace(graph: &'a GenericGraph<T, A>, index: usize, mut workspace: Workspace<'a>) -> Self {
+        workspace.reset(graph.vertex_count());
+        if workspace.start(index) {
+            workspace.stack.push(index);
         }
 
         Dfs {
             vertices: graph.vertices.as_slice(),
-            handled,
-            stack,
+            workspace,
             marker: PhantomData
         }
     }
@@ -52,19 +173,20 @@ where   T: 'a,
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let index = self.stack.pop()?;
+        let workspace = &mut *self.workspace;
+        let index = workspace.stack.pop()?;
         let container = &self.vertices[index];
         for &i in container.neighbors() {
-            if !self.handled[i] {
-                self.handled[i] = true;
-                self.stack.push(i);
+            if !workspace.handled[i] {
+                workspace.handled[i] = true;
+                workspace.stack.push(i);
             }
         }
         Some(container.contained())
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.stack.len(), Some(self.handled.len()))
+        (self.workspace.stack.len(), Some(self.vertices.len()))
     }
 }
 
@@ -149,8 +271,7 @@ where   T: 'a,
         A: AdjContainer<T>
 {
         vertices: &'a [A],
-        handled: Vec<bool>,
-        stack: Vec<usize>,
+        workspace: Workspace<'a>,
         marker: PhantomData<T>
 }
 
@@ -160,19 +281,18 @@ impl<'a, T, A> DfsWithIndex<'a, T, A>
 {
 
     pub(crate) fn new(graph: &'a GenericGraph<T, A>, index: usize) -> Self {
-        let mut handled: Vec<bool> = vec![false; graph.vertex_count()];
-        let mut stack: Vec<usize> = Vec::with_capacity(graph.vertex_count());
-        
-        if index < handled.len()
-        {
-            stack.push(index);
-            handled[index] = true;
+        Self::with_workspace(graph, index, Workspace::owned(TraversalWorkspace::for_dfs(graph.vertex_count())))
+    }
+
+    pub(crate) fn with_workspace(graph: &'a GenericGraph<T, A>, index: usize, mut workspace: Workspace<'a>) -> Self {
+        workspace.reset(graph.vertex_count());
+        if workspace.start(index) {
+            workspace.stack.push(index);
         }
-        
+
         DfsWithIndex {
             vertices: graph.vertices.as_slice(),
-            handled,
-            stack,
+            workspace,
             marker: PhantomData
         }
     }
@@ -186,19 +306,20 @@ where   T: 'a,
         type Item = (usize, &'a T);
 
         fn next(&mut self) -> Option<Self::Item> {
-            let index = self.stack.pop()?;
+            let workspace = &mut *self.workspace;
+            let index = workspace.stack.pop()?;
             let container = &self.vertices[index];
             for &i in container.neighbors() {
-                if !self.handled[i] {
-                    self.handled[i] = true;
-                    self.stack.push(i);
+                if !workspace.handled[i] {
+                    workspace.handled[i] = true;
+                    workspace.stack.push(i);
                 }
             }
             Some((index, container.contained()))
         }
 
         fn size_hint(&self) -> (usize, Option<usize>) {
-            (self.stack.len(), Some(self.vertices.len()))
+            (self.workspace.stack.len(), Some(self.vertices.len()))
         }
 }
 
@@ -210,9 +331,7 @@ where   T: 'a,
         A: AdjContainer<T>
 {
         vertices: &'a [A],
-        handled: Vec<bool>,
-        queue0: VecDeque<usize>,
-        queue1: VecDeque<usize>,
+        workspace: Workspace<'a>,
         depth: usize,
         marker: PhantomData<T>
 }
@@ -222,36 +341,26 @@ where   T: 'a,
         A: AdjContainer<T>
 {
         pub(crate) fn new(graph: &'a GenericGraph<T, A>, index: usize) -> Self {
-            let mut handled= vec![false; graph.vertex_count()];
-            let mut queue0 = VecDeque::with_capacity(graph.vertex_count() / 2);
-            let queue1 = VecDeque::with_capacity(graph.vertex_count() / 2);
-            
-            if index < graph.vertex_count() {
-                queue0.push_back(index);
-                handled[index] = true;
-            }
+            Self::with_workspace(graph, index, Workspace::owned(TraversalWorkspace::for_bfs(graph.vertex_count())))
+        }
 
-            Bfs {
+        pub(crate) fn with_workspace(graph: &'a GenericGraph<T, A>, index: usize, workspace: Workspace<'a>) -> Self {
+            let mut bfs = Bfs {
                 vertices: graph.vertices.as_slice(),
-                handled,
-                queue0,
-                queue1,
+                workspace,
                 depth: 0,
                 marker: PhantomData
-            }
+            };
+            bfs.reuse(index);
+            bfs
         }
 
         pub(crate) fn reuse(&mut self, index: usize) {
-
-            self.handled.fill(false);
- 
-            self.queue0.clear();
-            self.queue1.clear();
+            self.workspace.reset(self.vertices.len());
             self.depth = 0;
 
-            if index < self.vertices.len() {
-                self.queue0.push_back(index);
-                self.handled[index] = true;
+            if self.workspace.start(index) {
+                self.workspace.queue0.push_back(index);
             }
         }
 }
@@ -265,27 +374,28 @@ where   T: 'a,
 {
         type Item = (usize, &'a T, usize);
         fn next(&mut self) -> Option<Self::Item> {
+            let workspace = &mut *self.workspace;
             // if queue0 is not empty, take element from queue, push neighbors to other queue
-            if let Some(index) = self.queue0.pop_front() {
+            if let Some(index) = workspace.queue0.pop_front() {
                 let container = &self.vertices[index];
                 for &i in container.neighbors() {
-                    if !self.handled[i] {
-                        self.handled[i] = true;
-                        self.queue1.push_back(i);
+                    if !workspace.handled[i] {
+                        workspace.handled[i] = true;
+                        workspace.queue1.push_back(i);
                     }
                 }
                 Some((index, container.contained(), self.depth))
-            } else if self.queue1.is_empty() {
+            } else if workspace.queue1.is_empty() {
                 None
             } else {
-                std::mem::swap(&mut self.queue0, &mut self.queue1);
+                std::mem::swap(&mut workspace.queue0, &mut workspace.queue1);
                 self.depth += 1;
                 self.next()
             }
         }
 
         fn size_hint(&self) -> (usize, Option<usize>) {
-            (self.queue0.len() + self.queue1.len(), Some(self.vertices.len()))
+            (self.workspace.queue0.len() + self.workspace.queue1.len(), Some(self.vertices.len()))
         }
 }
 
@@ -371,9 +481,7 @@ where   T: 'a,
         A: AdjContainer<T>,
 {
         vertices: &'a [A],
-        handled: Vec<bool>,
-        queue0: VecDeque<usize>,
-        queue1: VecDeque<usize>,
+        workspace: Workspace<'a>,
         depth: usize,
         marker: PhantomData<T>
 }
@@ -383,32 +491,42 @@ where   T: 'a,
         A: AdjContainer<T>,
         
 {
-    pub(crate) fn new<F>(graph: &'a GenericGraph<T, A>, index: usize, mut filter: F) -> Option<Self>
+    pub(crate) fn new<F>(graph: &'a GenericGraph<T, A>, index: usize, filter: F) -> Option<Self>
+    where F: FnMut(&T, usize) -> bool,
+    {
+        Self::with_workspace(graph, index, filter, Workspace::owned(TraversalWorkspace::for_bfs(graph.vertex_count())))
+    }
+
+    pub(crate) fn with_workspace<F>(
+        graph: &'a GenericGraph<T, A>,
+        index: usize,
+        mut filter: F,
+        mut workspace: Workspace<'a>
+    ) -> Option<Self>
     where F: FnMut(&T, usize) -> bool,
     {
         if index >= graph.vertex_count() || !filter(graph.at(index), index) {
             return None;
         }
-        let mut handled: Vec<_> =  graph.container_iter()
+        workspace.reset(graph.vertex_count());
+        workspace.handled
+            .iter_mut()
+            .zip(graph.container_iter())
             .enumerate()
-            .map(
-                |(index, state)|
+            .for_each(
+                |(index, (handled, state))|
                 {
-                    !filter(state.contained(), index)
+                    *handled = !filter(state.contained(), index);
                 }
-            ).collect();
-        let mut queue0 = VecDeque::with_capacity(graph.vertex_count() / 2);
-        let queue1 = VecDeque::with_capacity(graph.vertex_count() / 2);
+            );
         
-        queue0.push_back(index);
-        handled[index] = true;
+        workspace.queue0.push_back(index);
+        workspace.handled[index] = true;
 
         Some(
             Self{
-                handled,
                 vertices: graph.vertices.as_slice(),
-                queue0,
-                queue1,
+                workspace,
                 depth: 0,
                 marker: PhantomData
             }
@@ -420,13 +538,15 @@ where   T: 'a,
     /// * **panics** if index is out of bounds
     pub fn is_handled(&self, index: usize) -> bool
     {
-        self.handled[index]
+        self.workspace.handled[index]
     }
 
     /// Efficiently reuse the iterator, now possibly starting at a new index
     /// * returns Err(self) without changing self, if index out of Bounds 
     /// or filter (filter_fn) of (vertex_at_index, index) is false
     /// * otherwise: prepares iterator to be used again and returns Ok(self)
+    // returns the iterator itself in both cases, the size of the error is intended
+    #[allow(clippy::result_large_err)]
     pub fn reuse<F>(mut self, index: usize, mut filter: F) -> Result<Self, Self>
     where F: FnMut(&T, usize) -> bool,
     {
@@ -434,7 +554,8 @@ where   T: 'a,
             return Err(self);
         }
 
-        self.handled
+        let workspace = &mut *self.workspace;
+        workspace.handled
             .iter_mut()
             .zip(
                 self.vertices.iter()
@@ -446,16 +567,16 @@ where   T: 'a,
                 }
             );
 
-        for i in 0..self.handled.len() {
-            self.handled[i] = false;
+        for i in 0..workspace.handled.len() {
+            workspace.handled[i] = false;
         }
-        self.queue0.clear();
-        self.queue1.clear();
+        workspace.queue0.clear();
+        workspace.queue1.clear();
         self.depth = 0;
 
         
-        self.queue0.push_back(index);
-        self.handled[index] = true;
+        workspace.queue0.push_back(index);
+        workspace.handled[index] = true;
         Ok(self)
     }
 }
@@ -466,33 +587,117 @@ where   T: 'a,
 {
     type Item = (usize, &'a T, usize);
     fn next(&mut self) -> Option<Self::Item> {
+        let workspace = &mut *self.workspace;
         // if queue0 is not empty, take element from queue, push neighbors to other queue
-        if let Some(index) = self.queue0.pop_front() {
+        if let Some(index) = workspace.queue0.pop_front() {
             let container = self.vertices.get(index)?;
             for &i in container.neighbors() {
-                if self.handled[i]
+                if workspace.handled[i]
                 {
                     continue;
                 }
                 
-                self.handled[i] = true;
-                self.queue1.push_back(i);
+                workspace.handled[i] = true;
+                workspace.queue1.push_back(i);
                 
             }
             Some((index, container.contained(), self.depth))
-        } else if self.queue1.is_empty() {
+        } else if workspace.queue1.is_empty() {
             None
         } else {
-            std::mem::swap(&mut self.queue0, &mut self.queue1);
+            std::mem::swap(&mut workspace.queue0, &mut workspace.queue1);
             self.depth += 1;
             self.next()
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.queue0.len() + self.queue1.len(), Some(self.vertices.len()))
+        (self.workspace.queue0.len() + self.workspace.queue1.len(), Some(self.vertices.len()))
     }
 }
 
 
 
+
+/// # Random walk iterator
+/// * iterator returns the index of the current vertex, starting with the first vertex
+/// * in each step the walker stays at its current vertex with probability `stay_probability`
+///   (lazy random walk), otherwise it moves to a neighbor drawn uniformly at random
+/// * the walker stays forever at isolated vertices
+/// * the iterator never ends, unless the first vertex is out of bounds
+pub struct RandomWalk<'a, T, A, R>
+where   T: 'a,
+        A: AdjContainer<T>
+{
+        vertices: &'a [A],
+        current: Option<usize>,
+        started: bool,
+        stay_probability: f64,
+        rng: R,
+        marker: PhantomData<T>
+}
+
+impl<'a, T, A, R> RandomWalk<'a, T, A, R>
+where   T: 'a,
+        A: AdjContainer<T>,
+        R: Rng
+{
+        pub(crate) fn new(graph: &'a GenericGraph<T, A>, index: usize, stay_probability: f64, rng: R) -> Self {
+            let current = if index < graph.vertex_count() {
+                Some(index)
+            } else {
+                None
+            };
+
+            RandomWalk {
+                vertices: graph.vertices.as_slice(),
+                current,
+                started: false,
+                stay_probability,
+                rng,
+                marker: PhantomData
+            }
+        }
+
+        /// # Index of the current vertex
+        /// * `None` if the first vertex was out of bounds
+        pub fn current(&self) -> Option<usize> {
+            self.current
+        }
+}
+
+impl<'a, T, A, R> Iterator for RandomWalk<'a, T, A, R>
+where   T: 'a,
+        A: AdjContainer<T>,
+        R: Rng
+{
+        type Item = usize;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let index = self.current?;
+            if !self.started {
+                self.started = true;
+                return Some(index);
+            }
+            let container = &self.vertices[index];
+            let degree = container.degree();
+            if degree == 0
+                || (self.stay_probability > 0.0 && self.rng.gen::<f64>() < self.stay_probability)
+            {
+                return Some(index);
+            }
+            let which = self.rng.gen_range(0..degree);
+            let next = *container.neighbors()
+                .nth(which)
+                .unwrap();
+            self.current = Some(next);
+            Some(next)
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            match self.current {
+                None => (0, Some(0)),
+                Some(_) => (usize::MAX, None)
+            }
+        }
+}
@@ -0,0 +1,405 @@
+use{
+    super::{generic_graph::*, edge_index::EdgeIndex},
+    crate::{
+        traits::*,
+        graph::{NodeContainer, Graph}
+    },
+    std::marker::PhantomData
+};
+
+// assemble graph from node data and (symmetric) adjacency lists
+fn from_adjacency<T>(nodes: Vec<T>, adj: Vec<Vec<usize>>, edge_count: usize) -> Graph<T>
+{
+    debug_assert_eq!(nodes.len(), adj.len());
+    debug_assert_eq!(adj.iter().map(Vec::len).sum::<usize>(), 2 * edge_count);
+    let vertices: Vec<_> = nodes.into_iter()
+        .zip(adj)
+        .enumerate()
+        .map(
+            |(id, (node, adj))|
+            NodeContainer{
+                id,
+                adj,
+                node
+            }
+        ).collect();
+    GenericGraph{
+        next_id: vertices.len(),
+        edge_count,
+        vertices,
+        phantom: PhantomData
+    }
+}
+
+/// # Graph operations
+/// * create new graphs derived from `self` (and another graph)
+/// * like in [`clone_topology`](#method.clone_topology), the closure `map`
+///   creates the contained data of the new vertices from the old ones
+impl<T, A> GenericGraph<T, A>
+where A: AdjContainer<T>
+{
+    /// # Complement graph
+    /// * vertex `i` of the complement corresponds to vertex `i` of `self`
+    /// * `i` and `j` (`i != j`) are adjacent in the complement, **iff** they are not adjacent in `self`
+    /// * independent sets of `self` are cliques of the complement and vice versa
+    /// # Example
+    /// ```
+    /// use net_ensembles::{Graph, EmptyNode, AdjContainer};
+    ///
+    /// let mut graph = Graph::<EmptyNode>::new(4);
+    /// graph.add_edge(0, 1).unwrap();
+    ///
+    /// let complement = graph.complement(|_| EmptyNode{});
+    /// assert_eq!(complement.edge_count(), 5);
+    /// assert!(!complement.container(0).is_adjacent(1));
+    /// assert!(complement.container(0).is_adjacent(2));
+    /// ```
+    pub fn complement<F, T2>(&self, mut map: F) -> Graph<T2>
+    where F: FnMut(&T) -> T2
+    {
+        let n = self.vertex_count();
+        let mut is_neighbor = vec![false; n];
+        let mut adj = Vec::with_capacity(n);
+        for (i, container) in self.vertices.iter().enumerate() {
+            for &j in container.neighbors() {
+                is_neighbor[j] = true;
+            }
+            let mut list = Vec::with_capacity(n - 1 - container.degree());
+            list.extend(
+                (0..n).filter(|&j| j != i && !is_neighbor[j])
+            );
+            for &j in container.neighbors() {
+                is_neighbor[j] = false;
+            }
+            adj.push(list);
+        }
+        let nodes = self.vertices
+            .iter()
+            .map(|container| map(container.contained()))
+            .collect();
+        let edge_count = n * n.saturating_sub(1) / 2 - self.edge_count();
+        from_adjacency(nodes, adj, edge_count)
+    }
+
+    /// # Line graph
+    /// * every edge of `self` becomes a vertex of the line graph, vertex `k` corresponds
+    ///   to the `k`-th edge of [`edges`](#method.edges), i.e., to
+    ///   [`self.edge_index().edge(k)`](crate::EdgeIndex::edge)
+    /// * two vertices of the line graph are adjacent, **iff** the corresponding
+    ///   edges share a vertex
+    /// * `map(i, j)` is called with the contained data of both end points of the edge `(i, j)`
+    /// * matchings of `self` are independent sets of the line graph
+    /// # Example
+    /// ```
+    /// use net_ensembles::{Graph, EmptyNode, AdjContainer};
+    ///
+    /// // star with 3 leafs
+    /// let mut star = Graph::<EmptyNode>::new(4);
+    /// for i in 1..4 {
+    ///     star.add_edge(0, i).unwrap();
+    /// }
+    ///
+    /// // line graph is a triangle
+    /// let line = star.line_graph(|_, _| EmptyNode{});
+    /// assert_eq!(line.vertex_count(), 3);
+    /// assert_eq!(line.edge_count(), 3);
+    /// ```
+    pub fn line_graph<F, T2>(&self, mut map: F) -> Graph<T2>
+    where F: FnMut(&T, &T) -> T2
+    {
+        let edge_index = EdgeIndex::new(self);
+        let mut incident = vec![Vec::new(); self.vertex_count()];
+        for (k, &(i, j)) in edge_index.iter().enumerate() {
+            incident[i].push(k);
+            incident[j].push(k);
+        }
+
+        let adj = edge_index.iter()
+            .enumerate()
+            .map(
+                |(k, &(i, j))|
+                incident[i].iter()
+                    .chain(incident[j].iter())
+                    .copied()
+                    .filter(|&other| other != k)
+                    .collect()
+            ).collect();
+        let nodes = edge_index.iter()
+            .map(
+                |&(i, j)|
+                map(self.at(i), self.at(j))
+            ).collect();
+        let edge_count = incident.iter()
+            .map(|edges| edges.len() * edges.len().saturating_sub(1) / 2)
+            .sum();
+        from_adjacency(nodes, adj, edge_count)
+    }
+
+    /// # Cartesian product
+    /// * vertex `(i, j)`, i.e., vertex `i` of `self` and vertex `j` of `other`,
+    ///   has the index `i * other.vertex_count() + j`
+    /// * `(i, j)` and `(i2, j2)` are adjacent, **iff** either `i == i2` and `j`, `j2` are adjacent in `other`,
+    ///   or `j == j2` and `i`, `i2` are adjacent in `self`
+    /// * e.g., the product of two chains is a square lattice,
+    ///   the product of two rings is a square lattice with periodic boundary conditions
+    /// # Example
+    /// ```
+    /// use net_ensembles::{Graph, EmptyNode, AdjContainer};
+    ///
+    /// let mut ring = Graph::<EmptyNode>::new(4);
+    /// for i in 0..4 {
+    ///     ring.add_edge(i, (i + 1) % 4).unwrap();
+    /// }
+    ///
+    /// let torus = ring.cartesian_product(&ring, |_, _| EmptyNode{});
+    /// assert_eq!(torus.vertex_count(), 16);
+    /// assert!(torus.degree_iter().all(|degree| degree == 4));
+    /// // (1, 2) is adjacent to (1, 3)
+    /// assert!(torus.container(1 * 4 + 2).is_adjacent(1 * 4 + 3));
+    /// ```
+    pub fn cartesian_product<F, T2, A2, T3>(&self, other: &GenericGraph<T2, A2>, mut map: F) -> Graph<T3>
+    where F: FnMut(&T, &T2) -> T3,
+          A2: AdjContainer<T2>
+    {
+        let n2 = other.vertex_count();
+        let mut adj = Vec::with_capacity(self.vertex_count() * n2);
+        let mut nodes = Vec::with_capacity(self.vertex_count() * n2);
+        for c1 in self.vertices.iter() {
+            for (j, c2) in other.vertices.iter().enumerate() {
+                let mut list = Vec::with_capacity(c1.degree() + c2.degree());
+                list.extend(
+                    c1.neighbors()
+                        .map(|&i2| i2 * n2 + j)
+                );
+                let offset = c1.id() * n2;
+                list.extend(
+                    c2.neighbors()
+                        .map(|&j2| offset + j2)
+                );
+                adj.push(list);
+                nodes.push(map(c1.contained(), c2.contained()));
+            }
+        }
+        let edge_count = self.vertex_count() * other.edge_count()
+            + n2 * self.edge_count();
+        from_adjacency(nodes, adj, edge_count)
+    }
+
+    /// # Tensor product
+    /// * also known as categorical, direct or Kronecker product
+    /// * vertex `(i, j)`, i.e., vertex `i` of `self` and vertex `j` of `other`,
+    ///   has the index `i * other.vertex_count() + j`
+    /// * `(i, j)` and `(i2, j2)` are adjacent, **iff** `i`, `i2` are adjacent in `self`
+    ///   **and** `j`, `j2` are adjacent in `other`
+    /// # Example
+    /// ```
+    /// use net_ensembles::{Graph, EmptyNode, AdjContainer};
+    ///
+    /// let mut edge = Graph::<EmptyNode>::new(2);
+    /// edge.add_edge(0, 1).unwrap();
+    ///
+    /// let mut triangle = Graph::<EmptyNode>::new(3);
+    /// triangle.add_edge(0, 1).unwrap();
+    /// triangle.add_edge(1, 2).unwrap();
+    /// triangle.add_edge(2, 0).unwrap();
+    ///
+    /// // bipartite double cover of the triangle: ring of length 6
+    /// let product = triangle.tensor_product(&edge, |_, _| EmptyNode{});
+    /// assert_eq!(product.edge_count(), 6);
+    /// assert!(product.is_connected().unwrap());
+    /// assert!(product.degree_iter().all(|degree| degree == 2));
+    /// ```
+    pub fn tensor_product<F, T2, A2, T3>(&self, other: &GenericGraph<T2, A2>, mut map: F) -> Graph<T3>
+    where F: FnMut(&T, &T2) -> T3,
+          A2: AdjContainer<T2>
+    {
+        let n2 = other.vertex_count();
+        let mut adj = Vec::with_capacity(self.vertex_count() * n2);
+        let mut nodes = Vec::with_capacity(self.vertex_count() * n2);
+        for c1 in self.vertices.iter() {
+            for c2 in other.vertices.iter() {
+                let mut list = Vec::with_capacity(c1.degree() * c2.degree());
+                for &i2 in c1.neighbors() {
+                    let offset = i2 * n2;
+                    list.extend(
+                        c2.neighbors()
+                            .map(|&j2| offset + j2)
+                    );
+                }
+                adj.push(list);
+                nodes.push(map(c1.contained(), c2.contained()));
+            }
+        }
+        let edge_count = 2 * self.edge_count() * other.edge_count();
+        from_adjacency(nodes, adj, edge_count)
+    }
+
+    /// # Disjoint union
+    /// * vertices `0..self.vertex_count()` correspond to the vertices of `self`,
+    ///   their contained data is created by `map_self`
+    /// * vertex `self.vertex_count() + j` corresponds to vertex `j` of `other`,
+    ///   its contained data is created by `map_other`
+    /// * there are no edges between both parts
+    /// # Example
+    /// ```
+    /// use net_ensembles::{Graph, EmptyNode, AdjContainer};
+    ///
+    /// let mut edge = Graph::<EmptyNode>::new(2);
+    /// edge.add_edge(0, 1).unwrap();
+    ///
+    /// let union = edge.disjoint_union(&edge, |_| EmptyNode{}, |_| EmptyNode{});
+    /// assert_eq!(union.vertex_count(), 4);
+    /// assert_eq!(union.edge_count(), 2);
+    /// assert!(union.container(2).is_adjacent(3));
+    /// assert_eq!(union.connected_components(), vec![2, 2]);
+    /// ```
+    pub fn disjoint_union<F, G, T2, A2, T3>(
+        &self,
+        other: &GenericGraph<T2, A2>,
+        mut map_self: F,
+        mut map_other: G
+    ) -> Graph<T3>
+    where F: FnMut(&T) -> T3,
+          G: FnMut(&T2) -> T3,
+          A2: AdjContainer<T2>
+    {
+        let offset = self.vertex_count();
+        let total = offset + other.vertex_count();
+        let mut adj = Vec::with_capacity(total);
+        let mut nodes = Vec::with_capacity(total);
+        for container in self.vertices.iter() {
+            adj.push(container.neighbors().copied().collect());
+            nodes.push(map_self(container.contained()));
+        }
+        for container in other.vertices.iter() {
+            adj.push(
+                container.neighbors()
+                    .map(|&j| offset + j)
+                    .collect()
+            );
+            nodes.push(map_other(container.contained()));
+        }
+        from_adjacency(nodes, adj, self.edge_count() + other.edge_count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EmptyNode, ErEnsembleC, Graph, traits::*};
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    // chain, every vertex contains its index
+    fn chain(n: usize) -> Graph<usize> {
+        let mut graph = Graph::<EmptyNode>::new(n);
+        for i in 1..n {
+            graph.add_edge(i - 1, i).unwrap();
+        }
+        let mut index = 0;
+        graph.clone_topology(
+            |_|
+            {
+                index += 1;
+                index - 1
+            }
+        )
+    }
+
+    fn assert_consistent<T>(graph: &Graph<T>) {
+        let degree_sum: usize = graph.degree_iter().sum();
+        assert_eq!(degree_sum, 2 * graph.edge_count());
+        for (i, j) in graph.edges() {
+            assert!(graph.container(j).is_adjacent(i));
+        }
+        for i in 0..graph.vertex_count() {
+            assert!(!graph.container(i).is_adjacent(i));
+            let mut neighbors: Vec<_> = graph.container(i).neighbors().collect();
+            let len = neighbors.len();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            assert_eq!(len, neighbors.len());
+        }
+    }
+
+    #[test]
+    fn complement_twice() {
+        let e = ErEnsembleC::<EmptyNode, Pcg64>::new(50, 5.0, Pcg64::seed_from_u64(4));
+        let graph = e.graph();
+        let complement = graph.complement(|_| EmptyNode{});
+        assert_consistent(&complement);
+        for (i, j) in graph.edges() {
+            assert!(!complement.container(i).is_adjacent(j));
+        }
+        let twice = complement.complement(|_| EmptyNode{});
+        assert_consistent(&twice);
+        assert_eq!(twice.edge_count(), graph.edge_count());
+        for (i, j) in twice.edges() {
+            assert!(graph.container(i).is_adjacent(j));
+        }
+
+        assert_eq!(Graph::<EmptyNode>::new(0).complement(|_| EmptyNode{}).vertex_count(), 0);
+    }
+
+    #[test]
+    fn line_graph() {
+        let e = ErEnsembleC::<EmptyNode, Pcg64>::new(50, 4.0, Pcg64::seed_from_u64(5));
+        let graph = e.graph();
+        let index = graph.edge_index();
+        let line = graph.line_graph(|_, _| EmptyNode{});
+        assert_consistent(&line);
+        assert_eq!(line.vertex_count(), graph.edge_count());
+        for (k, l) in line.edges() {
+            let (a, b) = index.edge(k).unwrap();
+            let (c, d) = index.edge(l).unwrap();
+            assert!(a == c || a == d || b == c || b == d);
+        }
+
+        // node data from the end points
+        let chain = chain(4);
+        let line = chain.line_graph(|&a, &b| a + b);
+        assert_eq!((0..3).map(|k| *line.at(k)).collect::<Vec<_>>(), vec![1, 3, 5]);
+        assert_eq!(line.edge_count(), 2);
+    }
+
+    #[test]
+    fn products() {
+        let a = chain(3);
+        let b = chain(4);
+        let grid = a.cartesian_product(&b, |&i, &j| (i, j));
+        assert_consistent(&grid);
+        assert_eq!(grid.vertex_count(), 12);
+        // 3 rows with 3 edges, 4 columns with 2 edges
+        assert_eq!(grid.edge_count(), 3 * 3 + 4 * 2);
+        // vertex (1, 2)
+        assert_eq!(grid.at(4 + 2), &(1, 2));
+        assert_eq!(grid.diameter(), Some(2 + 3));
+
+        let e = ErEnsembleC::<EmptyNode, Pcg64>::new(20, 3.0, Pcg64::seed_from_u64(6));
+        let tensor = e.graph().tensor_product(&b, |_, &j| j);
+        assert_consistent(&tensor);
+        for i in 0..e.graph().vertex_count() {
+            for j in 0..b.vertex_count() {
+                assert_eq!(
+                    tensor.container(i * 4 + j).degree(),
+                    e.graph().container(i).degree() * b.container(j).degree()
+                );
+                assert_eq!(tensor.at(i * 4 + j), &j);
+            }
+        }
+    }
+
+    #[test]
+    fn disjoint_union() {
+        let a = chain(3);
+        let e = ErEnsembleC::<EmptyNode, Pcg64>::new(20, 3.0, Pcg64::seed_from_u64(7));
+        let union = a.disjoint_union(e.graph(), |&i| i, |_| 100);
+        assert_consistent(&union);
+        assert_eq!(union.vertex_count(), 23);
+        assert_eq!(union.edge_count(), 2 + e.graph().edge_count());
+        assert_eq!(union.at(2), &2);
+        assert_eq!(union.at(3), &100);
+        for (i, j) in e.graph().edges() {
+            assert!(union.container(i + 3).is_adjacent(j + 3));
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use{
+    super::generic_graph::*,
+    crate::traits::*,
+    rayon::prelude::*
+};
+
+// sources per task. Fixed, so that the floating point results
+// do not depend on the number of threads
+const SOURCES_PER_TASK: usize = 32;
+// tasks per block. The partial results of a block are added in order,
+// before the next block starts
+const CHUNKS_PER_BLOCK: usize = 32;
+
+/// # Parallel measurements
+/// * requires feature `parallel`
+/// * the all-pairs algorithms below start one breadth first search from every vertex,
+///   these searches are distributed over the threads of the rayon thread pool
+impl<T, A> GenericGraph<T, A>
+where T: Sync,
+      A: AdjContainer<T> + Sync
+{
+    /// # Parallel version of [`vertex_load`](#method.vertex_load)
+    /// * result may differ from `vertex_load` due to rounding, as the
+    ///   contributions of the vertices are summed in a different order.
+    ///   It does not depend on the number of threads though
+    pub fn par_vertex_load(&self, include_endpoints: bool) -> Vec<f64>
+    {
+        let n = self.vertex_count();
+        let sources: Vec<_> = (0..n).collect();
+        // one accumulator per task of a block, reused for every block,
+        // so that the memory does not grow with the number of sources
+        let mut partials = vec![vec![0.0; n]; CHUNKS_PER_BLOCK.min(n.div_ceil(SOURCES_PER_TASK))];
+        let mut load = vec![0.0; n];
+        for block in sources.chunks(SOURCES_PER_TASK * CHUNKS_PER_BLOCK) {
+            let used = block.len().div_ceil(SOURCES_PER_TASK);
+            partials[..used].par_iter_mut()
+                .zip(block.par_chunks(SOURCES_PER_TASK))
+                .for_each_init(
+                    || VertexLoadBuffers::new(n),
+                    |buffers, (b, chunk)|
+                    {
+                        b.iter_mut().for_each(|val| *val = 0.0);
+                        for &i in chunk {
+                            self.vertex_load_from(i, include_endpoints, buffers, b);
+                        }
+                    }
+                );
+            // add the partial results in chunk order
+            let partials = &partials[..used];
+            load.par_iter_mut()
+                .enumerate()
+                .for_each(
+                    |(index, total)|
+                    {
+                        for b in partials {
+                            *total += b[index];
+                        }
+                    }
+                );
+        }
+        load
+    }
+
+    /// # Parallel version of [`closeness_centrality`](#method.closeness_centrality)
+    /// * same result as `closeness_centrality`
+    pub fn par_closeness_centrality(&self) -> Vec<f64>
+    {
+        let n = self.vertex_count();
+        let sources: Vec<_> = (0..n).collect();
+        let count = sources.par_chunks(SOURCES_PER_TASK)
+            .map_init(
+                || self.bfs_index_depth(0),
+                |bfs, chunk|
+                {
+                    let mut count = vec![0; n];
+                    for &i in chunk {
+                        bfs.reuse(i);
+                        for (index, _, depth) in &mut *bfs {
+                            count[index] += depth;
+                        }
+                    }
+                    count
+                }
+            ).reduce(
+                || vec![0; n],
+                |mut total, count|
+                {
+                    total.iter_mut()
+                        .zip(count)
+                        .for_each(|(total, count)| *total += count);
+                    total
+                }
+            );
+        let val = (n as f64) - 1.0;
+        count.into_iter()
+            .map(|count| val / count as f64)
+            .collect()
+    }
+
+    /// # Parallel version of [`diameter`](#method.diameter)
+    /// * returns `None` **if** graph not connected **or** does not contain any vertices
+    pub fn par_diameter(&self) -> Option<usize>
+    {
+        if !self.is_connected()? {
+            return None;
+        }
+        let max = (1..self.vertex_count()).into_par_iter()
+            .map_init(
+                || self.bfs_index_depth(0),
+                |bfs, index|
+                {
+                    bfs.reuse(index);
+                    bfs.by_ref()
+                        .last()
+                        .map_or(0, |(.., depth)| depth)
+                }
+            ).max()
+            .unwrap_or(0);
+        Some(max)
+    }
+
+    /// # Parallel version of [`transitivity`](#method.transitivity)
+    /// * same result as `transitivity`, i.e., `NaN` if there are no paths of length two
+    pub fn par_transitivity(&self) -> f64
+    {
+        let (closed_path_count, path_count) = (0..self.vertex_count())
+            .into_par_iter()
+            .map(
+                |source_index|
+                {
+                    let mut path_count: usize = 0;
+                    let mut closed_path_count: usize = 0;
+                    for neighbor_1 in self.container(source_index).neighbors() {
+                        for neighbor_2 in self
+                                            .container(*neighbor_1)
+                                            .neighbors()
+                                            .filter(|&i| *i != source_index)
+                        {
+                            if self
+                                .container(*neighbor_2)
+                                .is_adjacent(source_index)
+                            {
+                                closed_path_count += 1;
+                            }
+                            path_count += 1;
+                        }
+                    }
+                    (closed_path_count, path_count)
+                }
+            ).reduce(
+                || (0, 0),
+                |a, b| (a.0 + b.0, a.1 + b.1)
+            );
+        closed_path_count as f64 / path_count as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EmptyNode, ErEnsembleC, Graph, traits::*};
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    #[test]
+    fn same_as_sequential() {
+        let e = ErEnsembleC::<EmptyNode, Pcg64>::new(200, 3.0, Pcg64::seed_from_u64(3));
+        let graph = e.graph();
+        let load = graph.vertex_load(true);
+        let par_load = graph.par_vertex_load(true);
+        assert!(load.iter().zip(par_load.iter()).all(|(a, b)| (a - b).abs() <= 1e-9 * a.abs().max(1.0)));
+        assert_eq!(par_load, graph.par_vertex_load(true));
+        let load = graph.vertex_load(false);
+        let par_load = graph.par_vertex_load(false);
+        assert!(load.iter().zip(par_load.iter()).all(|(a, b)| (a - b).abs() <= 1e-9 * a.abs().max(1.0)));
+
+        assert_eq!(graph.par_transitivity().to_bits(), graph.transitivity().to_bits());
+        assert_eq!(graph.par_diameter(), graph.diameter());
+
+        let (_, ids) = graph.connected_components_ids();
+        // component of vertex 0
+        let members = (0..graph.vertex_count())
+            .filter(|&i| ids[i] == 0)
+            .collect::<Vec<_>>();
+        let component = graph.cloned_subgraph(members).unwrap();
+        assert!(component.par_diameter().is_some());
+        assert_eq!(component.par_diameter(), component.diameter());
+        assert_eq!(component.par_closeness_centrality(), component.closeness_centrality());
+
+        let empty = Graph::<EmptyNode>::new(0);
+        assert_eq!(empty.par_diameter(), None);
+        assert!(empty.par_vertex_load(true).is_empty());
+    }
+
+    #[test]
+    fn load_independent_of_threads() {
+        // more sources than fit into one block
+        let e = ErEnsembleC::<EmptyNode, Pcg64>::new(1500, 2.5, Pcg64::seed_from_u64(8));
+        let graph = e.graph();
+        let load = graph.vertex_load(false);
+        let loads: Vec<_> = [1, 3, 8].iter()
+            .map(
+                |&threads|
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap()
+                    .install(|| graph.par_vertex_load(false))
+            ).collect();
+        assert!(load.iter().zip(loads[0].iter()).all(|(a, b)| (a - b).abs() <= 1e-9 * a.abs().max(1.0)));
+        assert_eq!(loads[0], loads[1]);
+        assert_eq!(loads[0], loads[2]);
+    }
+}
@@ -109,6 +109,22 @@ impl<T> AdjContainer<T> for NodeContainer<T> {
         Ok(())
     }
 
+    #[doc(hidden)]
+    unsafe fn push_unchecked(&mut self, other: &mut Self)
+    {
+        self.adj.push(other.id());
+        other.adj.push(self.id);
+    }
+
+    #[doc(hidden)]
+    unsafe fn set_adj_unchecked<I>(&mut self, neighbors: I) -> bool
+    where I: Iterator<Item=usize>
+    {
+        self.adj.clear();
+        self.adj.extend(neighbors);
+        true
+    }
+
     /// Tries to remove edges, returns error `GraphErrors::EdgeDoesNotExist` if impossible
     #[doc(hidden)]
     unsafe fn remove(&mut self, other: &mut Self)
@@ -149,6 +165,17 @@ impl<T> AdjList<usize> for NodeContainer<T>{
     }
 }
 
+#[cfg(feature = "serde_support")]
+impl<T> crate::snapshot::SnapshotContainer<T> for NodeContainer<T> {
+    type Extra = ();
+
+    fn snapshot_extra(&self) {}
+
+    fn from_snapshot(id: usize, node: T, adj: Vec<usize>, _: ()) -> Option<Self> {
+        Some(Self{id, adj, node})
+    }
+}
+
 /// # Contains the topology and **implements functions** for analyzing topology
 /// used for graph ensembles
 /// # Example:
@@ -354,16 +381,71 @@ where T: Node
             phantom: PhantomData::<T>,
         }
     }
+}
 
-    pub(crate) fn reset_from_graph(&mut self, other: &Self)
+impl<T> Graph<T>
+{
+    /// # Adds a vertex
+    /// * the new vertex contains `node` and has no edges
+    /// * returns the index of the new vertex, i.e., the old `vertex_count()`
+    pub fn add_vertex(&mut self, node: T) -> usize
+    {
+        let index = self.vertices.len();
+        self.vertices.push(NodeContainer::new(index, node));
+        self.next_id += 1;
+        index
+    }
+
+    /// # Removes a vertex and all of its edges
+    /// * the last vertex is moved to `index` (swap remove), all other vertices keep their index
+    /// * returns what was contained in the removed vertex and the old index of the moved vertex,
+    ///   which is `None` if the removed vertex was the last one
+    /// * **panics** if index out of bounds
+    /// ```
+    /// use net_ensembles::{Graph, EmptyNode, AdjContainer};
+    ///
+    /// let mut graph = Graph::<EmptyNode>::complete_graph(4);
+    /// let (_, moved) = graph.remove_vertex(1);
+    /// // vertex 3 is now vertex 1
+    /// assert_eq!(moved, Some(3));
+    /// assert_eq!(graph.vertex_count(), 3);
+    /// assert_eq!(graph.edge_count(), 3);
+    /// assert!(graph.container(1).is_adjacent(2));
+    /// ```
+    pub fn remove_vertex(&mut self, index: usize) -> (T, Option<usize>)
     {
-        assert!(other.vertex_count() <= self.vertex_count());
-        self.clear_edges();
-        for i in 0..other.vertex_count(){
-            self.vertices[i].adj.extend_from_slice(other.vertices[i].adj.as_slice());
+        assert!(
+            index < self.vertex_count(),
+            "net_ensembles - panic - index out of bounds! vertex_count: {}, index: {}",
+            self.vertex_count(),
+            index
+        );
+        let neighbors = std::mem::take(&mut self.vertices[index].adj);
+        for &neighbor in neighbors.iter() {
+            self.vertices[neighbor].swap_remove_element(index);
         }
-        self.edge_count = other.edge_count;
+        self.edge_count -= neighbors.len();
 
+        let last = self.vertices.len() - 1;
+        let removed = self.vertices.swap_remove(index);
+        self.next_id -= 1;
+        if index == last {
+            return (removed.node, None);
+        }
+
+        // the moved vertex gets the index of the removed vertex
+        let moved = &mut self.vertices[index];
+        moved.id = index;
+        let adj = std::mem::take(&mut moved.adj);
+        for &neighbor in adj.iter() {
+            let entry = self.vertices[neighbor].adj
+                .iter_mut()
+                .find(|other| **other == last)
+                .expect("remove_vertex ERROR 0");
+            *entry = index;
+        }
+        self.vertices[index].adj = adj;
+        (removed.node, Some(last))
     }
 }
 
@@ -430,5 +512,62 @@ mod tests {
         assert_eq!(g.vertex_count(), 50);
     }
 
+    #[test]
+    fn add_and_remove_vertices() {
+        use rand::SeedableRng;
+        use rand_pcg::Pcg64;
+
+        let mut rng = Pcg64::seed_from_u64(2390);
+        let mut graph = Graph::<EmptyNode>::new(0);
+        // index of vertex in graph -> label
+        let mut labels = Vec::new();
+        // edges, as labels
+        let mut edges = Vec::new();
+        let mut next_label = 0;
+        for round in 0..300 {
+            if round % 3 == 2 {
+                let index = rng.gen_range(0..graph.vertex_count());
+                let (_, moved) = graph.remove_vertex(index);
+                let label = labels.swap_remove(index);
+                edges.retain(|&(a, b)| a != label && b != label);
+                assert_eq!(moved.is_some(), index < labels.len());
+            } else {
+                let index = graph.add_vertex(EmptyNode::new_from_index(0));
+                assert_eq!(index, labels.len());
+                labels.push(next_label);
+                next_label += 1;
+                for _ in 0..2 {
+                    let other = rng.gen_range(0..graph.vertex_count());
+                    if other != index && graph.add_edge(index, other).is_ok() {
+                        edges.push((labels[index], labels[other]));
+                    }
+                }
+            }
+
+            // rebuilding the graph has to give the same graph and therefore the same measurements
+            let mut expected = Graph::<EmptyNode>::new(labels.len());
+            for &(a, b) in edges.iter() {
+                let i = labels.iter().position(|&l| l == a).unwrap();
+                let j = labels.iter().position(|&l| l == b).unwrap();
+                expected.add_edge(i, j).unwrap();
+            }
+            assert_eq!(graph.vertex_count(), expected.vertex_count());
+            assert_eq!(graph.edge_count(), expected.edge_count());
+            for (c, e) in graph.container_iter().zip(expected.container_iter()) {
+                assert_eq!(c.id(), e.id());
+                let mut adj = c.adj.clone();
+                let mut expected_adj = e.adj.clone();
+                adj.sort_unstable();
+                expected_adj.sort_unstable();
+                assert_eq!(adj, expected_adj);
+            }
+            assert_eq!(graph.connected_components(), expected.connected_components());
+            assert_eq!(graph.diameter(), expected.diameter());
+            assert_eq!(graph.leaf_count(), expected.leaf_count());
+            assert_eq!(graph.transitivity().to_bits(), expected.transitivity().to_bits());
+        }
+        assert!(graph.vertex_count() > 50);
+    }
+
 
 }
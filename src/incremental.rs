@@ -0,0 +1,622 @@
+//! # Incremental observables
+//! Wang-Landau or entropic sampling need the energy of the graph after every markov step.
+//! Instead of evaluating it from scratch, i.e., `O(vertices + edges)` or worse,
+//! the observables of this module are updated with the edges changed by the step,
+//! which costs about the degree of the affected vertices.
+//!
+//! * all observables implement [`IncrementalObservable`]
+//! * the steps of the ensembles report their changes via [`EdgeChanges`]
+//! * [`ConnectivityTracker`](crate::connectivity::ConnectivityTracker) is an
+//!   incremental observable as well, its value is the number of connected components
+//!
+//! # Example
+//! ```
+//! use net_ensembles::{EmptyNode, ErEnsembleC, traits::*};
+//! use net_ensembles::incremental::*;
+//! use rand_pcg::Pcg64;
+//! use net_ensembles::rand::SeedableRng;
+//!
+//! let rng = Pcg64::seed_from_u64(7650);
+//! let mut e = ErEnsembleC::<EmptyNode, Pcg64>::new(50, 4.0, rng);
+//!
+//! let mut leafs = LeafCount::new(e.graph());
+//! let mut transitivity = Transitivity::new(e.graph());
+//! for _ in 0..100 {
+//!     let step = e.m_step();
+//!     leafs.track_step(e.graph(), &step);
+//!     transitivity.track_step(e.graph(), &step);
+//!
+//!     // reject steps, which result in less than 10 triangles
+//!     if transitivity.triangle_count() < 10 {
+//!         e.undo_step_quiet(&step);
+//!         leafs.track_undo(e.graph(), &step);
+//!         transitivity.track_undo(e.graph(), &step);
+//!     }
+//! }
+//! assert_eq!(leafs.value(), e.graph().leaf_count());
+//! assert_eq!(transitivity.value().to_bits(), e.graph().transitivity().to_bits());
+//! ```
+use crate::{
+    traits::*,
+    GenericGraph,
+    connectivity::{EdgeChanges, ConnectivityTracker}
+};
+
+/// # Observable, which is updated with the changes of every markov step
+/// * see [module documentation](crate::incremental)
+/// * the observable has to be informed about **every** change of the topology,
+///   after `randomize` or other changes, which are not tracked, call [`reset`](#tymethod.reset)
+pub trait IncrementalObservable {
+    /// type of the observable
+    type Value;
+
+    /// * recalculates the observable for `graph` from scratch
+    fn reset<T, A>(&mut self, graph: &GenericGraph<T, A>)
+    where A: AdjContainer<T>;
+
+    /// * call **after** the edges `added` were added to and the edges `removed` were removed from the graph
+    /// * `graph` is the resulting graph
+    fn update<T, A>(&mut self, graph: &GenericGraph<T, A>, added: &[(usize, usize)], removed: &[(usize, usize)])
+    where A: AdjContainer<T>;
+
+    /// current value of the observable
+    fn value(&self) -> Self::Value;
+
+    /// * call **after** `step` was performed, `graph` is the resulting graph
+    fn track_step<T, A, S>(&mut self, graph: &GenericGraph<T, A>, step: &S)
+    where A: AdjContainer<T>,
+          S: EdgeChanges
+    {
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        step.edge_changes(&mut added, &mut removed);
+        self.update(graph, &added, &removed);
+    }
+
+    /// * call **after** `step` was undone, `graph` is the resulting graph
+    fn track_undo<T, A, S>(&mut self, graph: &GenericGraph<T, A>, step: &S)
+    where A: AdjContainer<T>,
+          S: EdgeChanges
+    {
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        step.edge_changes(&mut added, &mut removed);
+        self.update(graph, &removed, &added);
+    }
+}
+
+/// calls `f(degree_before, degree_after)` once for every vertex, whose degree was changed
+fn for_each_degree_change<T, A, F>(
+    graph: &GenericGraph<T, A>,
+    added: &[(usize, usize)],
+    removed: &[(usize, usize)],
+    mut f: F
+)
+where A: AdjContainer<T>,
+      F: FnMut(usize, usize)
+{
+    let endpoints = || added.iter()
+        .chain(removed.iter())
+        .flat_map(|&(i, j)| [i, j]);
+    let incident = |edges: &[(usize, usize)], vertex: usize| edges.iter()
+        .filter(|&&(i, j)| i == vertex || j == vertex)
+        .count();
+
+    for (k, vertex) in endpoints().enumerate() {
+        // only the first occurrence counts
+        if endpoints().take(k).any(|other| other == vertex) {
+            continue;
+        }
+        let after = graph.container(vertex).degree();
+        let before = after + incident(removed, vertex) - incident(added, vertex);
+        if before != after {
+            f(before, after);
+        }
+    }
+}
+
+/// # Number of edges
+/// * `O(1)` per step
+#[derive(Debug, Clone)]
+pub struct EdgeCount {
+    count: usize,
+}
+
+impl EdgeCount {
+    /// create observable for `graph`
+    pub fn new<T, A>(graph: &GenericGraph<T, A>) -> Self
+    where A: AdjContainer<T>
+    {
+        Self{
+            count: graph.edge_count()
+        }
+    }
+}
+
+impl IncrementalObservable for EdgeCount {
+    type Value = usize;
+
+    fn reset<T, A>(&mut self, graph: &GenericGraph<T, A>)
+    where A: AdjContainer<T>
+    {
+        self.count = graph.edge_count();
+    }
+
+    fn update<T, A>(&mut self, _: &GenericGraph<T, A>, added: &[(usize, usize)], removed: &[(usize, usize)])
+    where A: AdjContainer<T>
+    {
+        self.count = self.count + added.len() - removed.len();
+    }
+
+    fn value(&self) -> usize
+    {
+        self.count
+    }
+}
+
+/// # Number of leafs, i.e., vertices with degree 1
+/// * same as [`GenericGraph::leaf_count`]
+/// * `O(1)` per changed edge
+#[derive(Debug, Clone)]
+pub struct LeafCount {
+    count: usize,
+}
+
+impl LeafCount {
+    /// create observable for `graph`
+    pub fn new<T, A>(graph: &GenericGraph<T, A>) -> Self
+    where A: AdjContainer<T>
+    {
+        Self{
+            count: graph.leaf_count()
+        }
+    }
+}
+
+impl IncrementalObservable for LeafCount {
+    type Value = usize;
+
+    fn reset<T, A>(&mut self, graph: &GenericGraph<T, A>)
+    where A: AdjContainer<T>
+    {
+        self.count = graph.leaf_count();
+    }
+
+    fn update<T, A>(&mut self, graph: &GenericGraph<T, A>, added: &[(usize, usize)], removed: &[(usize, usize)])
+    where A: AdjContainer<T>
+    {
+        let mut count = self.count;
+        for_each_degree_change(
+            graph,
+            added,
+            removed,
+            |before, after|
+            {
+                if before == 1 {
+                    count -= 1;
+                } else if after == 1 {
+                    count += 1;
+                }
+            }
+        );
+        self.count = count;
+    }
+
+    fn value(&self) -> usize
+    {
+        self.count
+    }
+}
+
+/// # Degree histogram
+/// * entry `k` of [`value`](#method.value) is the number of vertices with degree `k`,
+///   the last entry belongs to the maximal degree
+/// * `O(1)` per changed edge, amortized
+#[derive(Debug, Clone)]
+pub struct DegreeHistogram {
+    // index: degree, has length vertex_count
+    hist: Vec<usize>,
+    max_degree: usize,
+}
+
+impl DegreeHistogram {
+    /// create observable for `graph`
+    pub fn new<T, A>(graph: &GenericGraph<T, A>) -> Self
+    where A: AdjContainer<T>
+    {
+        let mut histogram = Self{
+            hist: Vec::new(),
+            max_degree: 0,
+        };
+        histogram.reset(graph);
+        histogram
+    }
+
+    /// * histogram without copying
+    /// * same as [`value`](#method.value)
+    pub fn histogram(&self) -> &[usize]
+    {
+        if self.hist.is_empty() {
+            &self.hist
+        } else {
+            &self.hist[..=self.max_degree]
+        }
+    }
+
+    /// number of vertices with degree `degree`
+    pub fn count(&self, degree: usize) -> usize
+    {
+        self.hist.get(degree)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// maximal degree of the graph, `0` for empty graphs
+    pub fn max_degree(&self) -> usize
+    {
+        self.max_degree
+    }
+}
+
+impl IncrementalObservable for DegreeHistogram {
+    type Value = Vec<usize>;
+
+    fn reset<T, A>(&mut self, graph: &GenericGraph<T, A>)
+    where A: AdjContainer<T>
+    {
+        self.hist.clear();
+        self.hist.resize(graph.vertex_count(), 0);
+        self.max_degree = 0;
+        for degree in graph.degree_iter() {
+            self.hist[degree] += 1;
+            self.max_degree = self.max_degree.max(degree);
+        }
+    }
+
+    fn update<T, A>(&mut self, graph: &GenericGraph<T, A>, added: &[(usize, usize)], removed: &[(usize, usize)])
+    where A: AdjContainer<T>
+    {
+        let hist = &mut self.hist;
+        let max_degree = &mut self.max_degree;
+        for_each_degree_change(
+            graph,
+            added,
+            removed,
+            |before, after|
+            {
+                hist[before] -= 1;
+                hist[after] += 1;
+                if after > *max_degree {
+                    *max_degree = after;
+                }
+            }
+        );
+        while *max_degree > 0 && hist[*max_degree] == 0 {
+            *max_degree -= 1;
+        }
+    }
+
+    /// * copy of [`histogram`](#method.histogram)
+    fn value(&self) -> Vec<usize>
+    {
+        self.histogram().to_vec()
+    }
+}
+
+/// # Number of triangles
+/// * `O(degree)` per changed edge, where degree refers to the endpoints of the edge
+#[derive(Debug, Clone)]
+pub struct TriangleCount {
+    count: usize,
+    mark: Vec<usize>,
+    epoch: usize,
+}
+
+impl TriangleCount {
+    /// create observable for `graph`
+    pub fn new<T, A>(graph: &GenericGraph<T, A>) -> Self
+    where A: AdjContainer<T>
+    {
+        let mut triangles = Self{
+            count: 0,
+            mark: Vec::new(),
+            epoch: 0,
+        };
+        triangles.reset(graph);
+        triangles
+    }
+
+    /// * number of common neighbors of `i` and `j`
+    /// * in `graph` plus the edges `extra` minus the edges `missing`
+    fn common_neighbors<T, A>(
+        &mut self,
+        graph: &GenericGraph<T, A>,
+        extra: &[(usize, usize)],
+        missing: &[(usize, usize)],
+        i: usize,
+        j: usize
+    ) -> usize
+    where A: AdjContainer<T>
+    {
+        self.epoch += 1;
+        let epoch = self.epoch;
+        let mark = &mut self.mark;
+        for_each_neighbor(graph, extra, missing, j, |neighbor| mark[neighbor] = epoch);
+        let mut count = 0;
+        for_each_neighbor(
+            graph,
+            extra,
+            missing,
+            i,
+            |neighbor|
+            if mark[neighbor] == epoch {
+                count += 1;
+            }
+        );
+        count
+    }
+}
+
+/// neighbors of `vertex` in `graph` plus the edges `extra` minus the edges `missing`
+fn for_each_neighbor<T, A, F>(
+    graph: &GenericGraph<T, A>,
+    extra: &[(usize, usize)],
+    missing: &[(usize, usize)],
+    vertex: usize,
+    mut f: F
+)
+where A: AdjContainer<T>,
+      F: FnMut(usize)
+{
+    let other = |&(i, j): &(usize, usize)| {
+        if i == vertex {
+            Some(j)
+        } else if j == vertex {
+            Some(i)
+        } else {
+            None
+        }
+    };
+    for &neighbor in graph.container(vertex).neighbors() {
+        if !missing.iter().any(|edge| other(edge) == Some(neighbor)) {
+            f(neighbor);
+        }
+    }
+    extra.iter()
+        .filter_map(other)
+        .for_each(f);
+}
+
+impl IncrementalObservable for TriangleCount {
+    type Value = usize;
+
+    fn reset<T, A>(&mut self, graph: &GenericGraph<T, A>)
+    where A: AdjContainer<T>
+    {
+        self.mark.clear();
+        self.mark.resize(graph.vertex_count(), 0);
+        self.epoch = 0;
+        // every triangle is counted once for each of its edges
+        let mut count = 0;
+        for container in graph.container_iter() {
+            let index = container.id();
+            for &neighbor in container.neighbors() {
+                if index < neighbor {
+                    count += self.common_neighbors(graph, &[], &[], index, neighbor);
+                }
+            }
+        }
+        self.count = count / 3;
+    }
+
+    fn update<T, A>(&mut self, graph: &GenericGraph<T, A>, added: &[(usize, usize)], removed: &[(usize, usize)])
+    where A: AdjContainer<T>
+    {
+        // add the edges one after another, starting from the graph before the changes
+        for (k, &(i, j)) in added.iter().enumerate() {
+            self.count += self.common_neighbors(graph, removed, &added[k..], i, j);
+        }
+        // then remove them one after another
+        for (k, &(i, j)) in removed.iter().enumerate() {
+            self.count -= self.common_neighbors(graph, &removed[k..], &[], i, j);
+        }
+    }
+
+    fn value(&self) -> usize
+    {
+        self.count
+    }
+}
+
+/// # Transitivity
+/// * same as [`GenericGraph::transitivity`], i.e., `NaN` if there are no paths of length two
+/// * `O(degree)` per changed edge, where degree refers to the endpoints of the edge
+#[derive(Debug, Clone)]
+pub struct Transitivity {
+    triangles: TriangleCount,
+    // number of paths of length two, each counted in one direction
+    triples: usize,
+}
+
+impl Transitivity {
+    /// create observable for `graph`
+    pub fn new<T, A>(graph: &GenericGraph<T, A>) -> Self
+    where A: AdjContainer<T>
+    {
+        let mut transitivity = Self{
+            triangles: TriangleCount::new(graph),
+            triples: 0,
+        };
+        transitivity.triples = triples(graph.degree_iter());
+        transitivity
+    }
+
+    /// number of triangles
+    pub fn triangle_count(&self) -> usize
+    {
+        self.triangles.value()
+    }
+
+    /// number of paths of length two
+    pub fn triple_count(&self) -> usize
+    {
+        self.triples
+    }
+}
+
+fn triples<I>(degrees: I) -> usize
+where I: Iterator<Item=usize>
+{
+    degrees.map(triples_of).sum()
+}
+
+fn triples_of(degree: usize) -> usize
+{
+    degree * degree.saturating_sub(1) / 2
+}
+
+impl IncrementalObservable for Transitivity {
+    type Value = f64;
+
+    fn reset<T, A>(&mut self, graph: &GenericGraph<T, A>)
+    where A: AdjContainer<T>
+    {
+        self.triangles.reset(graph);
+        self.triples = triples(graph.degree_iter());
+    }
+
+    fn update<T, A>(&mut self, graph: &GenericGraph<T, A>, added: &[(usize, usize)], removed: &[(usize, usize)])
+    where A: AdjContainer<T>
+    {
+        self.triangles.update(graph, added, removed);
+        let mut triples = self.triples;
+        for_each_degree_change(
+            graph,
+            added,
+            removed,
+            |before, after| triples = triples + triples_of(after) - triples_of(before)
+        );
+        self.triples = triples;
+    }
+
+    fn value(&self) -> f64
+    {
+        // same order of operations as GenericGraph::transitivity
+        let closed_path_count = 6 * self.triangles.value();
+        let path_count = 2 * self.triples;
+        closed_path_count as f64 / path_count as f64
+    }
+}
+
+impl IncrementalObservable for ConnectivityTracker {
+    /// number of connected components
+    type Value = usize;
+
+    fn reset<T, A>(&mut self, graph: &GenericGraph<T, A>)
+    where A: AdjContainer<T>
+    {
+        ConnectivityTracker::reset(self, graph);
+    }
+
+    fn update<T, A>(&mut self, graph: &GenericGraph<T, A>, added: &[(usize, usize)], removed: &[(usize, usize)])
+    where A: AdjContainer<T>
+    {
+        self.apply(graph, added, removed);
+    }
+
+    fn value(&self) -> usize
+    {
+        self.component_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyNode, test_chains::*};
+
+    struct Observables {
+        edges: EdgeCount,
+        leafs: LeafCount,
+        hist: DegreeHistogram,
+        transitivity: Transitivity,
+        components: ConnectivityTracker,
+    }
+
+    impl TrackedObservable for Observables {
+        fn new_tracked<T, A>(graph: &GenericGraph<T, A>) -> Self
+        where A: AdjContainer<T>
+        {
+            Self{
+                edges: EdgeCount::new(graph),
+                leafs: LeafCount::new(graph),
+                hist: DegreeHistogram::new(graph),
+                transitivity: Transitivity::new(graph),
+                components: ConnectivityTracker::new(graph),
+            }
+        }
+
+        fn track<T, A, S>(&mut self, graph: &GenericGraph<T, A>, step: &S, undo: bool)
+        where A: AdjContainer<T>,
+              S: EdgeChanges
+        {
+            if undo {
+                self.edges.track_undo(graph, step);
+                self.leafs.track_undo(graph, step);
+                self.hist.track_undo(graph, step);
+                self.transitivity.track_undo(graph, step);
+                IncrementalObservable::track_undo(&mut self.components, graph, step);
+            } else {
+                self.edges.track_step(graph, step);
+                self.leafs.track_step(graph, step);
+                self.hist.track_step(graph, step);
+                self.transitivity.track_step(graph, step);
+                IncrementalObservable::track_step(&mut self.components, graph, step);
+            }
+        }
+
+        fn assert_tracked<T, A>(&mut self, graph: &GenericGraph<T, A>)
+        where T: Node,
+              A: AdjContainer<T>
+        {
+            assert_eq!(self.edges.value(), graph.edge_count());
+            assert_eq!(self.leafs.value(), graph.leaf_count());
+            let mut hist = vec![0; graph.degree_iter().max().unwrap() + 1];
+            graph.degree_iter().for_each(|degree| hist[degree] += 1);
+            assert_eq!(self.hist.value(), hist);
+            assert_eq!(self.transitivity.value().to_bits(), graph.transitivity().to_bits());
+            assert_eq!(self.components.value(), graph.connected_components().len());
+        }
+    }
+
+    #[test]
+    fn tracks_markov_chains() {
+        check_chains::<Observables>(30, 4.0, 500, 11);
+    }
+
+    #[test]
+    fn several_changes_at_once() {
+        // triangle 0 1 2, path 2 3 4
+        let mut graph: crate::Graph<EmptyNode> = crate::Graph::new(5);
+        for &(i, j) in &[(0, 1), (1, 2), (0, 2), (2, 3), (3, 4)] {
+            graph.add_edge(i, j).unwrap();
+        }
+        let mut triangles = TriangleCount::new(&graph);
+        assert_eq!(triangles.value(), 1);
+
+        // close triangle 2 3 4 and 1 2 4, open triangle 0 1 2
+        graph.add_edge(2, 4).unwrap();
+        graph.add_edge(1, 4).unwrap();
+        graph.remove_edge(0, 1).unwrap();
+        triangles.update(&graph, &[(2, 4), (1, 4)], &[(0, 1)]);
+        assert_eq!(triangles.value(), 2);
+
+        let mut hist = DegreeHistogram::new(&graph);
+        assert_eq!(hist.histogram(), &[0, 1, 2, 1, 1]);
+        graph.remove_edge(2, 4).unwrap();
+        graph.remove_edge(1, 4).unwrap();
+        hist.update(&graph, &[], &[(2, 4), (1, 4)]);
+        assert_eq!(hist.histogram(), &[0, 3, 1, 1]);
+        assert_eq!(hist.max_degree(), 3);
+    }
+}
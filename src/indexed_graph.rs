@@ -0,0 +1,407 @@
+//! # Topology with constant time edge lookup
+//! Same as [`Graph`](crate::Graph), but every vertex additionally stores
+//! a hash index of its neighbors.
+//!
+//! Checking whether two vertices are adjacent is `O(1)` on average instead of linear in the degree,
+//! which speeds up `add_edge`, `remove_edge` and `transitivity` for vertices with large degree,
+//! e.g., dense Erdős-Rényi graphs or hubs of Barabási-Albert graphs.
+//! The price is additional memory and a slower iteration over all vertices.
+//!
+//! The adjacency lists are changed in exactly the same way as the adjacency lists of
+//! [`NodeContainer`](crate::graph::NodeContainer), i.e., an ensemble using
+//! [`IndexedNodeContainer`] creates the same graphs as the default ensemble for the same seed.
+
+use {
+    crate::{
+        traits::*,
+        GraphErrors,
+        GenericGraph,
+        graph::NodeContainer
+    },
+    std::{
+        collections::HashMap,
+        marker::PhantomData
+    }
+};
+
+use rand::{Rng, seq::SliceRandom};
+#[cfg(feature = "serde_support")]
+use serde::{Serialize, Deserialize};
+
+/// # Adjacency container with hash index
+/// * contains adjacency list, internal id (normally the index in the graph)
+///   and the user specified data `T`
+/// * additionally maps every neighbor to its position in the adjacency list,
+///   so that `is_adjacent` and removing edges are `O(1)` on average
+/// * see trait **`AdjContainer`**
+/// # Serialization
+/// The hash index is not serialized, it is rebuilt on deserialization.
+/// The serialized form is the same as the one of [`NodeContainer`]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde_support", serde(from = "NodeContainer<T>"))]
+pub struct IndexedNodeContainer<T>{
+    pub(crate) id: usize,
+    pub(crate) adj: Vec<usize>,
+    pub(crate) node: T,
+    #[cfg_attr(feature = "serde_support", serde(skip_serializing))]
+    position: HashMap<usize, usize>,
+}
+
+impl<T> From<NodeContainer<T>> for IndexedNodeContainer<T> {
+    fn from(container: NodeContainer<T>) -> Self
+    {
+        let mut res = Self{
+            id: container.id,
+            adj: container.adj,
+            node: container.node,
+            position: HashMap::new(),
+        };
+        res.rebuild_index();
+        res
+    }
+}
+
+impl<T> IndexedNodeContainer<T> {
+    fn rebuild_index(&mut self)
+    {
+        self.position.clear();
+        self.position.extend(
+            self.adj
+                .iter()
+                .enumerate()
+                .map(|(index, &neighbor)| (neighbor, index))
+        );
+    }
+
+    #[inline]
+    fn push_neighbor(&mut self, neighbor: usize)
+    {
+        self.position.insert(neighbor, self.adj.len());
+        self.adj.push(neighbor);
+    }
+
+    fn swap_remove_element(&mut self, elem: usize)
+    {
+        let index = self.position
+            .remove(&elem)
+            .expect("swap_remove_element ERROR 0");
+        self.adj.swap_remove(index);
+        // the last neighbor moved to `index`
+        if let Some(&moved) = self.adj.get(index) {
+            self.position.insert(moved, index);
+        }
+    }
+}
+
+impl<T> AdjContainer<T> for IndexedNodeContainer<T> {
+
+    /// Create new instance with id
+    fn new(id: usize, node: T) -> Self {
+        IndexedNodeContainer{
+            id,
+            adj: Vec::new(),
+            node,
+            position: HashMap::new(),
+        }
+    }
+
+    /// return reference to what the IndexedNodeContainer contains
+    fn contained(&self) -> &T {
+        &self.node
+    }
+
+    /// return mut reference to what the IndexedNodeContainer contains
+    fn contained_mut(&mut self) -> &mut T {
+        &mut self.node
+    }
+
+    /// returns iterator over indices of neighbors
+    fn neighbors(&self) -> IterWrapper {
+        IterWrapper::new_generic(self.adj.iter())
+    }
+
+    /// count number of neighbors, i.e. number of edges incident to `self`
+    fn degree(&self) -> usize {
+        self.adj.len()
+    }
+
+    /// returns id of container
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    /// returns `Some(first element from the adjecency List)` or `None`
+    fn get_adj_first(&self) -> Option<&usize> {
+        self.adj.first()
+    }
+
+    /// check if vertex with `other_id` is adjacent to self
+    /// * `O(1)` on average
+    fn is_adjacent(&self, other_id: usize) -> bool {
+        self.position.contains_key(&other_id)
+    }
+
+    /// # Sorting adjecency lists
+    /// * calls `sort_unstable()` on the adjecency list and rebuilds the index
+    fn sort_adj(&mut self) {
+        self.adj.sort_unstable();
+        self.rebuild_index();
+    }
+
+    fn shuffle_adj<R: Rng>(&mut self, rng: &mut R) {
+        self.adj.shuffle(rng);
+        self.rebuild_index();
+    }
+
+    #[doc(hidden)]
+    unsafe fn clear_edges(&mut self) {
+        self.adj.clear();
+        self.position.clear();
+    }
+
+    #[doc(hidden)]
+    unsafe fn push(&mut self, other: &mut Self)
+        -> Result<(), GraphErrors>
+    {
+        if self.is_adjacent(other.id()) {
+            return Err(GraphErrors::EdgeExists);
+        }
+        self.push_unchecked(other);
+        Ok(())
+    }
+
+    #[doc(hidden)]
+    unsafe fn push_unchecked(&mut self, other: &mut Self)
+    {
+        self.push_neighbor(other.id);
+        other.push_neighbor(self.id);
+    }
+
+    #[doc(hidden)]
+    unsafe fn set_adj_unchecked<I>(&mut self, neighbors: I) -> bool
+    where I: Iterator<Item=usize>
+    {
+        self.adj.clear();
+        self.adj.extend(neighbors);
+        self.rebuild_index();
+        true
+    }
+
+    /// Tries to remove edges, returns error `GraphErrors::EdgeDoesNotExist` if impossible
+    #[doc(hidden)]
+    unsafe fn remove(&mut self, other: &mut Self)
+        -> Result<(), GraphErrors>
+    {
+        if !self.is_adjacent(other.id()){
+            return Err(GraphErrors::EdgeDoesNotExist);
+        }
+
+        self.swap_remove_element(other.id());
+        other.swap_remove_element(self.id());
+
+        Ok(())
+    }
+}
+
+impl<T> AdjList<usize> for IndexedNodeContainer<T>{
+    fn edges(&self) -> &[usize]
+    {
+        self.adj.as_slice()
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<T> crate::snapshot::SnapshotContainer<T> for IndexedNodeContainer<T> {
+    type Extra = ();
+
+    fn snapshot_extra(&self) {}
+
+    fn from_snapshot(id: usize, node: T, adj: Vec<usize>, _: ()) -> Option<Self> {
+        let container: Self = NodeContainer{id, adj, node}.into();
+        // duplicates in the adjacency list are invalid
+        if container.position.len() == container.adj.len() {
+            Some(container)
+        } else {
+            None
+        }
+    }
+}
+
+/// # Graph with constant time edge lookup
+/// * drop-in replacement for [`Graph`](crate::Graph), see [module documentation](crate::indexed_graph)
+/// * all ensembles based on `Graph<T>` can use it by specifying the container,
+///   e.g., `ErEnsembleC<T, R, IndexedNodeContainer<T>>`, and creating them with
+///   the `*_with_container` constructors, e.g.,
+///   [`ErEnsembleC::new_with_container`](crate::ErEnsembleC::new_with_container)
+/// ```
+/// use net_ensembles::{IndexedGraph, EmptyNode, AdjContainer};
+///
+/// let mut graph: IndexedGraph<EmptyNode> = IndexedGraph::new(4);
+/// graph.add_edge(0, 1).unwrap();
+/// graph.add_edge(0, 2).unwrap();
+/// assert!(graph.add_edge(1, 0).is_err());
+///
+/// graph.remove_edge(0, 1).unwrap();
+/// assert!(!graph.container(0).is_adjacent(1));
+/// assert!(graph.container(0).is_adjacent(2));
+/// ```
+pub type IndexedGraph<T> = GenericGraph<T, IndexedNodeContainer<T>>;
+
+impl<T: Node, A: AdjContainer<T>> From<&GenericGraph<T, A>> for IndexedGraph<T>
+{
+    fn from(source: &GenericGraph<T, A>) -> Self
+    {
+        let vertices = source
+            .container_iter()
+            .map(|container|
+                NodeContainer{
+                    id: container.id(),
+                    node: container.contained().clone(),
+                    adj: container.neighbors().copied().collect(),
+                }.into()
+            ).collect();
+        Self{
+            next_id: source.next_id,
+            edge_count: source.edge_count(),
+            vertices,
+            phantom: PhantomData::<T>,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyNode, Graph, ErEnsembleC, ErEnsembleM, BAensemble, ConfigurationModel};
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    fn assert_same_adjacency(graph: &Graph<EmptyNode>, indexed: &IndexedGraph<EmptyNode>)
+    {
+        assert_eq!(graph.edge_count(), indexed.edge_count());
+        for (c, ic) in graph.container_iter().zip(indexed.container_iter()) {
+            assert_eq!(c.edges(), ic.edges());
+            assert_eq!(ic.position.len(), ic.degree());
+            for (index, neighbor) in ic.neighbors().enumerate() {
+                assert_eq!(ic.position[neighbor], index);
+            }
+        }
+    }
+
+    #[test]
+    fn same_as_graph() {
+        let mut rng = Pcg64::seed_from_u64(2384);
+        let n = 30;
+        let mut graph: Graph<EmptyNode> = Graph::new(n);
+        let mut indexed: IndexedGraph<EmptyNode> = IndexedGraph::new(n);
+        for _ in 0..5000 {
+            let i = rng.gen_range(0..n);
+            let j = rng.gen_range(0..n);
+            if i == j {
+                continue;
+            }
+            assert_eq!(graph.container(i).is_adjacent(j), indexed.container(i).is_adjacent(j));
+            if rng.gen::<f64>() < 0.6 {
+                assert_eq!(graph.add_edge(i, j).is_ok(), indexed.add_edge(i, j).is_ok());
+            } else {
+                assert_eq!(graph.remove_edge(i, j).is_ok(), indexed.remove_edge(i, j).is_ok());
+            }
+        }
+        assert_same_adjacency(&graph, &indexed);
+
+        graph.sort_adj();
+        indexed.sort_adj();
+        assert_same_adjacency(&graph, &indexed);
+        assert_eq!(graph.transitivity(), indexed.transitivity());
+
+        let converted = IndexedGraph::from(&graph);
+        assert_same_adjacency(&graph, &converted);
+    }
+
+    fn assert_same_graph<A1, A2>(g1: &GenericGraph<EmptyNode, A1>, g2: &GenericGraph<EmptyNode, A2>)
+    where A1: AdjContainer<EmptyNode>,
+          A2: AdjContainer<EmptyNode>
+    {
+        assert_eq!(g1.edge_count(), g2.edge_count());
+        for (c1, c2) in g1.container_iter().zip(g2.container_iter()) {
+            assert!(c1.neighbors().eq(c2.neighbors()));
+        }
+    }
+
+    #[test]
+    fn ensembles_are_drop_in() {
+        let er_c = ErEnsembleC::<EmptyNode, Pcg64>::new(100, 40.0, Pcg64::seed_from_u64(1));
+        let mut indexed_er_c: ErEnsembleC<EmptyNode, Pcg64, IndexedNodeContainer<EmptyNode>>
+            = ErEnsembleC::new_with_container(100, 40.0, Pcg64::seed_from_u64(1));
+        assert_same_graph(er_c.graph(), indexed_er_c.graph());
+
+        let mut er_m = ErEnsembleM::<EmptyNode, Pcg64>::new(50, 1000, Pcg64::seed_from_u64(2));
+        let mut indexed_er_m: ErEnsembleM<EmptyNode, Pcg64, IndexedNodeContainer<EmptyNode>>
+            = ErEnsembleM::new_with_container(50, 1000, Pcg64::seed_from_u64(2));
+        assert_same_graph(er_m.graph(), indexed_er_m.graph());
+        let mut steps = Vec::new();
+        let mut indexed_steps = Vec::new();
+        er_m.m_steps(2000, &mut steps);
+        indexed_er_m.m_steps(2000, &mut indexed_steps);
+        assert_same_graph(er_m.graph(), indexed_er_m.graph());
+        indexed_er_m.undo_steps_quiet(&indexed_steps);
+        er_m.undo_steps_quiet(&steps);
+        assert_same_graph(er_m.graph(), indexed_er_m.graph());
+
+        let ba: BAensemble<EmptyNode, Pcg64> = BAensemble::new(200, Pcg64::seed_from_u64(3), 3, 4);
+        let indexed_ba: BAensemble<EmptyNode, Pcg64, IndexedNodeContainer<EmptyNode>>
+            = BAensemble::new_with_container(200, Pcg64::seed_from_u64(3), 3, 4);
+        assert_same_graph(ba.graph(), indexed_ba.graph());
+
+        let sparse = ErEnsembleC::<EmptyNode, Pcg64>::new(100, 3.0, Pcg64::seed_from_u64(4));
+        let mut cm: ConfigurationModel<EmptyNode, Pcg64>
+            = ConfigurationModel::from_generic_graph(sparse.graph(), Pcg64::seed_from_u64(5));
+        let mut indexed_cm: ConfigurationModel<EmptyNode, Pcg64, IndexedNodeContainer<EmptyNode>>
+            = ConfigurationModel::from_generic_graph_with_container(sparse.graph(), Pcg64::seed_from_u64(5));
+        assert_same_graph(cm.graph(), indexed_cm.graph());
+        let mut steps = Vec::new();
+        let mut indexed_steps = Vec::new();
+        cm.m_steps(1000, &mut steps);
+        indexed_cm.m_steps(1000, &mut indexed_steps);
+        assert_same_graph(cm.graph(), indexed_cm.graph());
+        assert_eq!(&cm.graph().degree_vec(), indexed_cm.degree_vec());
+        cm.undo_steps_quiet(&steps);
+        indexed_cm.undo_steps_quiet(&indexed_steps);
+        assert_same_graph(cm.graph(), indexed_cm.graph());
+
+        indexed_er_c.sort_adj();
+        assert_eq!(indexed_er_c.graph().transitivity(), er_c.graph().transitivity());
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn snapshot_round_trip() {
+        use crate::snapshot::Snapshot;
+        let mut er_m: ErEnsembleM<EmptyNode, Pcg64, IndexedNodeContainer<EmptyNode>>
+            = ErEnsembleM::new_with_container(40, 300, Pcg64::seed_from_u64(5));
+        let mut bytes = Vec::new();
+        er_m.save_snapshot(&mut bytes).unwrap();
+        let mut loaded: ErEnsembleM<EmptyNode, Pcg64, IndexedNodeContainer<EmptyNode>>
+            = Snapshot::load_snapshot(bytes.as_slice()).unwrap();
+        er_m.m_steps_quiet(100);
+        loaded.m_steps_quiet(100);
+        assert_same_graph(er_m.graph(), loaded.graph());
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn serde_rebuilds_index() {
+        let mut graph: IndexedGraph<EmptyNode> = IndexedGraph::new(5);
+        graph.add_edge(0, 1).unwrap();
+        graph.add_edge(3, 1).unwrap();
+        graph.add_edge(4, 2).unwrap();
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let plain: Graph<EmptyNode> = serde_json::from_str(&json).unwrap();
+        let loaded: IndexedGraph<EmptyNode> = serde_json::from_str(&json).unwrap();
+        assert_same_adjacency(&plain, &loaded);
+        assert!(loaded.container(1).is_adjacent(3));
+        assert!(!loaded.container(1).is_adjacent(2));
+    }
+}
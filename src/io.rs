@@ -0,0 +1,356 @@
+//! # Reading and writing graphs
+//! Exchange graphs with other tools via common file formats.
+//!
+//! * edge lists: [`read_edge_list`] and [`write_edge_list`]
+//! * GraphML: [`read_graphml`] and [`write_graphml`]
+//! * graph6 and sparse6 of nauty: [`to_graph6`], [`to_sparse6`], [`from_graph6`],
+//!   [`from_sparse6`] and for files with one graph per line [`Graph6Writer`] and [`Graph6Reader`]
+//! * Pajek: [`read_pajek`] and [`write_pajek`]
+//! * GML: [`read_gml`] and [`write_gml`]
+//! * DOT: [`read_dot`], for writing see the [`Dot`](crate::traits::Dot) trait
+//!
+//! [`read_graph`] and [`read_graph_with`] pick the format from the file extension.
+//!
+//! Graphs are always read into a [`Graph<T>`](crate::Graph), where the nodes are
+//! created with [`Node::new_from_index`](crate::Node::new_from_index), unless the
+//! format carries node data, which is then converted by a closure.
+//! Errors are reported via [`ReadGraphError`].
+use {
+    crate::{
+        Node,
+        Graph,
+        graph::NodeContainer,
+        sw_graph::SwContainer,
+        watts_strogatz::WSContainer,
+        spacial::SpacialNodeContainer
+    },
+    std::{
+        fmt,
+        error::Error,
+        collections::HashMap,
+        fs::File,
+        io::BufReader,
+        path::Path
+    }
+};
+
+mod edge_list;
+mod graphml;
+mod graph6;
+mod pajek;
+mod gml;
+mod dot;
+
+pub use edge_list::*;
+pub use graphml::*;
+pub use graph6::*;
+pub use pajek::*;
+pub use gml::*;
+pub use dot::*;
+
+/// # Value of a vertex attribute
+/// * used by the formats that can store additional vertex data, e.g., GraphML and GML
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    /// boolean value
+    Bool(bool),
+    /// integer value
+    Int(i64),
+    /// floating point value
+    Double(f64),
+    /// text
+    String(String),
+}
+
+impl AttributeValue {
+    /// `Some` if value is `Bool`
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            AttributeValue::Bool(b) => Some(*b),
+            _ => None
+        }
+    }
+
+    /// `Some` if value is `Int`
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            AttributeValue::Int(i) => Some(*i),
+            _ => None
+        }
+    }
+
+    /// `Some` if value is `Double` or `Int`
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            AttributeValue::Double(d) => Some(*d),
+            AttributeValue::Int(i) => Some(*i as f64),
+            _ => None
+        }
+    }
+
+    /// `Some` if value is `String`
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttributeValue::String(s) => Some(s),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AttributeValue::Bool(b) => write!(f, "{}", b),
+            AttributeValue::Int(i) => write!(f, "{}", i),
+            AttributeValue::Double(d) => write!(f, "{}", d),
+            AttributeValue::String(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// # Vertex data stored in the adjacency container, that is exported automatically
+/// * implemented for all adjacency containers of this crate
+/// * [`SpacialNodeContainer`] exports its coordinates
+pub trait ExportAttributes {
+    /// * coordinates of the vertex, if it has any
+    fn coordinates(&self) -> Option<(f64, f64)> {
+        None
+    }
+
+    /// * attributes written for each vertex, before the attributes created by the user
+    /// * default: coordinates as `x` and `y`, if there are any
+    fn export_attributes(&self) -> Vec<(String, AttributeValue)> {
+        match self.coordinates() {
+            Some((x, y)) => vec![
+                ("x".to_owned(), AttributeValue::Double(x)),
+                ("y".to_owned(), AttributeValue::Double(y)),
+            ],
+            None => Vec::new()
+        }
+    }
+}
+
+impl<T> ExportAttributes for NodeContainer<T> {}
+
+impl<T: Node> ExportAttributes for SwContainer<T> {}
+
+impl<T> ExportAttributes for WSContainer<T> {}
+
+impl<T> ExportAttributes for SpacialNodeContainer<T> {
+    fn coordinates(&self) -> Option<(f64, f64)> {
+        Some((self.x, self.y))
+    }
+}
+
+/// # Errors that can occur while reading a graph
+/// * lines are counted starting at 1
+#[derive(Debug)]
+pub enum ReadGraphError {
+    /// ### Reading failed
+    /// The underlying reader returned an error
+    Io(std::io::Error),
+    /// ### Line does not match the format
+    Malformed{
+        /// line of the error
+        line: usize,
+        /// what went wrong
+        message: String,
+    },
+    /// ### Vertex index is not smaller than the number of vertices
+    IndexOutOfRange{
+        /// line of the error
+        line: usize,
+        /// offending index
+        index: usize,
+        /// number of vertices of the graph
+        vertex_count: usize,
+    },
+    /// ### Edge from a vertex to itself
+    /// Self-loops are not supported by the graphs of this crate
+    SelfLoop{
+        /// line of the error
+        line: usize,
+        /// index of the vertex
+        index: usize,
+    },
+    /// ### Edge was already read before
+    /// Multi-edges are not supported by the graphs of this crate
+    DuplicateEdge{
+        /// line of the error
+        line: usize,
+        /// the edge
+        edge: (usize, usize),
+    },
+    /// ### File extension does not belong to a supported format
+    /// See [`read_graph`]
+    UnknownFormat{
+        /// the extension, empty if the path has none
+        extension: String,
+    },
+}
+
+impl fmt::Display for ReadGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadGraphError::Io(e) => write!(f, "IO error: {}", e),
+            ReadGraphError::Malformed{line, message} => write!(f, "line {}: {}", line, message),
+            ReadGraphError::IndexOutOfRange{line, index, vertex_count} => write!(
+                f,
+                "line {}: index {} out of range for {} vertices",
+                line,
+                index,
+                vertex_count
+            ),
+            ReadGraphError::SelfLoop{line, index} => write!(f, "line {}: self-loop at vertex {}", line, index),
+            ReadGraphError::DuplicateEdge{line, edge} => write!(
+                f,
+                "line {}: duplicate edge {} {}",
+                line,
+                edge.0,
+                edge.1
+            ),
+            ReadGraphError::UnknownFormat{extension} => write!(f, "unknown file format `{}`", extension),
+        }
+    }
+}
+
+impl Error for ReadGraphError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadGraphError::Io(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for ReadGraphError {
+    fn from(e: std::io::Error) -> Self {
+        ReadGraphError::Io(e)
+    }
+}
+
+impl ReadGraphError {
+    pub(crate) fn malformed<S: Into<String>>(line: usize, message: S) -> Self {
+        ReadGraphError::Malformed{
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+/// * creates graph with `vertex_count` vertices and adds all `(line, i, j)` edges
+/// * checks for out of range indices, self-loops and duplicate edges
+pub(crate) fn build_graph<T: Node>(vertex_count: usize, edges: &[(usize, usize, usize)])
+    -> Result<Graph<T>, ReadGraphError>
+{
+    let mut graph = Graph::new(vertex_count);
+    for &(line, i, j) in edges {
+        for index in [i, j] {
+            if index >= vertex_count {
+                return Err(ReadGraphError::IndexOutOfRange{line, index, vertex_count});
+            }
+        }
+        if i == j {
+            return Err(ReadGraphError::SelfLoop{line, index: i});
+        }
+        if graph.add_edge(i, j).is_err() {
+            return Err(ReadGraphError::DuplicateEdge{line, edge: (i, j)});
+        }
+    }
+    Ok(graph)
+}
+
+/// # Read graph from file
+/// * picks the format from the file extension, see [`read_graph_with`]
+/// * nodes are created with [`Node::new_from_index`]
+pub fn read_graph<T, P>(path: P) -> Result<Graph<T>, ReadGraphError>
+where T: Node,
+      P: AsRef<Path>
+{
+    read_graph_with(path, |index, _| T::new_from_index(index))
+}
+
+/// # Read graph from file
+/// * picks the format from the file extension (case insensitive):
+///
+/// | extension | format |
+/// |-----------|--------|
+/// | `txt`, `edges`, `edgelist`, `el`, `csv`, `tsv` | edge list, see [`read_edge_list`] |
+/// | `graphml` | GraphML, see [`read_graphml`] |
+/// | `g6` | graph6, first graph of the file, see [`Graph6Reader`] |
+/// | `s6` | sparse6, first graph of the file, see [`Graph6Reader`] |
+/// | `net`, `paj` | Pajek, see [`read_pajek`] |
+/// | `gml` | GML, see [`read_gml`] |
+/// | `dot`, `gv` | DOT, see [`read_dot`] |
+///
+/// * `node` creates the data `T` of each vertex from its index and its attributes.
+///   Formats without vertex data pass empty attributes
+/// * returns [`UnknownFormat`](ReadGraphError::UnknownFormat) for other extensions
+pub fn read_graph_with<T, P, F>(path: P, mut node: F) -> Result<Graph<T>, ReadGraphError>
+where T: Node,
+      P: AsRef<Path>,
+      F: FnMut(usize, &HashMap<String, AttributeValue>) -> T
+{
+    let path = path.as_ref();
+    let extension = path.extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+
+    let open = || File::open(path).map(BufReader::new);
+    let mut graph = match extension.as_str() {
+        "graphml" => return read_graphml(open()?, node),
+        "net" | "paj" => return read_pajek(open()?, node),
+        "gml" => return read_gml(open()?, node),
+        "dot" | "gv" => return read_dot(open()?, node),
+        "txt" | "edges" | "edgelist" | "el" | "csv" | "tsv" => read_edge_list(open()?)?,
+        "g6" | "s6" => Graph6Reader::new(open()?)
+            .next()
+            .unwrap_or_else(|| Err(ReadGraphError::malformed(1, "file contains no graph")))?,
+        _ => return Err(ReadGraphError::UnknownFormat{extension}),
+    };
+    let empty = HashMap::new();
+    for index in 0..graph.vertex_count() {
+        *graph.at_mut(index) = node(index, &empty);
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyNode, traits::Dot};
+    use std::io::Write;
+
+    #[test]
+    fn read_graph_by_extension()
+    {
+        let graph = Graph::<EmptyNode>::complete_graph(5);
+        let dir = std::env::temp_dir();
+        let write = |name: &str, f: &dyn Fn(&mut File) -> std::io::Result<()>| {
+            let path = dir.join(format!("net_ensembles_read_graph_{}", name));
+            let mut file = File::create(&path).unwrap();
+            f(&mut file).unwrap();
+            path
+        };
+        let paths = [
+            write("a.txt", &|file| write_edge_list(&graph, file, true)),
+            write("a.GraphML", &|file| write_graphml(&graph, file, |_, _| Vec::<(String, _)>::new())),
+            write("a.g6", &|file| writeln!(file, "{}", to_graph6(&graph))),
+            write("a.s6", &|file| writeln!(file, "{}", to_sparse6(&graph))),
+            write("a.net", &|file| write_pajek(&graph, file, |index, _| index.to_string())),
+            write("a.gml", &|file| write_gml(&graph, file, |_, _| Vec::<(String, _)>::new())),
+            write("a.dot", &|file| graph.dot(file, "")),
+        ];
+        for path in paths.iter() {
+            let read: Graph<EmptyNode> = read_graph(path).unwrap();
+            assert_eq!(read.vertex_count(), 5);
+            assert_eq!(read.edge_count(), 10);
+            std::fs::remove_file(path).unwrap();
+        }
+        assert!(matches!(
+            read_graph::<EmptyNode, _>(dir.join("graph.unknown")),
+            Err(ReadGraphError::UnknownFormat{..})
+        ));
+    }
+}
@@ -0,0 +1,475 @@
+use {
+    super::*,
+    std::{
+        collections::HashMap,
+        io::Read
+    }
+};
+
+#[derive(Debug, PartialEq)]
+enum DotToken {
+    /// identifier, numeral, quoted or HTML string. `true` if it was not quoted
+    Id(String, bool),
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Equal,
+    Semicolon,
+    Comma,
+    Colon,
+    UndirectedEdge,
+    DirectedEdge,
+}
+
+/// splits DOT into tokens together with their line
+fn tokenize(s: &str) -> Result<Vec<(DotToken, usize)>, ReadGraphError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    let mut line = 1;
+    let mut line_start = true;
+    while let Some(c) = chars.next() {
+        let token_line = line;
+        let token = match c {
+            '\n' => {
+                line += 1;
+                line_start = true;
+                continue;
+            },
+            c if c.is_whitespace() => continue,
+            // preprocessor output
+            '#' if line_start => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+                continue;
+            },
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+                continue;
+            },
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        None => return Err(ReadGraphError::malformed(token_line, "unterminated comment")),
+                        Some('/') if previous == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            previous = c;
+                        }
+                    }
+                }
+                continue;
+            },
+            '{' => DotToken::LeftBrace,
+            '}' => DotToken::RightBrace,
+            '[' => DotToken::LeftBracket,
+            ']' => DotToken::RightBracket,
+            '=' => DotToken::Equal,
+            ';' => DotToken::Semicolon,
+            ',' => DotToken::Comma,
+            ':' => DotToken::Colon,
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                DotToken::UndirectedEdge
+            },
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                DotToken::DirectedEdge
+            },
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err(ReadGraphError::malformed(token_line, "unterminated string")),
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('"') => id.push('"'),
+                            // line continuation
+                            Some('\n') => line += 1,
+                            Some(c) => {
+                                id.push('\\');
+                                id.push(c);
+                            },
+                            None => return Err(ReadGraphError::malformed(token_line, "unterminated string")),
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            id.push(c);
+                        }
+                    }
+                }
+                DotToken::Id(id, false)
+            },
+            '<' => {
+                let mut id = String::new();
+                let mut depth = 1;
+                loop {
+                    let c = chars.next()
+                        .ok_or_else(|| ReadGraphError::malformed(token_line, "unterminated HTML string"))?;
+                    match c {
+                        '<' => depth += 1,
+                        '>' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        },
+                        '\n' => line += 1,
+                        _ => ()
+                    }
+                    id.push(c);
+                }
+                DotToken::Id(id, false)
+            },
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut id = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    id.push(c);
+                    chars.next();
+                }
+                DotToken::Id(id, true)
+            },
+            c => return Err(ReadGraphError::malformed(line, format!("unexpected character `{}`", c))),
+        };
+        line_start = false;
+        tokens.push((token, token_line));
+    }
+    Ok(tokens)
+}
+
+fn is_keyword(token: &DotToken, keyword: &str) -> bool {
+    match token {
+        DotToken::Id(id, true) => id.eq_ignore_ascii_case(keyword),
+        _ => false
+    }
+}
+
+struct DotParser {
+    tokens: Vec<(DotToken, usize)>,
+    position: usize,
+    node_defaults: HashMap<String, AttributeValue>,
+    ids: HashMap<String, usize>,
+    nodes: Vec<HashMap<String, AttributeValue>>,
+    edges: Vec<(usize, usize, usize)>,
+}
+
+impl DotParser {
+    fn peek(&self) -> Option<&DotToken> {
+        self.tokens
+            .get(self.position)
+            .map(|(token, _)| token)
+    }
+
+    /// line of the next token or of the last token
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |&(_, line)| line)
+    }
+
+    fn next(&mut self) -> Option<DotToken> {
+        let token = self.tokens
+            .get_mut(self.position)
+            .map(|(token, _)| std::mem::replace(token, DotToken::Semicolon));
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: DotToken, what: &str) -> Result<(), ReadGraphError> {
+        let line = self.line();
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(ReadGraphError::malformed(line, format!("expected {}", what)))
+        }
+    }
+
+    fn id(&mut self) -> Result<String, ReadGraphError> {
+        let line = self.line();
+        match self.next() {
+            Some(DotToken::Id(id, _)) => Ok(id),
+            _ => Err(ReadGraphError::malformed(line, "expected ID"))
+        }
+    }
+
+    /// `[a=b, c=d][e=f]`
+    fn attribute_lists(&mut self) -> Result<HashMap<String, AttributeValue>, ReadGraphError> {
+        let mut attributes = HashMap::new();
+        while self.peek() == Some(&DotToken::LeftBracket) {
+            self.next();
+            loop {
+                match self.peek() {
+                    Some(DotToken::RightBracket) => {
+                        self.next();
+                        break;
+                    },
+                    Some(DotToken::Comma) | Some(DotToken::Semicolon) => {
+                        self.next();
+                    },
+                    _ => {
+                        let key = self.id()?;
+                        self.expect(DotToken::Equal, "`=`")?;
+                        let value = self.id()?;
+                        attributes.insert(key, AttributeValue::String(value));
+                    }
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// node ID with optional port, which is ignored
+    fn node_id(&mut self) -> Result<String, ReadGraphError> {
+        let line = self.line();
+        let id = match self.next() {
+            Some(DotToken::Id(id, unquoted)) => {
+                if unquoted && id.eq_ignore_ascii_case("subgraph") {
+                    return Err(ReadGraphError::malformed(line, "subgraphs are not supported"));
+                }
+                id
+            },
+            Some(DotToken::LeftBrace) => return Err(ReadGraphError::malformed(line, "subgraphs are not supported")),
+            _ => return Err(ReadGraphError::malformed(line, "expected node ID")),
+        };
+        while self.peek() == Some(&DotToken::Colon) {
+            self.next();
+            self.id()?;
+        }
+        Ok(id)
+    }
+
+    fn node_index(&mut self, id: String) -> usize {
+        match self.ids.get(&id) {
+            Some(&index) => index,
+            None => {
+                let index = self.nodes.len();
+                self.ids.insert(id.clone(), index);
+                let mut attributes = self.node_defaults.clone();
+                // graphviz uses the name of the node as default label
+                attributes.entry("label".to_owned())
+                    .or_insert(AttributeValue::String(id));
+                self.nodes.push(attributes);
+                index
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<(), ReadGraphError> {
+        let line = self.line();
+        let token = self.peek().unwrap();
+        if is_keyword(token, "graph") || is_keyword(token, "edge") {
+            self.next();
+            self.attribute_lists()?;
+            return Ok(());
+        }
+        if is_keyword(token, "node") {
+            self.next();
+            let defaults = self.attribute_lists()?;
+            self.node_defaults.extend(defaults);
+            return Ok(());
+        }
+        let id = self.node_id()?;
+        match self.peek() {
+            Some(DotToken::Equal) => {
+                // graph attribute
+                self.next();
+                self.id()?;
+            },
+            Some(DotToken::UndirectedEdge) => {
+                let mut previous = self.node_index(id);
+                while self.peek() == Some(&DotToken::UndirectedEdge) {
+                    self.next();
+                    let line = self.line();
+                    let id = self.node_id()?;
+                    let next = self.node_index(id);
+                    self.edges.push((line, previous, next));
+                    previous = next;
+                }
+                self.attribute_lists()?;
+            },
+            Some(DotToken::DirectedEdge) => {
+                return Err(ReadGraphError::malformed(line, "directed edges are not supported"));
+            },
+            _ => {
+                let index = self.node_index(id);
+                let attributes = self.attribute_lists()?;
+                self.nodes[index].extend(attributes);
+            }
+        }
+        Ok(())
+    }
+
+    fn graph(&mut self) -> Result<(), ReadGraphError> {
+        if self.peek().is_some_and(|token| is_keyword(token, "strict")) {
+            self.next();
+        }
+        let line = self.line();
+        match self.next() {
+            Some(token) if is_keyword(&token, "graph") => (),
+            Some(token) if is_keyword(&token, "digraph") => {
+                return Err(ReadGraphError::malformed(line, "directed graphs are not supported"));
+            },
+            _ => return Err(ReadGraphError::malformed(line, "expected `graph`")),
+        }
+        if let Some(DotToken::Id(..)) = self.peek() {
+            self.next();
+        }
+        self.expect(DotToken::LeftBrace, "`{`")?;
+        loop {
+            match self.peek() {
+                None => return Err(ReadGraphError::malformed(self.line(), "missing `}`")),
+                Some(DotToken::RightBrace) => {
+                    self.next();
+                    break;
+                },
+                Some(DotToken::Semicolon) | Some(DotToken::Comma) => {
+                    self.next();
+                },
+                _ => self.statement()?,
+            }
+        }
+        if self.peek().is_some() {
+            return Err(ReadGraphError::malformed(self.line(), "unexpected content after graph"));
+        }
+        Ok(())
+    }
+}
+
+/// # Read DOT
+/// * reads undirected graphs, i.e., `graph { a -- b; }`, as written by the
+///   [`Dot`](crate::traits::Dot) and [`DotExtra`](crate::traits::DotExtra) traits
+/// * `node` creates the data `T` of each vertex from its index and its attributes.
+///   All attributes are [`AttributeValue::String`]. Attributes set with `node [...]`
+///   are used as defaults for the following vertices. As in graphviz, the `label` defaults to
+///   the name of the node
+/// * vertices are indexed in the order of appearance
+/// * graph and edge attributes as well as ports are ignored
+/// # Errors
+/// * [`Malformed`](ReadGraphError::Malformed) for invalid DOT, directed graphs and subgraphs
+/// * [`SelfLoop`](ReadGraphError::SelfLoop) and [`DuplicateEdge`](ReadGraphError::DuplicateEdge)
+/// # Example
+/// ```
+/// use net_ensembles::{Graph, EmptyNode, traits::*};
+/// use net_ensembles::io::read_dot;
+///
+/// let graph = Graph::<EmptyNode>::complete_graph(4);
+/// let mut buf = Vec::new();
+/// graph.dot_from_indices(&mut buf, "", |index| format!("vertex {}", index)).unwrap();
+///
+/// let mut labels = Vec::new();
+/// let read: Graph<EmptyNode> = read_dot(
+///     buf.as_slice(),
+///     |_, attributes| {
+///         labels.push(attributes["label"].to_string());
+///         EmptyNode{}
+///     }
+/// ).unwrap();
+/// assert_eq!(read.edge_count(), 6);
+/// assert_eq!(labels[3], "vertex 3");
+/// ```
+pub fn read_dot<T, R, F>(mut reader: R, mut node: F) -> Result<Graph<T>, ReadGraphError>
+where T: Node,
+      R: Read,
+      F: FnMut(usize, &HashMap<String, AttributeValue>) -> T
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    let mut parser = DotParser{
+        tokens: tokenize(&content)?,
+        position: 0,
+        node_defaults: HashMap::new(),
+        ids: HashMap::new(),
+        nodes: Vec::new(),
+        edges: Vec::new(),
+    };
+    parser.graph()?;
+
+    let mut graph = build_graph::<T>(parser.nodes.len(), &parser.edges)?;
+    for (index, attributes) in parser.nodes.iter().enumerate() {
+        *graph.at_mut(index) = node(index, attributes);
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyNode, traits::*};
+
+    type Attributes = Vec<HashMap<String, AttributeValue>>;
+
+    fn read(data: &str) -> Result<(Graph<EmptyNode>, Attributes), ReadGraphError>
+    {
+        let mut attributes = Vec::new();
+        let graph = read_dot(
+            data.as_bytes(),
+            |_, a| {
+                attributes.push(a.clone());
+                EmptyNode{}
+            }
+        )?;
+        Ok((graph, attributes))
+    }
+
+    #[test]
+    fn hand_written()
+    {
+        let data = r#"/* drawn by hand */
+strict graph "my graph" {
+    // defaults
+    node [shape=box, color=red];
+    rankdir = LR
+    a -- b -- c [weight=2];
+    c [label="the \"c\"", color=blue]
+    node [color=green]
+    d:port1 -- a
+    # lines starting with `#` are ignored -- like preprocessor output
+    <<b>html</b>> -- -1.5
+}"#;
+        let (graph, attributes) = read(data).unwrap();
+        assert_eq!(graph.vertex_count(), 6);
+        assert_eq!(graph.edge_count(), 4);
+        assert!(graph.container(0).is_adjacent(1));
+        assert!(graph.container(1).is_adjacent(2));
+        assert!(graph.container(3).is_adjacent(0));
+        assert!(graph.container(4).is_adjacent(5));
+
+        let string = |index: usize, key: &str| attributes[index][key].to_string();
+        assert_eq!(string(0, "label"), "a");
+        assert_eq!(string(0, "color"), "red");
+        assert_eq!(string(0, "shape"), "box");
+        assert_eq!(string(2, "label"), "the \"c\"");
+        assert_eq!(string(2, "color"), "blue");
+        assert_eq!(string(3, "color"), "green");
+        assert_eq!(string(4, "label"), "<b>html</b>");
+        assert_eq!(string(5, "label"), "-1.5");
+    }
+
+    #[test]
+    fn errors()
+    {
+        assert!(matches!(read("digraph { a -> b }"), Err(ReadGraphError::Malformed{line: 1, ..})));
+        assert!(matches!(read("graph {\n a -> b }"), Err(ReadGraphError::Malformed{line: 2, ..})));
+        assert!(matches!(read("graph {\n a -- b\n"), Err(ReadGraphError::Malformed{..})));
+        assert!(matches!(read("graph {\n subgraph { a } }"), Err(ReadGraphError::Malformed{line: 2, ..})));
+        assert!(matches!(read("graph {\n a [label=] }"), Err(ReadGraphError::Malformed{line: 2, ..})));
+        assert!(matches!(read("graph {\n a\n a -- a }"), Err(ReadGraphError::SelfLoop{line: 3, index: 0})));
+        assert!(matches!(
+            read("graph {\n a -- b\n b -- a }"),
+            Err(ReadGraphError::DuplicateEdge{line: 3, edge: (1, 0)})
+        ));
+        assert!(matches!(read("graph { } graph { }"), Err(ReadGraphError::Malformed{line: 1, ..})));
+    }
+}
@@ -0,0 +1,164 @@
+use {
+    super::*,
+    crate::{
+        traits::*,
+        GenericGraph
+    },
+    std::io::{BufRead, Write}
+};
+
+/// # Read edge list
+/// * every line contains one edge, i.e., two vertex indices separated by whitespace and/or a comma
+/// * everything after `#` or `%` is a comment, empty lines are ignored
+/// * optional header: if the first line, that is not empty or a comment, contains only a single
+///   number, it is the number of vertices. Otherwise the number of vertices is the largest index plus one
+/// * indices start at 0
+/// # Errors
+/// * [`Malformed`](ReadGraphError::Malformed) if a line does not consist of exactly two indices
+/// * [`IndexOutOfRange`](ReadGraphError::IndexOutOfRange) if an index is not smaller than the vertex count of the header
+/// * [`SelfLoop`](ReadGraphError::SelfLoop) and [`DuplicateEdge`](ReadGraphError::DuplicateEdge)
+/// # Example
+/// ```
+/// use net_ensembles::{Graph, EmptyNode};
+/// use net_ensembles::io::read_edge_list;
+///
+/// let data = "# my graph\n4\n0 1\n1,2 % comment\n2, 0\n";
+/// let graph: Graph<EmptyNode> = read_edge_list(data.as_bytes()).unwrap();
+/// assert_eq!(graph.vertex_count(), 4);
+/// assert_eq!(graph.edge_count(), 3);
+/// ```
+pub fn read_edge_list<T, R>(reader: R) -> Result<Graph<T>, ReadGraphError>
+where T: Node,
+      R: BufRead
+{
+    let mut vertex_count = None;
+    let mut first = true;
+    let mut edges = Vec::new();
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        let content = line.split(['#', '%'])
+            .next()
+            .unwrap();
+        let mut fields = content.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|field| !field.is_empty());
+
+        let parse = |field: &str| {
+            field.parse::<usize>()
+                .map_err(|_| ReadGraphError::malformed(line_number, format!("invalid index `{}`", field)))
+        };
+
+        let i = match fields.next() {
+            None => continue,
+            Some(field) => parse(field)?
+        };
+        let is_first = first;
+        first = false;
+        let j = match fields.next() {
+            None if is_first => {
+                vertex_count = Some(i);
+                continue;
+            },
+            None => return Err(ReadGraphError::malformed(line_number, "expected two indices")),
+            Some(field) => parse(field)?
+        };
+        if fields.next().is_some() {
+            return Err(ReadGraphError::malformed(line_number, "expected two indices"));
+        }
+        edges.push((line_number, i, j));
+    }
+
+    let vertex_count = match vertex_count {
+        Some(vertex_count) => vertex_count,
+        None => {
+            let mut vertex_count = 0;
+            for &(line, i, j) in edges.iter() {
+                let needed = i.max(j)
+                    .checked_add(1)
+                    .ok_or_else(|| ReadGraphError::malformed(line, "vertex index too large"))?;
+                vertex_count = vertex_count.max(needed);
+            }
+            vertex_count
+        }
+    };
+    build_graph(vertex_count, &edges)
+}
+
+/// # Write edge list
+/// * writes one edge `i j` per line, with `i < j`
+/// * `with_header`: write the number of vertices in the first line. Without header,
+///   isolated vertices with large indices are lost
+/// * can be read by [`read_edge_list`]
+pub fn write_edge_list<T, A, W>(graph: &GenericGraph<T, A>, mut writer: W, with_header: bool) -> Result<(), std::io::Error>
+where A: AdjContainer<T>,
+      W: Write
+{
+    if with_header {
+        writeln!(writer, "{}", graph.vertex_count())?;
+    }
+    for (i, container) in graph.container_iter().enumerate() {
+        for &j in container.neighbors() {
+            if i < j {
+                writeln!(writer, "{} {}", i, j)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyNode, ErEnsembleC, rand::SeedableRng};
+    use rand_pcg::Pcg64;
+
+    fn read(data: &str) -> Result<Graph<EmptyNode>, ReadGraphError>
+    {
+        read_edge_list(data.as_bytes())
+    }
+
+    #[test]
+    fn round_trip()
+    {
+        let rng = Pcg64::seed_from_u64(1239);
+        let e = ErEnsembleC::<EmptyNode, _>::new(100, 3.0, rng);
+        for with_header in [true, false] {
+            let mut buf = Vec::new();
+            write_edge_list(e.graph(), &mut buf, with_header).unwrap();
+            let graph: Graph<EmptyNode> = read_edge_list(buf.as_slice()).unwrap();
+            assert_eq!(graph.edge_count(), e.graph().edge_count());
+            for i in 0..graph.vertex_count() {
+                for &j in e.graph().container(i).neighbors() {
+                    assert!(graph.container(i).is_adjacent(j));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn errors()
+    {
+        let graph = read("% only comments\n\n   \n").unwrap();
+        assert_eq!(graph.vertex_count(), 0);
+        let graph = read("0 3\n").unwrap();
+        assert_eq!(graph.vertex_count(), 4);
+
+        assert!(matches!(read("3\n0 1\n1\n"), Err(ReadGraphError::Malformed{line: 3, ..})));
+        assert!(matches!(read("0 1 2\n"), Err(ReadGraphError::Malformed{line: 1, ..})));
+        assert!(matches!(read("0 a\n"), Err(ReadGraphError::Malformed{line: 1, ..})));
+        assert!(matches!(read("0 -1\n"), Err(ReadGraphError::Malformed{line: 1, ..})));
+        assert!(matches!(
+            read(&format!("0 1\n0 {}\n", usize::MAX)),
+            Err(ReadGraphError::Malformed{line: 2, ..})
+        ));
+        assert!(matches!(
+            read("3\n0 1\n# comment\n1 3\n"),
+            Err(ReadGraphError::IndexOutOfRange{line: 4, index: 3, vertex_count: 3})
+        ));
+        assert!(matches!(read("1 1\n"), Err(ReadGraphError::SelfLoop{line: 1, index: 1})));
+        assert!(matches!(
+            read("0 1\n1,0\n"),
+            Err(ReadGraphError::DuplicateEdge{line: 2, edge: (1, 0)})
+        ));
+    }
+}
@@ -0,0 +1,422 @@
+use {
+    super::*,
+    crate::{
+        traits::*,
+        GenericGraph
+    },
+    std::{
+        collections::HashMap,
+        io::{Read, Write}
+    }
+};
+
+/// Values of the GML key-value lists
+#[derive(Debug)]
+enum GmlValue {
+    Int(i64),
+    Real(f64),
+    String(String),
+    List(Vec<GmlEntry>),
+}
+
+#[derive(Debug)]
+struct GmlEntry {
+    key: String,
+    value: GmlValue,
+    line: usize,
+}
+
+struct GmlParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl<'a> GmlParser<'a> {
+    fn new(s: &'a str) -> Self {
+        Self{
+            chars: s.chars().peekable(),
+            line: 1,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if c == '#' {
+                // comment until end of line
+                while self.chars.peek().is_some_and(|&c| c != '\n') {
+                    self.chars.next();
+                }
+            } else if c.is_whitespace() {
+                if c == '\n' {
+                    self.line += 1;
+                }
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn take_while<P: Fn(char) -> bool>(&mut self, predicate: P) -> String {
+        let mut s = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !predicate(c) {
+                break;
+            }
+            s.push(c);
+            self.chars.next();
+        }
+        s
+    }
+
+    /// parses key-value pairs until `]` or the end of the input
+    fn list(&mut self, nested: bool) -> Result<Vec<GmlEntry>, ReadGraphError> {
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                None if nested => return Err(ReadGraphError::malformed(self.line, "missing `]`")),
+                None => return Ok(entries),
+                Some(']') if nested => {
+                    self.chars.next();
+                    return Ok(entries);
+                },
+                _ => ()
+            }
+            let line = self.line;
+            let key = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+            if key.is_empty() || !key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+                return Err(ReadGraphError::malformed(line, "expected key"));
+            }
+            self.skip_whitespace();
+            let value = self.value(&key)?;
+            entries.push(GmlEntry{key, value, line});
+        }
+    }
+
+    fn value(&mut self, key: &str) -> Result<GmlValue, ReadGraphError> {
+        let line = self.line;
+        match self.chars.peek() {
+            Some('[') => {
+                self.chars.next();
+                self.list(true).map(GmlValue::List)
+            },
+            Some('"') => {
+                self.chars.next();
+                let text = self.take_while(|c| c != '"');
+                if self.chars.next().is_none() {
+                    return Err(ReadGraphError::malformed(line, "unterminated string"));
+                }
+                self.line += text.matches('\n').count();
+                Ok(GmlValue::String(unescape(&text)))
+            },
+            _ => {
+                let number = self.take_while(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+                if let Ok(int) = number.parse() {
+                    Ok(GmlValue::Int(int))
+                } else if let Ok(real) = number.parse() {
+                    Ok(GmlValue::Real(real))
+                } else {
+                    Err(ReadGraphError::malformed(line, format!("invalid value for `{}`", key)))
+                }
+            }
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('"', "&quot;")
+}
+
+fn unescape(s: &str) -> String {
+    const ENTITIES: [(&str, char); 4] = [("&amp;", '&'), ("&quot;", '"'), ("&lt;", '<'), ("&gt;", '>')];
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        // GML allows a plain `&`
+        let (length, c) = ENTITIES.iter()
+            .find(|(entity, _)| rest.starts_with(entity))
+            .map_or((1, '&'), |&(entity, c)| (entity.len(), c));
+        result.push(c);
+        rest = &rest[length..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// adds scalar values to `attributes`, nested lists are flattened as `parent.key`
+fn flatten(entries: &[GmlEntry], prefix: &str, attributes: &mut HashMap<String, AttributeValue>) {
+    for entry in entries {
+        let key = format!("{}{}", prefix, entry.key);
+        let value = match &entry.value {
+            GmlValue::Int(i) => AttributeValue::Int(*i),
+            GmlValue::Real(r) => AttributeValue::Double(*r),
+            GmlValue::String(s) => AttributeValue::String(s.clone()),
+            GmlValue::List(list) => {
+                flatten(list, &format!("{}.", key), attributes);
+                continue;
+            }
+        };
+        attributes.insert(key, value);
+    }
+}
+
+fn int_entry(entries: &[GmlEntry], key: &str, line: usize) -> Result<i64, ReadGraphError> {
+    match entries.iter().find(|entry| entry.key == key) {
+        Some(GmlEntry{value: GmlValue::Int(i), ..}) => Ok(*i),
+        Some(entry) => Err(ReadGraphError::malformed(entry.line, format!("`{}` has to be an integer", key))),
+        None => Err(ReadGraphError::malformed(line, format!("missing `{}`", key))),
+    }
+}
+
+/// # Read GML
+/// * reads the first `graph` of the file into a `Graph<T>`
+/// * `node` creates the data `T` of each vertex from its index and its attributes.
+///   Attributes of nested lists are flattened, e.g., `graphics [ x 1.0 ]` becomes `graphics.x`
+/// * vertices are indexed in the order of appearance, the GML `id` is part of the attributes
+/// * edge attributes are ignored
+/// # Errors
+/// * [`Malformed`](ReadGraphError::Malformed) for invalid GML, directed graphs or unknown node ids
+/// * [`SelfLoop`](ReadGraphError::SelfLoop) and [`DuplicateEdge`](ReadGraphError::DuplicateEdge)
+pub fn read_gml<T, R, F>(mut reader: R, mut node: F) -> Result<Graph<T>, ReadGraphError>
+where T: Node,
+      R: Read,
+      F: FnMut(usize, &HashMap<String, AttributeValue>) -> T
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    let entries = GmlParser::new(&content).list(false)?;
+
+    let graph_entries = entries.iter()
+        .find_map(|entry| match &entry.value {
+            GmlValue::List(list) if entry.key == "graph" => Some(list),
+            _ => None
+        }).ok_or_else(|| ReadGraphError::malformed(1, "missing `graph`"))?;
+
+    let mut ids = HashMap::new();
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    for entry in graph_entries {
+        match (entry.key.as_str(), &entry.value) {
+            ("directed", GmlValue::Int(1)) => return Err(
+                ReadGraphError::malformed(entry.line, "directed graphs are not supported")
+            ),
+            ("node", GmlValue::List(list)) => {
+                let id = int_entry(list, "id", entry.line)?;
+                if ids.insert(id, nodes.len()).is_some() {
+                    return Err(ReadGraphError::malformed(entry.line, format!("duplicate node id {}", id)));
+                }
+                let mut attributes = HashMap::new();
+                flatten(list, "", &mut attributes);
+                nodes.push(attributes);
+            },
+            ("edge", GmlValue::List(list)) => {
+                let source = int_entry(list, "source", entry.line)?;
+                let target = int_entry(list, "target", entry.line)?;
+                edges.push((entry.line, source, target));
+            },
+            _ => ()
+        }
+    }
+
+    let mut indexed_edges = Vec::with_capacity(edges.len());
+    for (line, source, target) in edges {
+        let lookup = |id: i64| ids.get(&id)
+            .copied()
+            .ok_or_else(|| ReadGraphError::malformed(line, format!("unknown node id {}", id)));
+        indexed_edges.push((line, lookup(source)?, lookup(target)?));
+    }
+
+    let mut graph = build_graph::<T>(nodes.len(), &indexed_edges)?;
+    for (index, attributes) in nodes.iter().enumerate() {
+        *graph.at_mut(index) = node(index, attributes);
+    }
+    Ok(graph)
+}
+
+fn invalid_input(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+}
+
+/// # Write GML
+/// * `attributes` creates the attributes of each vertex from its index and its data `T`,
+///   similar to the label closures of the [`Dot`](crate::traits::Dot) trait
+/// * attributes of the adjacency container, e.g., the coordinates of a
+///   [`SpacialGraph`](crate::spacial::SpacialGraph), are written automatically,
+///   see [`ExportAttributes`]
+/// * vertex `i` gets the id `i`
+/// * booleans are written as integers `0` and `1`
+/// * returns an error of kind `InvalidInput` for non-finite numbers or if the name of an attribute
+///   is not a valid GML key or `id`
+/// # Example
+/// ```
+/// use net_ensembles::{Graph, EmptyNode};
+/// use net_ensembles::io::{write_gml, read_gml, AttributeValue};
+///
+/// let graph = Graph::<EmptyNode>::complete_graph(4);
+/// let mut buf = Vec::new();
+/// write_gml(
+///     &graph,
+///     &mut buf,
+///     |index, _| vec![("label", AttributeValue::String(format!("v{}", index)))]
+/// ).unwrap();
+///
+/// let mut labels = Vec::new();
+/// let read: Graph<EmptyNode> = read_gml(
+///     buf.as_slice(),
+///     |_, attributes| {
+///         labels.push(attributes["label"].to_string());
+///         EmptyNode{}
+///     }
+/// ).unwrap();
+/// assert_eq!(read.edge_count(), 6);
+/// assert_eq!(labels, vec!["v0", "v1", "v2", "v3"]);
+/// ```
+pub fn write_gml<T, A, W, F, S>(graph: &GenericGraph<T, A>, mut writer: W, mut attributes: F) -> Result<(), std::io::Error>
+where A: AdjContainer<T> + ExportAttributes,
+      W: Write,
+      F: FnMut(usize, &T) -> Vec<(S, AttributeValue)>,
+      S: Into<String>
+{
+    writeln!(writer, "graph [")?;
+    writeln!(writer, "  directed 0")?;
+    for (index, container) in graph.container_iter().enumerate() {
+        writeln!(writer, "  node [")?;
+        writeln!(writer, "    id {}", index)?;
+        let mut data = container.export_attributes();
+        data.extend(
+            attributes(index, container.contained())
+                .into_iter()
+                .map(|(name, value)| (name.into(), value))
+        );
+        for (name, value) in data {
+            let valid_key = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_key || name == "id" {
+                return Err(invalid_input(format!("invalid GML key `{}`", name)));
+            }
+            match value {
+                AttributeValue::Bool(b) => writeln!(writer, "    {} {}", name, b as u8)?,
+                AttributeValue::Int(i) => writeln!(writer, "    {} {}", name, i)?,
+                AttributeValue::Double(d) if d.is_finite() => writeln!(writer, "    {} {:?}", name, d)?,
+                AttributeValue::Double(_) => return Err(
+                    invalid_input(format!("non-finite number for `{}`", name))
+                ),
+                AttributeValue::String(s) => writeln!(writer, "    {} \"{}\"", name, escape(&s))?,
+            }
+        }
+        writeln!(writer, "  ]")?;
+    }
+    for (i, container) in graph.container_iter().enumerate() {
+        for &j in container.neighbors() {
+            if i < j {
+                writeln!(writer, "  edge [")?;
+                writeln!(writer, "    source {}", i)?;
+                writeln!(writer, "    target {}", j)?;
+                writeln!(writer, "  ]")?;
+            }
+        }
+    }
+    writeln!(writer, "]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyNode, spacial::SpacialEnsemble, rand::SeedableRng};
+    use rand_pcg::Pcg64;
+
+    fn read(data: &str) -> Result<Graph<EmptyNode>, ReadGraphError>
+    {
+        read_gml(data.as_bytes(), |_, _| EmptyNode{})
+    }
+
+    #[test]
+    fn spacial_round_trip()
+    {
+        let rng = Pcg64::seed_from_u64(9123);
+        let e = SpacialEnsemble::<EmptyNode, _>::new(40, rng, 0.95, 3.0);
+        let mut buf = Vec::new();
+        write_gml(
+            e.as_ref(),
+            &mut buf,
+            |index, _| vec![
+                ("name", AttributeValue::String(format!("\"{}\" & more", index))),
+                ("odd", AttributeValue::Bool(index % 2 == 1)),
+            ]
+        ).unwrap();
+
+        let mut read_attributes = Vec::new();
+        let graph: Graph<EmptyNode> = read_gml(
+            buf.as_slice(),
+            |_, attributes| {
+                read_attributes.push(attributes.clone());
+                EmptyNode{}
+            }
+        ).unwrap();
+        assert_eq!(graph.edge_count(), e.as_ref().edge_count());
+        for (index, container) in e.as_ref().container_iter().enumerate() {
+            let attributes = &read_attributes[index];
+            assert_eq!(attributes["x"].as_f64(), Some(container.get_x()));
+            assert_eq!(attributes["y"].as_f64(), Some(container.get_y()));
+            assert_eq!(attributes["name"].as_str(), Some(format!("\"{}\" & more", index).as_str()));
+            assert_eq!(attributes["odd"].as_int(), Some(index as i64 % 2));
+            for &j in container.neighbors() {
+                assert!(graph.container(index).is_adjacent(j));
+            }
+        }
+
+        let mut buf = Vec::new();
+        let result = write_gml(e.as_ref(), &mut buf, |_, _| vec![("id", AttributeValue::Int(1))]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn foreign_gml()
+    {
+        let data = r#"Creator "some tool"
+# comment
+graph
+[
+  label "test"
+  node [ id 7 label "seven" graphics [ x 1.5 y -2e1 ] ]
+  node [ id 3 ]
+  node [ id 4 ]
+  edge [ source 7 target 4 weight 1.0 ]
+  edge [ source 4 target 3 ]
+]"#;
+        let mut read_attributes = Vec::new();
+        let graph: Graph<EmptyNode> = read_gml(
+            data.as_bytes(),
+            |_, attributes| {
+                read_attributes.push(attributes.clone());
+                EmptyNode{}
+            }
+        ).unwrap();
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.container(0).is_adjacent(2));
+        assert!(graph.container(1).is_adjacent(2));
+        assert_eq!(read_attributes[0]["label"].as_str(), Some("seven"));
+        assert_eq!(read_attributes[0]["graphics.x"].as_f64(), Some(1.5));
+        assert_eq!(read_attributes[0]["graphics.y"].as_f64(), Some(-20.0));
+        assert_eq!(read_attributes[1]["id"].as_int(), Some(3));
+
+        assert!(matches!(
+            read("graph [\n directed 1\n]"),
+            Err(ReadGraphError::Malformed{line: 2, ..})
+        ));
+        assert!(matches!(
+            read("graph [\n node [ id 1 ]\n edge [ source 1 target 2 ]\n]"),
+            Err(ReadGraphError::Malformed{line: 3, ..})
+        ));
+        assert!(matches!(
+            read("graph [\n node [ id 1 ]\n edge [ source 1 target 1 ]\n]"),
+            Err(ReadGraphError::SelfLoop{line: 3, index: 0})
+        ));
+        assert!(matches!(read("graph [\n node [ id 1 ]\n"), Err(ReadGraphError::Malformed{..})));
+        assert!(matches!(read("graph [ node [ id x ] ]"), Err(ReadGraphError::Malformed{line: 1, ..})));
+    }
+}
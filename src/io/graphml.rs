@@ -0,0 +1,664 @@
+use {
+    super::*,
+    crate::{
+        traits::*,
+        GenericGraph
+    },
+    std::{
+        collections::HashMap,
+        io::{Read, Write}
+    }
+};
+
+fn type_name(value: &AttributeValue) -> &'static str {
+    match value {
+        AttributeValue::Bool(_) => "boolean",
+        AttributeValue::Int(_) => "long",
+        AttributeValue::Double(_) => "double",
+        AttributeValue::String(_) => "string",
+    }
+}
+
+fn parse_value(type_name: &str, text: &str) -> Option<AttributeValue> {
+    let text = text.trim();
+    match type_name {
+        "boolean" => match text.to_ascii_lowercase().as_str() {
+            "true" | "1" => Some(AttributeValue::Bool(true)),
+            "false" | "0" => Some(AttributeValue::Bool(false)),
+            _ => None
+        },
+        "int" | "long" => text.parse().ok().map(AttributeValue::Int),
+        "float" | "double" => match text {
+            "INF" => Some(f64::INFINITY),
+            "-INF" => Some(f64::NEG_INFINITY),
+            _ => text.parse().ok()
+        }.map(AttributeValue::Double),
+        "string" => Some(AttributeValue::String(text.to_owned())),
+        _ => None
+    }
+}
+
+/// text of `<data>`, escaped
+fn value_text(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::Double(d) if d.is_infinite() => {
+            if *d > 0.0 {
+                "INF".to_owned()
+            } else {
+                "-INF".to_owned()
+            }
+        },
+        AttributeValue::String(s) => escape_xml(s),
+        _ => value.to_string()
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape_xml(s: &str, line: usize) -> Result<String, ReadGraphError> {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(';')
+            .ok_or_else(|| ReadGraphError::malformed(line, "unterminated entity"))?;
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| ReadGraphError::malformed(line, format!("unknown entity `&{};`", entity)))?
+            }
+        };
+        result.push(c);
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// # Write GraphML
+/// * `attributes` creates the attributes of each vertex from its index and its data `T`,
+///   similar to the label closures of the [`Dot`](crate::traits::Dot) trait
+/// * the attribute types are derived from the [`AttributeValue`] variants
+/// * attributes of the adjacency container, e.g., the coordinates of a
+///   [`SpacialGraph`](crate::spacial::SpacialGraph), are written automatically,
+///   see [`ExportAttributes`]
+/// * vertex `i` gets the id `n{i}`
+/// * returns an error of kind `InvalidInput`, if the same attribute name is used with different types
+/// # Example
+/// ```
+/// use net_ensembles::{Graph, CountingNode};
+/// use net_ensembles::io::{write_graphml, read_graphml, AttributeValue};
+///
+/// let graph = Graph::<CountingNode>::complete_graph(3);
+/// let mut buf = Vec::new();
+/// write_graphml(
+///     &graph,
+///     &mut buf,
+///     |_, node| vec![("number", AttributeValue::Int(node.number() as i64))]
+/// ).unwrap();
+///
+/// let read: Graph<CountingNode> = read_graphml(
+///     buf.as_slice(),
+///     |_, attributes| CountingNode{
+///         index: attributes["number"].as_int().unwrap() as usize
+///     }
+/// ).unwrap();
+/// assert_eq!(read.edge_count(), 3);
+/// assert_eq!(read.at(2).number(), 2);
+/// ```
+pub fn write_graphml<T, A, W, F, S>(graph: &GenericGraph<T, A>, mut writer: W, mut attributes: F) -> Result<(), std::io::Error>
+where A: AdjContainer<T> + ExportAttributes,
+      W: Write,
+      F: FnMut(usize, &T) -> Vec<(S, AttributeValue)>,
+      S: Into<String>
+{
+    // collect attributes first, the keys have to be declared before the nodes
+    let mut keys: Vec<(String, &'static str)> = Vec::new();
+    let mut key_ids: HashMap<String, usize> = HashMap::new();
+    let mut node_data = Vec::with_capacity(graph.vertex_count());
+    for (index, container) in graph.container_iter().enumerate() {
+        let mut data = container.export_attributes();
+        data.extend(
+            attributes(index, container.contained())
+                .into_iter()
+                .map(|(name, value)| (name.into(), value))
+        );
+        let mut with_ids = Vec::with_capacity(data.len());
+        for (name, value) in data {
+            let id = match key_ids.get(&name) {
+                Some(&id) => {
+                    if keys[id].1 != type_name(&value) {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            format!("attribute `{}` used with different types", name)
+                        ));
+                    }
+                    id
+                },
+                None => {
+                    let id = keys.len();
+                    keys.push((name.clone(), type_name(&value)));
+                    key_ids.insert(name, id);
+                    id
+                }
+            };
+            with_ids.push((id, value));
+        }
+        node_data.push(with_ids);
+    }
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">"#
+    )?;
+    for (id, (name, type_name)) in keys.iter().enumerate() {
+        writeln!(
+            writer,
+            r#"  <key id="d{}" for="node" attr.name="{}" attr.type="{}"/>"#,
+            id,
+            escape_xml(name),
+            type_name
+        )?;
+    }
+    writeln!(writer, r#"  <graph id="G" edgedefault="undirected">"#)?;
+    for (index, data) in node_data.iter().enumerate() {
+        if data.is_empty() {
+            writeln!(writer, r#"    <node id="n{}"/>"#, index)?;
+        } else {
+            writeln!(writer, r#"    <node id="n{}">"#, index)?;
+            for (id, value) in data {
+                writeln!(writer, r#"      <data key="d{}">{}</data>"#, id, value_text(value))?;
+            }
+            writeln!(writer, "    </node>")?;
+        }
+    }
+    for (i, container) in graph.container_iter().enumerate() {
+        for &j in container.neighbors() {
+            if i < j {
+                writeln!(writer, r#"    <edge source="n{}" target="n{}"/>"#, i, j)?;
+            }
+        }
+    }
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
+
+/// Token of the minimal XML tokenizer
+#[derive(Debug)]
+enum XmlToken {
+    Start{
+        name: String,
+        attributes: HashMap<String, String>,
+        empty: bool,
+        line: usize,
+    },
+    End{
+        name: String,
+    },
+    Text(String),
+}
+
+/// Minimal XML tokenizer, sufficient for GraphML.
+/// Skips declarations, processing instructions and comments
+struct XmlTokenizer<'a> {
+    rest: &'a str,
+    line: usize,
+}
+
+impl<'a> XmlTokenizer<'a> {
+    fn new(s: &'a str) -> Self {
+        Self{
+            rest: s,
+            line: 1,
+        }
+    }
+
+    fn advance(&mut self, n: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(n);
+        self.line += taken.matches('\n').count();
+        self.rest = rest;
+        taken
+    }
+
+    /// advance until after `pattern`, returns everything before `pattern`
+    fn advance_past(&mut self, pattern: &str, what: &str) -> Result<&'a str, ReadGraphError> {
+        let line = self.line;
+        let pos = self.rest.find(pattern)
+            .ok_or_else(|| ReadGraphError::malformed(line, format!("unterminated {}", what)))?;
+        let taken = self.advance(pos);
+        self.advance(pattern.len());
+        Ok(taken)
+    }
+
+    fn next_token(&mut self) -> Result<Option<XmlToken>, ReadGraphError> {
+        loop {
+            if self.rest.is_empty() {
+                return Ok(None);
+            }
+            if !self.rest.starts_with('<') {
+                let pos = self.rest.find('<').unwrap_or(self.rest.len());
+                let line = self.line;
+                let text = self.advance(pos);
+                return Ok(Some(XmlToken::Text(unescape_xml(text, line)?)));
+            }
+            if self.rest.starts_with("<!--") {
+                self.advance_past("-->", "comment")?;
+            } else if self.rest.starts_with("<![CDATA[") {
+                self.advance("<![CDATA[".len());
+                let text = self.advance_past("]]>", "CDATA section")?;
+                return Ok(Some(XmlToken::Text(text.to_owned())));
+            } else if self.rest.starts_with("<?") {
+                self.advance_past("?>", "processing instruction")?;
+            } else if self.rest.starts_with("<!") {
+                self.advance_past(">", "declaration")?;
+            } else {
+                return self.tag().map(Some);
+            }
+        }
+    }
+
+    fn tag(&mut self) -> Result<XmlToken, ReadGraphError> {
+        let line = self.line;
+        self.advance(1);
+        // `>` is allowed in quoted attribute values
+        let mut quote = None;
+        let end = self.rest.char_indices()
+            .find(
+                |&(_, c)|
+                {
+                    match quote {
+                        Some(q) if c == q => quote = None,
+                        Some(_) => (),
+                        None if c == '"' || c == '\'' => quote = Some(c),
+                        None => return c == '>',
+                    }
+                    false
+                }
+            ).map(|(pos, _)| pos)
+            .ok_or_else(|| ReadGraphError::malformed(line, "unterminated tag"))?;
+        let content = self.advance(end);
+        self.advance(1);
+        if let Some(name) = content.strip_prefix('/') {
+            return Ok(XmlToken::End{name: local_name(name.trim()).to_owned()});
+        }
+        let (content, empty) = match content.strip_suffix('/') {
+            Some(content) => (content, true),
+            None => (content, false)
+        };
+        let name_end = content.find(char::is_whitespace)
+            .unwrap_or(content.len());
+        let name = local_name(&content[..name_end]).to_owned();
+        let mut attributes = HashMap::new();
+        let mut rest = content[name_end..].trim_start();
+        while !rest.is_empty() {
+            let eq = rest.find('=')
+                .ok_or_else(|| ReadGraphError::malformed(line, "invalid attribute"))?;
+            let key = local_name(rest[..eq].trim()).to_owned();
+            rest = rest[eq + 1..].trim_start();
+            let quote = rest.chars()
+                .next()
+                .filter(|&c| c == '"' || c == '\'')
+                .ok_or_else(|| ReadGraphError::malformed(line, "attribute value has to be quoted"))?;
+            rest = &rest[1..];
+            let end = rest.find(quote)
+                .ok_or_else(|| ReadGraphError::malformed(line, "unterminated attribute value"))?;
+            attributes.insert(key, unescape_xml(&rest[..end], line)?);
+            rest = rest[end + 1..].trim_start();
+        }
+        Ok(XmlToken::Start{name, attributes, empty, line})
+    }
+
+    /// skips everything until the end tag matching an already read start tag
+    fn skip_element(&mut self) -> Result<(), ReadGraphError> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next_token()? {
+                None => return Err(ReadGraphError::malformed(self.line, "unexpected end of file")),
+                Some(XmlToken::Start{empty: false, ..}) => depth += 1,
+                Some(XmlToken::End{..}) => depth -= 1,
+                _ => ()
+            }
+        }
+        Ok(())
+    }
+
+    /// text content of an element, nested elements are skipped
+    fn text_content(&mut self) -> Result<String, ReadGraphError> {
+        let mut text = String::new();
+        loop {
+            match self.next_token()? {
+                None => return Err(ReadGraphError::malformed(self.line, "unexpected end of file")),
+                Some(XmlToken::Text(t)) => text.push_str(&t),
+                Some(XmlToken::Start{empty: false, ..}) => self.skip_element()?,
+                Some(XmlToken::Start{..}) => (),
+                Some(XmlToken::End{..}) => return Ok(text),
+            }
+        }
+    }
+}
+
+/// removes namespace prefix
+fn local_name(name: &str) -> &str {
+    name.rsplit(':')
+        .next()
+        .unwrap()
+}
+
+fn required<'a>(attributes: &'a HashMap<String, String>, key: &str, line: usize) -> Result<&'a str, ReadGraphError> {
+    attributes.get(key)
+        .map(String::as_str)
+        .ok_or_else(|| ReadGraphError::malformed(line, format!("missing attribute `{}`", key)))
+}
+
+struct GraphMlKey {
+    name: String,
+    type_name: String,
+    default: Option<String>,
+    for_nodes: bool,
+    // line of the `<key>` element, for errors in the default
+    line: usize,
+}
+
+/// # Read GraphML
+/// * reads the first graph of the file into a `Graph<T>`
+/// * `node` creates the data `T` of each vertex from its index and its attributes,
+///   which are keyed by their `attr.name`. Defaults declared by the keys are used for missing values
+/// * vertices are indexed in the order of appearance
+/// * edge attributes are ignored
+/// # Errors
+/// * [`Malformed`](ReadGraphError::Malformed) for invalid XML, directed graphs, unknown node ids
+///   or invalid attribute values
+/// * [`SelfLoop`](ReadGraphError::SelfLoop) and [`DuplicateEdge`](ReadGraphError::DuplicateEdge)
+pub fn read_graphml<T, R, F>(mut reader: R, mut node: F) -> Result<Graph<T>, ReadGraphError>
+where T: Node,
+      R: Read,
+      F: FnMut(usize, &HashMap<String, AttributeValue>) -> T
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    let mut tokenizer = XmlTokenizer::new(&content);
+
+    let mut keys: HashMap<String, GraphMlKey> = HashMap::new();
+    let mut node_ids: HashMap<String, usize> = HashMap::new();
+    let mut nodes: Vec<HashMap<String, AttributeValue>> = Vec::new();
+    let mut edges: Vec<(usize, String, String)> = Vec::new();
+    let mut graph_depth = 0;
+    let mut graph_read = false;
+    let mut current_node: Option<usize> = None;
+
+    while let Some(token) = tokenizer.next_token()? {
+        let (name, attributes, empty, line) = match token {
+            XmlToken::Start{name, attributes, empty, line} => (name, attributes, empty, line),
+            XmlToken::End{name, ..} => {
+                match name.as_str() {
+                    "graph" => graph_depth -= 1,
+                    "node" => current_node = None,
+                    _ => ()
+                }
+                continue;
+            },
+            XmlToken::Text(_) => continue,
+        };
+        match name.as_str() {
+            "graphml" => (),
+            "key" => {
+                let id = required(&attributes, "id", line)?.to_owned();
+                let for_nodes = matches!(
+                    attributes.get("for").map(String::as_str),
+                    None | Some("node") | Some("all")
+                );
+                let key_name = attributes.get("attr.name")
+                    .cloned()
+                    .unwrap_or_else(|| id.clone());
+                let type_name = attributes.get("attr.type")
+                    .cloned()
+                    .unwrap_or_else(|| "string".to_owned());
+                let mut default = None;
+                if !empty {
+                    // look for <default>
+                    loop {
+                        match tokenizer.next_token()? {
+                            None => return Err(ReadGraphError::malformed(line, "unterminated key")),
+                            Some(XmlToken::Start{name, empty: false, ..}) if name == "default" => {
+                                default = Some(tokenizer.text_content()?);
+                            },
+                            Some(XmlToken::Start{empty: false, ..}) => tokenizer.skip_element()?,
+                            Some(XmlToken::End{..}) => break,
+                            _ => ()
+                        }
+                    }
+                }
+                keys.insert(id, GraphMlKey{name: key_name, type_name, default, for_nodes, line});
+            },
+            "graph" => {
+                if graph_read || graph_depth > 0 {
+                    return Err(ReadGraphError::malformed(line, "nested or multiple graphs are not supported"));
+                }
+                if attributes.get("edgedefault").map(String::as_str) == Some("directed") {
+                    return Err(ReadGraphError::malformed(line, "directed graphs are not supported"));
+                }
+                graph_read = true;
+                graph_depth += 1;
+                if empty {
+                    graph_depth -= 1;
+                }
+            },
+            "node" if graph_depth == 1 && current_node.is_none() => {
+                let id = required(&attributes, "id", line)?.to_owned();
+                if node_ids.insert(id, nodes.len()).is_some() {
+                    return Err(ReadGraphError::malformed(line, "duplicate node id"));
+                }
+                nodes.push(HashMap::new());
+                if !empty {
+                    current_node = Some(nodes.len() - 1);
+                }
+            },
+            "data" if current_node.is_some() => {
+                let key_id = required(&attributes, "key", line)?;
+                let key = keys.get(key_id)
+                    .ok_or_else(|| ReadGraphError::malformed(line, format!("unknown key `{}`", key_id)))?;
+                let text = if empty {
+                    String::new()
+                } else {
+                    tokenizer.text_content()?
+                };
+                let value = parse_value(&key.type_name, &text)
+                    .ok_or_else(|| ReadGraphError::malformed(line, format!("invalid value `{}` for key `{}`", text, key_id)))?;
+                nodes[current_node.unwrap()].insert(key.name.clone(), value);
+            },
+            "edge" if graph_depth == 1 && current_node.is_none() => {
+                if attributes.get("directed").map(String::as_str) == Some("true") {
+                    return Err(ReadGraphError::malformed(line, "directed edges are not supported"));
+                }
+                let source = required(&attributes, "source", line)?.to_owned();
+                let target = required(&attributes, "target", line)?.to_owned();
+                edges.push((line, source, target));
+                if !empty {
+                    tokenizer.skip_element()?;
+                }
+            },
+            "hyperedge" => return Err(ReadGraphError::malformed(line, "hyperedges are not supported")),
+            _ => {
+                if !empty {
+                    tokenizer.skip_element()?;
+                }
+            }
+        }
+    }
+
+    // defaults
+    for key in keys.values().filter(|key| key.for_nodes) {
+        if let Some(default) = key.default.as_ref() {
+            let value = parse_value(&key.type_name, default)
+                .ok_or_else(|| ReadGraphError::malformed(key.line, format!("invalid default `{}` for `{}`", default, key.name)))?;
+            for attributes in nodes.iter_mut() {
+                attributes.entry(key.name.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+    }
+
+    let mut indexed_edges = Vec::with_capacity(edges.len());
+    for (line, source, target) in edges {
+        let lookup = |id: &str| node_ids.get(id)
+            .copied()
+            .ok_or_else(|| ReadGraphError::malformed(line, format!("unknown node `{}`", id)));
+        indexed_edges.push((line, lookup(&source)?, lookup(&target)?));
+    }
+
+    let mut graph = build_graph::<T>(nodes.len(), &indexed_edges)?;
+    for (index, attributes) in nodes.iter().enumerate() {
+        *graph.at_mut(index) = node(index, attributes);
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyNode, spacial::SpacialEnsemble, rand::SeedableRng};
+    use rand_pcg::Pcg64;
+
+    #[test]
+    fn spacial_round_trip()
+    {
+        let rng = Pcg64::seed_from_u64(238);
+        let e = SpacialEnsemble::<EmptyNode, _>::new(30, rng, 0.95, 3.0);
+        let mut buf = Vec::new();
+        write_graphml(
+            e.as_ref(),
+            &mut buf,
+            |index, _| vec![
+                ("name", AttributeValue::String(format!("<{}> & \"more\"", index))),
+                ("even", AttributeValue::Bool(index % 2 == 0)),
+            ]
+        ).unwrap();
+
+        let mut coordinates = Vec::new();
+        let mut names = Vec::new();
+        let graph: Graph<EmptyNode> = read_graphml(
+            buf.as_slice(),
+            |index, attributes| {
+                coordinates.push((attributes["x"].as_f64().unwrap(), attributes["y"].as_f64().unwrap()));
+                names.push(attributes["name"].as_str().unwrap().to_owned());
+                assert_eq!(attributes["even"].as_bool(), Some(index % 2 == 0));
+                EmptyNode{}
+            }
+        ).unwrap();
+
+        assert_eq!(graph.edge_count(), e.as_ref().edge_count());
+        for (index, container) in e.as_ref().container_iter().enumerate() {
+            assert_eq!(coordinates[index], (container.get_x(), container.get_y()));
+            assert_eq!(names[index], format!("<{}> & \"more\"", index));
+            for &j in container.neighbors() {
+                assert!(graph.container(index).is_adjacent(j));
+            }
+        }
+    }
+
+    #[test]
+    fn foreign_graphml()
+    {
+        let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- written by another tool -->
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="color" attr.type="string">
+    <default>yellow</default>
+  </key>
+  <key id="d1" for="edge" attr.name="weight" attr.type="double"/>
+  <graph id="G" edgedefault="undirected">
+    <node id="a"><data key="d0">green</data></node>
+    <node id="b"/>
+    <edge source="a" target="c"><data key="d1">1.0</data></edge>
+    <node id="c"/>
+  </graph>
+</graphml>"#;
+        let mut colors = Vec::new();
+        let graph: Graph<EmptyNode> = read_graphml(
+            data.as_bytes(),
+            |_, attributes| {
+                colors.push(attributes["color"].as_str().unwrap().to_owned());
+                EmptyNode{}
+            }
+        ).unwrap();
+        assert_eq!(colors, vec!["green", "yellow", "yellow"]);
+        assert!(graph.container(0).is_adjacent(2));
+        assert_eq!(graph.edge_count(), 1);
+
+        let directed = data.replace("undirected", "directed");
+        assert!(matches!(
+            read_graphml::<EmptyNode, _, _>(directed.as_bytes(), |_, _| EmptyNode{}),
+            Err(ReadGraphError::Malformed{line: 8, ..})
+        ));
+        let invalid_default = data.replace("attr.type=\"string\"", "attr.type=\"int\"")
+            .replace(">green<", ">3<");
+        assert!(matches!(
+            read_graphml::<EmptyNode, _, _>(invalid_default.as_bytes(), |_, _| EmptyNode{}),
+            Err(ReadGraphError::Malformed{line: 4, ..})
+        ));
+        let unknown = data.replace("target=\"c\"", "target=\"d\"");
+        assert!(read_graphml::<EmptyNode, _, _>(unknown.as_bytes(), |_, _| EmptyNode{}).is_err());
+        let self_loop = data.replace("target=\"c\"", "target=\"a\"");
+        assert!(matches!(
+            read_graphml::<EmptyNode, _, _>(self_loop.as_bytes(), |_, _| EmptyNode{}),
+            Err(ReadGraphError::SelfLoop{line: 11, index: 0})
+        ));
+    }
+
+    #[test]
+    fn greater_than_in_attribute_value()
+    {
+        let data = r#"<graphml>
+  <key id="d0" for="node" attr.name="name" attr.type="string" desc='1 > 0'/>
+  <graph edgedefault="undirected">
+    <node id="a"><data key="d0" desc="a>b">x > y</data></node>
+    <node id="b"/>
+    <edge source="a" target="b" label=">"/>
+  </graph>
+</graphml>"#;
+        let mut names = Vec::new();
+        let graph: Graph<EmptyNode> = read_graphml(
+            data.as_bytes(),
+            |_, attributes| {
+                names.push(attributes.get("name").and_then(|name| name.as_str()).map(str::to_owned));
+                EmptyNode{}
+            }
+        ).unwrap();
+        assert_eq!(names, vec![Some("x > y".to_owned()), None]);
+        assert_eq!(graph.edge_count(), 1);
+
+        let unterminated = data.replace("label=\">\"/>", "label=\">/>");
+        assert!(matches!(
+            read_graphml::<EmptyNode, _, _>(unterminated.as_bytes(), |_, _| EmptyNode{}),
+            Err(ReadGraphError::Malformed{..})
+        ));
+    }
+}
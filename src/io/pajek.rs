@@ -0,0 +1,297 @@
+use {
+    super::*,
+    crate::{
+        traits::*,
+        GenericGraph
+    },
+    std::{
+        collections::HashMap,
+        io::{BufRead, Write}
+    }
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Preamble,
+    Vertices,
+    Edges,
+    EdgesList,
+}
+
+/// splits line into tokens, quoted tokens may contain whitespace.
+/// Returns the tokens together with a flag, whether they were quoted
+fn tokenize(line: &str, line_number: usize) -> Result<Vec<(&str, bool)>, ReadGraphError> {
+    let mut tokens = Vec::new();
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"')
+                .ok_or_else(|| ReadGraphError::malformed(line_number, "unterminated label"))?;
+            tokens.push((&quoted[..end], true));
+            rest = quoted[end + 1..].trim_start();
+        } else {
+            let end = rest.find(char::is_whitespace)
+                .unwrap_or(rest.len());
+            tokens.push((&rest[..end], false));
+            rest = rest[end..].trim_start();
+        }
+    }
+    Ok(tokens)
+}
+
+/// converts 1 based Pajek index into 0 based index
+fn parse_index(token: &str, line: usize) -> Result<usize, ReadGraphError> {
+    match token.parse::<usize>() {
+        Ok(index) if index > 0 => Ok(index - 1),
+        _ => Err(ReadGraphError::malformed(line, format!("invalid vertex number `{}`", token)))
+    }
+}
+
+/// # Read Pajek network
+/// * reads `.net` files with the sections `*Vertices`, `*Edges` and `*Edgeslist`
+/// * `node` creates the data `T` of each vertex from its index and its attributes:
+///   the label is stored as `label`, the coordinates as `x`, `y` and `z`.
+///   Vertices that are not listed have no attributes
+/// * edge weights and further vertex parameters are ignored
+/// * lines starting with `%` are comments
+/// * vertices are numbered starting at 1 in Pajek files, but starting at 0 in the
+///   graph and in the errors
+/// # Errors
+/// * [`Malformed`](ReadGraphError::Malformed) for invalid lines, `*Arcs`, `*Arcslist`, `*Matrix`
+///   or if `*Vertices` is missing. The latter is reported at the last line of the file
+/// * [`IndexOutOfRange`](ReadGraphError::IndexOutOfRange), [`SelfLoop`](ReadGraphError::SelfLoop)
+///   and [`DuplicateEdge`](ReadGraphError::DuplicateEdge)
+pub fn read_pajek<T, R, F>(reader: R, mut node: F) -> Result<Graph<T>, ReadGraphError>
+where T: Node,
+      R: BufRead,
+      F: FnMut(usize, &HashMap<String, AttributeValue>) -> T
+{
+    let mut section = Section::Preamble;
+    let mut vertex_count = None;
+    let mut attributes: Vec<HashMap<String, AttributeValue>> = Vec::new();
+    let mut edges = Vec::new();
+    // lines start at 1, also for empty files
+    let mut last_line = 1;
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        last_line = line_number;
+        let content = line.trim();
+        if content.is_empty() || content.starts_with('%') {
+            continue;
+        }
+
+        if content.starts_with('*') {
+            let mut words = content.split_whitespace();
+            let keyword = words.next()
+                .unwrap()
+                .to_ascii_lowercase();
+            section = match keyword.as_str() {
+                "*network" => Section::Preamble,
+                "*vertices" => {
+                    if vertex_count.is_some() {
+                        return Err(ReadGraphError::malformed(line_number, "multiple `*Vertices`"));
+                    }
+                    let n = words.next()
+                        .and_then(|n| n.parse::<usize>().ok())
+                        .ok_or_else(|| ReadGraphError::malformed(line_number, "expected number of vertices"))?;
+                    vertex_count = Some(n);
+                    attributes = vec![HashMap::new(); n];
+                    Section::Vertices
+                },
+                "*edges" => Section::Edges,
+                "*edgeslist" => Section::EdgesList,
+                "*arcs" | "*arcslist" => return Err(
+                    ReadGraphError::malformed(line_number, "directed arcs are not supported")
+                ),
+                _ => return Err(
+                    ReadGraphError::malformed(line_number, format!("unsupported section `{}`", keyword))
+                ),
+            };
+            if section != Section::Preamble && vertex_count.is_none() {
+                return Err(ReadGraphError::malformed(line_number, "`*Vertices` has to be the first section"));
+            }
+            continue;
+        }
+
+        let tokens = tokenize(content, line_number)?;
+        match section {
+            Section::Preamble => (),
+            Section::Vertices => {
+                let index = parse_index(tokens[0].0, line_number)?;
+                let vertex_count = vertex_count.unwrap();
+                if index >= vertex_count {
+                    return Err(ReadGraphError::IndexOutOfRange{line: line_number, index, vertex_count});
+                }
+                let map = &mut attributes[index];
+                let mut rest = tokens[1..].iter().peekable();
+                if let Some(&&(label, quoted)) = rest.peek() {
+                    if quoted || label.parse::<f64>().is_err() {
+                        map.insert("label".to_owned(), AttributeValue::String(label.to_owned()));
+                        rest.next();
+                    }
+                }
+                for name in ["x", "y", "z"] {
+                    match rest.next().and_then(|(token, _)| token.parse::<f64>().ok()) {
+                        Some(value) => {
+                            map.insert(name.to_owned(), AttributeValue::Double(value));
+                        },
+                        None => break
+                    }
+                }
+            },
+            Section::Edges => {
+                if tokens.len() < 2 {
+                    return Err(ReadGraphError::malformed(line_number, "expected two vertices"));
+                }
+                let i = parse_index(tokens[0].0, line_number)?;
+                let j = parse_index(tokens[1].0, line_number)?;
+                edges.push((line_number, i, j));
+            },
+            Section::EdgesList => {
+                let i = parse_index(tokens[0].0, line_number)?;
+                for (token, _) in &tokens[1..] {
+                    edges.push((line_number, i, parse_index(token, line_number)?));
+                }
+            },
+        }
+    }
+
+    let vertex_count = vertex_count
+        .ok_or_else(|| ReadGraphError::malformed(last_line, "missing `*Vertices`"))?;
+    let mut graph = build_graph::<T>(vertex_count, &edges)?;
+    for (index, attributes) in attributes.iter().enumerate() {
+        *graph.at_mut(index) = node(index, attributes);
+    }
+    Ok(graph)
+}
+
+/// # Write Pajek network
+/// * `labels` creates the label of each vertex from its index and its data `T`,
+///   similar to the label closures of the [`Dot`](crate::traits::Dot) trait.
+///   Quotation marks in labels are replaced by `'`, as Pajek does not support escaping
+/// * coordinates, e.g., of a [`SpacialGraph`](crate::spacial::SpacialGraph), are written
+///   automatically, see [`ExportAttributes::coordinates`]
+/// * vertices are numbered starting at 1, as required by Pajek
+/// # Example
+/// ```
+/// use net_ensembles::{Graph, EmptyNode};
+/// use net_ensembles::io::{write_pajek, read_pajek};
+///
+/// let graph = Graph::<EmptyNode>::complete_graph(3);
+/// let mut buf = Vec::new();
+/// write_pajek(&graph, &mut buf, |index, _| format!("v{}", index)).unwrap();
+/// let s = String::from_utf8(buf).unwrap();
+/// assert!(s.starts_with("*Vertices 3\n1 \"v0\"\n"));
+///
+/// let mut labels = Vec::new();
+/// let read: Graph<EmptyNode> = read_pajek(
+///     s.as_bytes(),
+///     |_, attributes| {
+///         labels.push(attributes["label"].as_str().unwrap().to_owned());
+///         EmptyNode{}
+///     }
+/// ).unwrap();
+/// assert_eq!(read.edge_count(), 3);
+/// assert_eq!(labels, vec!["v0", "v1", "v2"]);
+/// ```
+pub fn write_pajek<T, A, W, F, S>(graph: &GenericGraph<T, A>, mut writer: W, mut labels: F) -> Result<(), std::io::Error>
+where A: AdjContainer<T> + ExportAttributes,
+      W: Write,
+      F: FnMut(usize, &T) -> S,
+      S: AsRef<str>
+{
+    writeln!(writer, "*Vertices {}", graph.vertex_count())?;
+    for (index, container) in graph.container_iter().enumerate() {
+        let label = labels(index, container.contained());
+        write!(writer, "{} \"{}\"", index + 1, label.as_ref().replace('"', "'"))?;
+        if let Some((x, y)) = container.coordinates() {
+            write!(writer, " {} {}", x, y)?;
+        }
+        writeln!(writer)?;
+    }
+    writeln!(writer, "*Edges")?;
+    for (i, container) in graph.container_iter().enumerate() {
+        for &j in container.neighbors() {
+            if i < j {
+                writeln!(writer, "{} {}", i + 1, j + 1)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyNode, spacial::SpacialEnsemble, rand::SeedableRng};
+    use rand_pcg::Pcg64;
+
+    fn read(data: &str) -> Result<Graph<EmptyNode>, ReadGraphError>
+    {
+        read_pajek(data.as_bytes(), |_, _| EmptyNode{})
+    }
+
+    #[test]
+    fn spacial_round_trip()
+    {
+        let rng = Pcg64::seed_from_u64(8346);
+        let e = SpacialEnsemble::<EmptyNode, _>::new(40, rng, 0.95, 3.0);
+        let mut buf = Vec::new();
+        write_pajek(e.as_ref(), &mut buf, |index, _| format!("vertex \"{}\"", index)).unwrap();
+
+        let mut read_attributes = Vec::new();
+        let graph: Graph<EmptyNode> = read_pajek(
+            buf.as_slice(),
+            |_, attributes| {
+                read_attributes.push(attributes.clone());
+                EmptyNode{}
+            }
+        ).unwrap();
+        assert_eq!(graph.edge_count(), e.as_ref().edge_count());
+        for (index, container) in e.as_ref().container_iter().enumerate() {
+            let attributes = &read_attributes[index];
+            assert_eq!(attributes["x"].as_f64(), Some(container.get_x()));
+            assert_eq!(attributes["y"].as_f64(), Some(container.get_y()));
+            assert_eq!(attributes["label"].as_str(), Some(format!("vertex '{}'", index).as_str()));
+            for &j in container.neighbors() {
+                assert!(graph.container(index).is_adjacent(j));
+            }
+        }
+    }
+
+    #[test]
+    fn foreign_pajek()
+    {
+        let data = "% comment\n*Network test\n*Vertices 5\n1 \"a b\" 0.1 0.2 0.3 ic Red\n2 c\n3 0.5 0.5\n*Edges\n1 2 1.5\n*Edgeslist\n3 4 5\n";
+        let mut read_attributes = Vec::new();
+        let graph: Graph<EmptyNode> = read_pajek(
+            data.as_bytes(),
+            |_, attributes| {
+                read_attributes.push(attributes.clone());
+                EmptyNode{}
+            }
+        ).unwrap();
+        assert_eq!(graph.edge_count(), 3);
+        assert!(graph.container(2).is_adjacent(4));
+        assert_eq!(read_attributes[0]["label"].as_str(), Some("a b"));
+        assert_eq!(read_attributes[0]["z"].as_f64(), Some(0.3));
+        assert_eq!(read_attributes[1]["label"].as_str(), Some("c"));
+        assert!(!read_attributes[2].contains_key("label"));
+        assert_eq!(read_attributes[2]["y"].as_f64(), Some(0.5));
+        assert!(read_attributes[4].is_empty());
+
+        assert!(matches!(read("*Edges\n1 2\n"), Err(ReadGraphError::Malformed{line: 1, ..})));
+        assert!(matches!(read("*Network test\n% no vertices\n"), Err(ReadGraphError::Malformed{line: 2, ..})));
+        assert!(matches!(read(""), Err(ReadGraphError::Malformed{line: 1, ..})));
+        assert!(matches!(read("*Vertices 2\n*Arcs\n1 2\n"), Err(ReadGraphError::Malformed{line: 2, ..})));
+        assert!(matches!(read("*Vertices 2\n*Edges\n0 1\n"), Err(ReadGraphError::Malformed{line: 3, ..})));
+        assert!(matches!(
+            read("*Vertices 2\n*Edges\n1 3\n"),
+            Err(ReadGraphError::IndexOutOfRange{line: 3, index: 2, vertex_count: 2})
+        ));
+        assert!(matches!(read("*Vertices 2\n*Edges\n2 2\n"), Err(ReadGraphError::SelfLoop{line: 3, index: 1})));
+        assert!(read("").is_err());
+    }
+}
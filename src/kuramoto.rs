@@ -0,0 +1,317 @@
+//! # Kuramoto oscillators
+//! Phase oscillators coupled via the edges of a graph
+//!
+//! `d theta_i / dt = omega_i + K sum_j A_ij sin(theta_j - theta_i)`
+//!
+//! * the phases `theta_i` are stored in the additional data `T` of the vertices,
+//!   see [`OscillatorNode`]
+//! * the natural frequencies `omega_i` and the coupling constant `K` are
+//!   stored in [`Kuramoto`]
+//! * note that the coupling is not normalized, use `K/N` for the
+//!   classical mean-field normalization
+//! * integrated with the fourth order Runge-Kutta method and fixed step size
+//!
+//! # Example
+//! ```
+//! use net_ensembles::{ErEnsembleC, rand::SeedableRng};
+//! use net_ensembles::kuramoto::{Kuramoto, Oscillator};
+//! use rand_pcg::Pcg64;
+//!
+//! let rng = Pcg64::seed_from_u64(9823);
+//! let mut ensemble = ErEnsembleC::<Oscillator, _>::new(50, 6.0, rng);
+//!
+//! let frequencies = (0..50).map(|i| 0.01 * i as f64).collect();
+//! let mut kuramoto = Kuramoto::new(frequencies, 1.0, 0.01);
+//!
+//! // r(t) for t = 0, 0.01, ..., 10
+//! let r = kuramoto.integrate_ensemble(&mut ensemble, 1000);
+//! assert_eq!(r.len(), 1001);
+//! assert!((kuramoto.time() - 10.0).abs() < 1e-9);
+//! ```
+//! # Citation
+//! > F. A. Rodrigues, T. K. DM. Peron, P. Ji and J. Kurths,
+//! > "The Kuramoto model in complex networks"
+//! > *Physics Reports*&nbsp;**610**, 1-98&nbsp;(2016),
+//! > DOI: [10.1016/j.physrep.2015.10.008](https://doi.org/10.1016/j.physrep.2015.10.008)
+use {
+    crate::{
+        traits::*,
+        GenericGraph
+    },
+    std::f64::consts::TAU
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Serialize, Deserialize};
+
+/// # Node that contains the phase of an oscillator
+pub trait OscillatorNode {
+    /// * returns the phase
+    fn phase(&self) -> f64;
+
+    /// * set the phase
+    fn set_phase(&mut self, phase: f64);
+}
+
+/// # Node containing only a phase
+/// * initial phase is `0.0`
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Oscillator(pub f64);
+
+impl Node for Oscillator {
+    fn new_from_index(_: usize) -> Self {
+        Oscillator(0.0)
+    }
+}
+
+impl OscillatorNode for Oscillator {
+    fn phase(&self) -> f64 {
+        self.0
+    }
+
+    fn set_phase(&mut self, phase: f64) {
+        self.0 = phase;
+    }
+}
+
+/// # Kuramoto order parameter
+/// * returns `(r, psi)`, where `r e^{i psi} = 1/N sum_j e^{i theta_j}`
+/// * `r = 1` means perfect synchronization
+/// * returns `(NaN, NaN)` for empty graphs
+pub fn order_parameter<T, A>(graph: &GenericGraph<T, A>) -> (f64, f64)
+where T: Node + OscillatorNode,
+      A: AdjContainer<T>
+{
+    let (sin, cos) = graph.contained_iter()
+        .fold(
+            (0.0, 0.0),
+            |(sin, cos), node| {
+                let (s, c) = node.phase().sin_cos();
+                (sin + s, cos + c)
+            }
+        );
+    let n = graph.vertex_count() as f64;
+    let (sin, cos) = (sin / n, cos / n);
+    (sin.hypot(cos), sin.atan2(cos))
+}
+
+/// # Kuramoto model
+/// * natural frequencies, coupling constant and step size of the integrator
+/// * keeps track of the time
+#[derive(Debug, Clone)]
+pub struct Kuramoto {
+    natural_frequencies: Vec<f64>,
+    coupling: f64,
+    step_size: f64,
+    time: f64,
+    phases: Vec<f64>,
+    tmp: Vec<f64>,
+    k: [Vec<f64>; 4],
+}
+
+impl Kuramoto {
+    /// # Create Kuramoto integrator
+    /// * `natural_frequencies[i]` is the natural frequency of vertex `i`.
+    ///   Its length has to equal the number of vertices of the graph
+    /// * `coupling`: coupling constant `K`
+    /// * `step_size`: fixed step size of the Runge-Kutta integration
+    pub fn new(natural_frequencies: Vec<f64>, coupling: f64, step_size: f64) -> Self
+    {
+        let n = natural_frequencies.len();
+        Self{
+            natural_frequencies,
+            coupling,
+            step_size,
+            time: 0.0,
+            phases: vec![0.0; n],
+            tmp: vec![0.0; n],
+            k: [vec![0.0; n], vec![0.0; n], vec![0.0; n], vec![0.0; n]],
+        }
+    }
+
+    /// # Natural frequencies
+    pub fn natural_frequencies(&self) -> &[f64]
+    {
+        &self.natural_frequencies
+    }
+
+    /// # Coupling constant `K`
+    pub fn coupling(&self) -> f64
+    {
+        self.coupling
+    }
+
+    /// # Change coupling constant `K`
+    pub fn set_coupling(&mut self, coupling: f64)
+    {
+        self.coupling = coupling;
+    }
+
+    /// # Step size of the integrator
+    pub fn step_size(&self) -> f64
+    {
+        self.step_size
+    }
+
+    /// # Current time
+    /// * `step_size` times the number of integration steps so far
+    pub fn time(&self) -> f64
+    {
+        self.time
+    }
+
+    /// # Set current time
+    pub fn set_time(&mut self, time: f64)
+    {
+        self.time = time;
+    }
+
+    /// `derivative = omega + K sum_j A_ij sin(phases_j - phases_i)`
+    fn derivative<T, A>(
+        graph: &GenericGraph<T, A>,
+        natural_frequencies: &[f64],
+        coupling: f64,
+        phases: &[f64],
+        derivative: &mut [f64]
+    )
+    where T: Node,
+          A: AdjContainer<T>
+    {
+        for (index, container) in graph.container_iter().enumerate() {
+            let phase = phases[index];
+            let sum: f64 = container.neighbors()
+                .map(|&neighbor| (phases[neighbor] - phase).sin())
+                .sum();
+            derivative[index] = natural_frequencies[index] + coupling * sum;
+        }
+    }
+
+    /// calculates the phases after one Runge-Kutta step and stores them in `self.phases`
+    fn rk4<T, A>(&mut self, graph: &GenericGraph<T, A>)
+    where T: Node + OscillatorNode,
+          A: AdjContainer<T>
+    {
+        assert_eq!(
+            graph.vertex_count(),
+            self.natural_frequencies.len(),
+            "number of natural frequencies has to match vertex count"
+        );
+        for (phase, node) in self.phases.iter_mut().zip(graph.contained_iter()) {
+            *phase = node.phase();
+        }
+        let h = self.step_size;
+        let factors = [0.5 * h, 0.5 * h, h];
+        Self::derivative(graph, &self.natural_frequencies, self.coupling, &self.phases, &mut self.k[0]);
+        for (stage, factor) in factors.into_iter().enumerate() {
+            for ((tmp, phase), k) in self.tmp.iter_mut().zip(self.phases.iter()).zip(self.k[stage].iter()) {
+                *tmp = phase + factor * k;
+            }
+            let (_, rest) = self.k.split_at_mut(stage + 1);
+            Self::derivative(graph, &self.natural_frequencies, self.coupling, &self.tmp, &mut rest[0]);
+        }
+        for (index, phase) in self.phases.iter_mut().enumerate() {
+            let increment = self.k[0][index]
+                + 2.0 * self.k[1][index]
+                + 2.0 * self.k[2][index]
+                + self.k[3][index];
+            *phase = (*phase + h / 6.0 * increment).rem_euclid(TAU);
+        }
+        self.time += h;
+    }
+
+    /// # Perform one integration step
+    /// * updates the phases stored in the nodes
+    /// * phases are mapped to `[0, 2 pi)`
+    /// * **panics** if the number of natural frequencies does not match the number of vertices
+    pub fn step<T, A>(&mut self, graph: &mut GenericGraph<T, A>)
+    where T: Node + OscillatorNode,
+          A: AdjContainer<T>
+    {
+        self.rk4(graph);
+        for (node, &phase) in graph.contained_iter_mut().zip(self.phases.iter()) {
+            node.set_phase(phase);
+        }
+    }
+
+    /// # Perform one integration step on an ensemble
+    /// * same as [`step`](Self::step), but the phases are written via
+    ///   [`contained_iter_mut`](crate::traits::GraphIteratorsMut::contained_iter_mut)
+    pub fn step_ensemble<E, T, A>(&mut self, ensemble: &mut E)
+    where T: Node + OscillatorNode,
+          A: AdjContainer<T>,
+          E: WithGraph<T, GenericGraph<T, A>> + GraphIteratorsMut<T, GenericGraph<T, A>, A>
+    {
+        self.rk4(ensemble.graph());
+        for (node, &phase) in ensemble.contained_iter_mut().zip(self.phases.iter()) {
+            node.set_phase(phase);
+        }
+    }
+
+    /// # Integrate for `steps` steps
+    /// * returns the order parameter `r(t)` before the first and after each step,
+    ///   i.e., the returned vector has length `steps + 1`
+    /// * see [`step`](Self::step) and [`order_parameter`]
+    pub fn integrate<T, A>(&mut self, graph: &mut GenericGraph<T, A>, steps: usize) -> Vec<f64>
+    where T: Node + OscillatorNode,
+          A: AdjContainer<T>
+    {
+        let mut r = Vec::with_capacity(steps + 1);
+        r.push(order_parameter(graph).0);
+        for _ in 0..steps {
+            self.step(graph);
+            r.push(order_parameter(graph).0);
+        }
+        r
+    }
+
+    /// # Integrate ensemble for `steps` steps
+    /// * same as [`integrate`](Self::integrate), but for ensembles
+    pub fn integrate_ensemble<E, T, A>(&mut self, ensemble: &mut E, steps: usize) -> Vec<f64>
+    where T: Node + OscillatorNode,
+          A: AdjContainer<T>,
+          E: WithGraph<T, GenericGraph<T, A>> + GraphIteratorsMut<T, GenericGraph<T, A>, A>
+    {
+        let mut r = Vec::with_capacity(steps + 1);
+        r.push(order_parameter(ensemble.graph()).0);
+        for _ in 0..steps {
+            self.step_ensemble(ensemble);
+            r.push(order_parameter(ensemble.graph()).0);
+        }
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Graph;
+
+    #[test]
+    fn uncoupled()
+    {
+        let mut graph = Graph::<Oscillator>::complete_graph(3);
+        let mut kuramoto = Kuramoto::new(vec![0.0, 0.5, 1.0], 0.0, 0.1);
+        kuramoto.integrate(&mut graph, 10);
+        for (i, node) in graph.contained_iter().enumerate() {
+            assert!((node.phase() - 0.5 * i as f64).abs() < 1e-12);
+        }
+        assert!((kuramoto.time() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn phase_locking()
+    {
+        // two oscillators lock at phase difference phi with sin(phi) = delta omega / (2K)
+        let mut graph = Graph::<Oscillator>::complete_graph(2);
+        graph.at_mut(1).set_phase(2.0);
+        let mut kuramoto = Kuramoto::new(vec![0.5, -0.5], 1.0, 0.01);
+        let r = kuramoto.integrate(&mut graph, 5000);
+        let phi = (0.5_f64).asin();
+        assert!((r[5000] - (phi / 2.0).cos()).abs() < 1e-8);
+        assert!((r[0] - 1.0_f64.cos()).abs() < 1e-12);
+
+        let (_, psi) = order_parameter(&graph);
+        assert!(psi.is_finite());
+    }
+}
@@ -183,6 +183,7 @@
 //! * example for a Marcov chain of connected graphs
 //! * you can also create a Marcov chain with unconnected graphs if you want
 //! * see trait [```MarcovChain```](./sampling/traits/trait.MarkovChain.html)
+//! * to avoid checking the connectivity of the whole graph after every step, see module [`connectivity`](./connectivity/index.html)
 //! ```
 //! use net_ensembles::{EmptyNode, ErEnsembleM, traits::*};
 //! use rand_pcg::Pcg64;
@@ -281,6 +282,7 @@
 pub mod generic_graph;
 pub mod example_nodes;
 pub mod graph;
+pub mod indexed_graph;
 pub mod er_c;
 pub mod sw;
 pub mod er_m;
@@ -298,6 +300,23 @@ pub mod spacial;
 mod configuration_model;
 
 pub mod dual_graph;
+pub mod robustness;
+pub mod cascade;
+pub mod epidemics;
+pub mod spin;
+pub mod kuramoto;
+pub mod opinion;
+pub mod io;
+pub mod csr_graph;
+pub mod connectivity;
+pub mod incremental;
+#[cfg(feature = "serde_support")]
+pub mod snapshot;
+#[cfg(feature = "serde_support")]
+pub mod trajectory;
+mod union_find;
+#[cfg(test)]
+mod test_chains;
 
 pub use sw::SwEnsemble;
 pub use sw_graph::SwGraph;
@@ -305,7 +324,9 @@ pub use er_m::ErEnsembleM;
 pub use er_c::ErEnsembleC;
 pub use barabasi_albert::*;
 pub use graph::Graph;
-pub use generic_graph::GenericGraph;
+pub use indexed_graph::{IndexedGraph, IndexedNodeContainer};
+pub use csr_graph::CsrGraph;
+pub use generic_graph::{GenericGraph, TraversalWorkspace, EdgeIndex};
 pub use example_nodes::{EmptyNode, CountingNode};
 pub use traits::*;
 pub use traits::GraphErrors;
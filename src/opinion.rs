@@ -0,0 +1,527 @@
+//! # Opinion dynamics
+//! Opinions are stored in the additional data `T` of the vertices, see [`OpinionNode`].
+//!
+//! * [`Voter`]: a random vertex adopts the opinion of a random neighbor
+//! * [`MajorityRule`]: a random vertex adopts the majority opinion of itself and its neighbors
+//! * [`Deffuant`]: bounded confidence model with continuous opinions
+//!
+//! All models implement [`OpinionDynamics`], which offers a per-step interface and
+//! the measurement of the consensus time, both for graphs and for ensembles.
+//! The ensemble versions use the random number generator of the ensemble.
+//!
+//! # Example
+//! ```
+//! use net_ensembles::{ErEnsembleC, rand::SeedableRng};
+//! use net_ensembles::opinion::{Voter, OpinionDynamics, DiscreteOpinion};
+//! use rand_pcg::Pcg64;
+//!
+//! let rng = Pcg64::seed_from_u64(2390);
+//! let mut ensemble = ErEnsembleC::<DiscreteOpinion, _>::new(50, 4.0, rng);
+//!
+//! // initially, every vertex has its own opinion
+//! let steps = Voter.consensus_time_ensemble(&mut ensemble, 1_000_000);
+//! // measured in Monte Carlo steps, divide by 50 to get sweeps
+//! println!("{:?}", steps);
+//! ```
+//! # Citations
+//! > C. Castellano, S. Fortunato and V. Loreto,
+//! > "Statistical physics of social dynamics"
+//! > *Rev. Mod. Phys.*&nbsp;**81**, 591&nbsp;(2009),
+//! > DOI: [10.1103/RevModPhys.81.591](https://doi.org/10.1103/RevModPhys.81.591)
+//!
+//! > G. Deffuant, D. Neau, F. Amblard and G. Weisbuch,
+//! > "Mixing beliefs among interacting agents"
+//! > *Advs. Complex Syst.*&nbsp;**3**, 87-98&nbsp;(2000),
+//! > DOI: [10.1142/S0219525900000078](https://doi.org/10.1142/S0219525900000078)
+use {
+    crate::{
+        traits::*,
+        GenericGraph
+    },
+    rand::{Rng, SeedableRng}
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Serialize, Deserialize};
+
+/// # Node that contains an opinion of type `O`
+pub trait OpinionNode<O> {
+    /// * returns the opinion
+    fn opinion(&self) -> O;
+
+    /// * set the opinion
+    fn set_opinion(&mut self, opinion: O);
+}
+
+/// # Node containing only a discrete opinion
+/// * initial opinion equals the index of the vertex,
+///   i.e., every vertex starts with its own opinion
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct DiscreteOpinion(pub usize);
+
+impl Node for DiscreteOpinion {
+    fn new_from_index(index: usize) -> Self {
+        DiscreteOpinion(index)
+    }
+}
+
+impl OpinionNode<usize> for DiscreteOpinion {
+    fn opinion(&self) -> usize {
+        self.0
+    }
+
+    fn set_opinion(&mut self, opinion: usize) {
+        self.0 = opinion;
+    }
+}
+
+/// # Node containing only a continuous opinion
+/// * initial opinion is `0.0`
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct ContinuousOpinion(pub f64);
+
+impl Node for ContinuousOpinion {
+    fn new_from_index(_: usize) -> Self {
+        ContinuousOpinion(0.0)
+    }
+}
+
+impl OpinionNode<f64> for ContinuousOpinion {
+    fn opinion(&self) -> f64 {
+        self.0
+    }
+
+    fn set_opinion(&mut self, opinion: f64) {
+        self.0 = opinion;
+    }
+}
+
+/// draws a random vertex and a random neighbor of it.
+/// `None` if the drawn vertex is isolated or the graph is empty
+fn random_neighbor_pair<T, A, R>(graph: &GenericGraph<T, A>, rng: &mut R) -> Option<(usize, usize)>
+where T: Node,
+      A: AdjContainer<T>,
+      R: Rng
+{
+    if graph.vertex_count() == 0 {
+        return None;
+    }
+    let index = rng.gen_range(0..graph.vertex_count());
+    let container = graph.container(index);
+    if container.degree() == 0 {
+        return None;
+    }
+    let which = rng.gen_range(0..container.degree());
+    container.neighbors()
+        .nth(which)
+        .map(|&neighbor| (index, neighbor))
+}
+
+/// # Common interface of opinion dynamics
+pub trait OpinionDynamics {
+    /// type of the opinions
+    type Opinion: Copy + PartialEq;
+
+    /// # Propose one elementary update
+    /// * clears `changes` and pushes `(index, new_opinion)` for every vertex
+    ///   whose opinion changes
+    /// * does not change the graph
+    fn propose<T, A, R>(
+        &self,
+        graph: &GenericGraph<T, A>,
+        rng: &mut R,
+        changes: &mut Vec<(usize, Self::Opinion)>
+    )
+    where T: Node + OpinionNode<Self::Opinion>,
+          A: AdjContainer<T>,
+          R: Rng;
+
+    /// # Can an edge between these opinions still lead to changes?
+    /// * the dynamics has converged, if no such edge exists
+    fn is_active(&self, a: Self::Opinion, b: Self::Opinion) -> bool;
+
+    /// # Number of active edges
+    /// * see [`is_active`](Self::is_active)
+    fn active_edges<T, A>(&self, graph: &GenericGraph<T, A>) -> usize
+    where T: Node + OpinionNode<Self::Opinion>,
+          A: AdjContainer<T>
+    {
+        graph.container_iter()
+            .enumerate()
+            .map(
+                |(index, container)| {
+                    let opinion = container.contained().opinion();
+                    container.neighbors()
+                        .filter(
+                            |&&neighbor| neighbor > index
+                                && self.is_active(opinion, graph.at(neighbor).opinion())
+                        ).count()
+                }
+            ).sum()
+    }
+
+    /// # Perform one elementary update
+    /// * returns `true` if any opinion changed
+    fn step<T, A, R>(&self, graph: &mut GenericGraph<T, A>, rng: &mut R) -> bool
+    where T: Node + OpinionNode<Self::Opinion>,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        let mut changes = Vec::new();
+        self.propose(graph, rng, &mut changes);
+        for &(index, opinion) in changes.iter() {
+            graph.at_mut(index).set_opinion(opinion);
+        }
+        !changes.is_empty()
+    }
+
+    /// # Perform one elementary update on an ensemble
+    /// * same as [`step`](Self::step), but uses the random number generator of the ensemble
+    fn step_ensemble<E, T, A, R>(&self, ensemble: &mut E) -> bool
+    where T: Node + OpinionNode<Self::Opinion>,
+          A: AdjContainer<T>,
+          R: Rng + SeedableRng,
+          E: WithGraph<T, GenericGraph<T, A>> + HasRng<R>
+    {
+        with_ensemble_rng(
+            ensemble,
+            |ensemble, rng| {
+                let mut changes = Vec::new();
+                self.propose(ensemble.graph(), rng, &mut changes);
+                for &(index, opinion) in changes.iter() {
+                    ensemble.at_mut(index).set_opinion(opinion);
+                }
+                !changes.is_empty()
+            }
+        )
+    }
+
+    /// # Measure the consensus time
+    /// * performs elementary updates until no [active edges](Self::is_active) are left
+    /// * returns the number of elementary updates, divide by the number of vertices
+    ///   to get the time in Monte Carlo sweeps
+    /// * returns `None` if the dynamics did not converge within `max_steps` updates
+    /// * for disconnected graphs, every component has to reach consensus
+    fn consensus_time<T, A, R>(&self, graph: &mut GenericGraph<T, A>, max_steps: usize, rng: &mut R) -> Option<usize>
+    where T: Node + OpinionNode<Self::Opinion>,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        consensus_time_helper(
+            self,
+            graph,
+            |graph| graph,
+            GenericGraph::at_mut,
+            max_steps,
+            rng
+        )
+    }
+
+    /// # Measure the consensus time on an ensemble
+    /// * same as [`consensus_time`](Self::consensus_time), but uses the random number generator of the ensemble
+    fn consensus_time_ensemble<E, T, A, R>(&self, ensemble: &mut E, max_steps: usize) -> Option<usize>
+    where T: Node + OpinionNode<Self::Opinion>,
+          A: AdjContainer<T>,
+          R: Rng + SeedableRng,
+          E: WithGraph<T, GenericGraph<T, A>> + HasRng<R>
+    {
+        with_ensemble_rng(
+            ensemble,
+            |ensemble, rng| consensus_time_helper(
+                self,
+                ensemble,
+                E::graph,
+                E::at_mut,
+                max_steps,
+                rng
+            )
+        )
+    }
+}
+
+/// performs updates and keeps track of the number of active edges
+fn consensus_time_helper<D, H, T, A, R, G, M>(
+    dynamics: &D,
+    host: &mut H,
+    topology: G,
+    node_mut: M,
+    max_steps: usize,
+    rng: &mut R
+) -> Option<usize>
+where D: OpinionDynamics + ?Sized,
+      T: Node + OpinionNode<D::Opinion>,
+      A: AdjContainer<T>,
+      R: Rng,
+      H: ?Sized,
+      G: Fn(&H) -> &GenericGraph<T, A>,
+      M: Fn(&mut H, usize) -> &mut T
+{
+    let mut active = dynamics.active_edges(topology(host));
+    let mut changes = Vec::new();
+    for step in 0..max_steps {
+        if active == 0 {
+            return Some(step);
+        }
+        dynamics.propose(topology(host), rng, &mut changes);
+        for &(index, opinion) in changes.iter() {
+            let graph = topology(host);
+            let old = graph.at(index).opinion();
+            for &neighbor in graph.container(index).neighbors() {
+                let other = graph.at(neighbor).opinion();
+                if dynamics.is_active(old, other) {
+                    active -= 1;
+                }
+                if dynamics.is_active(opinion, other) {
+                    active += 1;
+                }
+            }
+            node_mut(host, index).set_opinion(opinion);
+        }
+    }
+    if active == 0 {
+        Some(max_steps)
+    } else {
+        None
+    }
+}
+
+/// # Voter model
+/// * a random vertex adopts the opinion of a random neighbor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Voter;
+
+impl OpinionDynamics for Voter {
+    type Opinion = usize;
+
+    fn propose<T, A, R>(
+        &self,
+        graph: &GenericGraph<T, A>,
+        rng: &mut R,
+        changes: &mut Vec<(usize, usize)>
+    )
+    where T: Node + OpinionNode<usize>,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        changes.clear();
+        if let Some((index, neighbor)) = random_neighbor_pair(graph, rng) {
+            let opinion = graph.at(neighbor).opinion();
+            if opinion != graph.at(index).opinion() {
+                changes.push((index, opinion));
+            }
+        }
+    }
+
+    fn is_active(&self, a: usize, b: usize) -> bool
+    {
+        a != b
+    }
+}
+
+/// # Majority rule
+/// * a random vertex adopts the most common opinion among itself and its neighbors
+/// * ties are broken uniformly at random
+/// * note that the dynamics can get stuck without reaching consensus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct MajorityRule;
+
+impl OpinionDynamics for MajorityRule {
+    type Opinion = usize;
+
+    fn propose<T, A, R>(
+        &self,
+        graph: &GenericGraph<T, A>,
+        rng: &mut R,
+        changes: &mut Vec<(usize, usize)>
+    )
+    where T: Node + OpinionNode<usize>,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        changes.clear();
+        if graph.vertex_count() == 0 {
+            return;
+        }
+        let index = rng.gen_range(0..graph.vertex_count());
+        let own = graph.at(index).opinion();
+
+        // (opinion, count)
+        let mut counts: Vec<(usize, usize)> = vec![(own, 1)];
+        for &neighbor in graph.container(index).neighbors() {
+            let opinion = graph.at(neighbor).opinion();
+            match counts.iter_mut().find(|(o, _)| *o == opinion) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((opinion, 1)),
+            }
+        }
+        let max = counts.iter()
+            .map(|&(_, count)| count)
+            .max()
+            .unwrap();
+        counts.retain(|&(_, count)| count == max);
+        let (opinion, _) = counts[rng.gen_range(0..counts.len())];
+        if opinion != own {
+            changes.push((index, opinion));
+        }
+    }
+
+    fn is_active(&self, a: usize, b: usize) -> bool
+    {
+        a != b
+    }
+}
+
+/// # Deffuant bounded confidence model
+/// * a random vertex `i` and a random neighbor `j` interact, if their opinions differ
+///   by less than the `threshold`
+/// * then `x_i += mu (x_j - x_i)` and `x_j += mu (x_i - x_j)`
+/// * the model converges into one or more opinion clusters. An edge stays
+///   [active](OpinionDynamics::is_active) as long as its opinions differ by
+///   at least `tolerance` and less than `threshold`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Deffuant {
+    threshold: f64,
+    convergence: f64,
+    tolerance: f64,
+}
+
+impl Deffuant {
+    /// # Create Deffuant model
+    /// * `threshold`: confidence bound `d`
+    /// * `convergence`: `mu`, should be in `(0, 0.5]`
+    /// * `tolerance`: opinions that differ by less are considered equal
+    ///   when measuring the consensus time
+    pub fn new(threshold: f64, convergence: f64, tolerance: f64) -> Self
+    {
+        Self{
+            threshold,
+            convergence,
+            tolerance,
+        }
+    }
+
+    /// # Confidence bound `d`
+    pub fn threshold(&self) -> f64
+    {
+        self.threshold
+    }
+
+    /// # Convergence parameter `mu`
+    pub fn convergence(&self) -> f64
+    {
+        self.convergence
+    }
+
+    /// # Tolerance used for the consensus time
+    pub fn tolerance(&self) -> f64
+    {
+        self.tolerance
+    }
+}
+
+impl OpinionDynamics for Deffuant {
+    type Opinion = f64;
+
+    fn propose<T, A, R>(
+        &self,
+        graph: &GenericGraph<T, A>,
+        rng: &mut R,
+        changes: &mut Vec<(usize, f64)>
+    )
+    where T: Node + OpinionNode<f64>,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        changes.clear();
+        if let Some((index, neighbor)) = random_neighbor_pair(graph, rng) {
+            let a = graph.at(index).opinion();
+            let b = graph.at(neighbor).opinion();
+            let diff = b - a;
+            if diff != 0.0 && diff.abs() < self.threshold {
+                changes.push((index, a + self.convergence * diff));
+                changes.push((neighbor, b - self.convergence * diff));
+            }
+        }
+    }
+
+    fn is_active(&self, a: f64, b: f64) -> bool
+    {
+        let diff = (a - b).abs();
+        diff >= self.tolerance && diff < self.threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, ErEnsembleC};
+    use rand_pcg::Pcg64;
+
+    #[test]
+    fn voter()
+    {
+        let mut rng = Pcg64::seed_from_u64(23);
+        let mut graph = Graph::<DiscreteOpinion>::complete_graph(10);
+        assert_eq!(Voter.active_edges(&graph), 45);
+        let steps = Voter.consensus_time(&mut graph, 1_000_000, &mut rng)
+            .unwrap();
+        assert!(steps > 0);
+        assert_eq!(Voter.active_edges(&graph), 0);
+        let opinion = graph.at(0).opinion();
+        assert!(graph.contained_iter().all(|node| node.opinion() == opinion));
+        assert_eq!(Voter.consensus_time(&mut graph, 10, &mut rng), Some(0));
+        assert!(!Voter.step(&mut graph, &mut rng));
+    }
+
+    #[test]
+    fn majority_rule()
+    {
+        // star: center with 4 leaves, 3 leaves share one opinion
+        let mut graph = Graph::<DiscreteOpinion>::new(5);
+        for i in 1..5 {
+            graph.add_edge(0, i).unwrap();
+        }
+        for (i, &opinion) in [0, 1, 1, 1, 2].iter().enumerate() {
+            graph.at_mut(i).set_opinion(opinion);
+        }
+        let mut rng = Pcg64::seed_from_u64(9);
+        let steps = MajorityRule.consensus_time(&mut graph, 10_000, &mut rng);
+        assert!(steps.is_some());
+        let opinion = graph.at(0).opinion();
+        assert!(graph.contained_iter().all(|node| node.opinion() == opinion));
+    }
+
+    #[test]
+    fn deffuant()
+    {
+        let mut graph = Graph::<ContinuousOpinion>::complete_graph(2);
+        graph.at_mut(1).set_opinion(0.4);
+        let model = Deffuant::new(0.5, 0.5, 1e-9);
+        let mut rng = Pcg64::seed_from_u64(3);
+        assert_eq!(model.consensus_time(&mut graph, 10, &mut rng), Some(1));
+        assert_eq!(graph.at(0).opinion(), 0.2);
+        assert_eq!(graph.at(1).opinion(), 0.2);
+
+        // opinions too far apart, converged without consensus
+        graph.at_mut(1).set_opinion(0.8);
+        assert_eq!(model.consensus_time(&mut graph, 10, &mut rng), Some(0));
+    }
+
+    #[test]
+    fn ensemble()
+    {
+        let rng = Pcg64::seed_from_u64(2390);
+        let mut ensemble = ErEnsembleC::<DiscreteOpinion, _>::new(30, 3.0, rng);
+        for _ in 0..100 {
+            Voter.step_ensemble(&mut ensemble);
+        }
+        // ER graph with c = 3 is likely disconnected, but every component can reach consensus
+        assert!(Voter.consensus_time_ensemble(&mut ensemble, 100_000_000).is_some());
+        assert_eq!(Voter.active_edges(ensemble.graph()), 0);
+    }
+}
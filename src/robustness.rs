@@ -0,0 +1,420 @@
+//! # Robustness against targeted attacks
+//! Vertices or edges are removed one after another, in the order given by some
+//! score, e.g., the degree or the betweenness. After each removal the size of the
+//! largest connected component (giant component) is tracked.
+//!
+//! * **non adaptive:** the scores are calculated once, for the initial graph
+//! * **adaptive:** the scores are recalculated after every removal
+//!
+//! This works for every [`GenericGraph`](crate::GenericGraph),
+//! see e.g. [`vertex_attack`](crate::GenericGraph::vertex_attack)
+//!
+//! # Citations
+//! The robustness measure `R` is defined in
+//! > C. M. Schneider, A. A. Moreira, J. S. Andrade Jr., S. Havlin and H. J. Herrmann,
+//! > "Mitigation of malicious attacks on networks"
+//! > *PNAS*&nbsp;**108**:3838-3841&nbsp;(2011),
+//! > DOI: [10.1073/pnas.1009440108](https://doi.org/10.1073/pnas.1009440108)
+//!
+//! For the different attack strategies see
+//! > P. Holme, B. J. Kim, C. N. Yoon and S. K. Han,
+//! > "Attack vulnerability of complex networks"
+//! > *Phys. Rev. E*&nbsp;**65**, 056109&nbsp;(2002),
+//! > DOI: [10.1103/PhysRevE.65.056109](https://doi.org/10.1103/PhysRevE.65.056109)
+//!
+//! Resilience of power grids, i.e., of the [spacial ensemble](crate::spacial), is for example studied in
+//! > Timo Dewenter and Alexander K. Hartmann,
+//! > "Large-deviation properties of resilience of power grids"
+//! > *New&nbsp;J.&nbsp;Phys.*&nbsp;**17**&nbsp;(2015),
+//! > DOI: [10.1088/1367-2630/17/1/015005](https://doi.org/10.1088/1367-2630/17/1/015005)
+use {
+    crate::{
+        traits::*,
+        graph::Graph,
+        union_find::UnionFind,
+        GenericGraph
+    },
+    std::collections::HashMap
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Serialize, Deserialize};
+
+/// # Which score decides the order of removal?
+/// * vertices (edges) with the highest score are removed first
+/// * ties are broken by removing the vertex with the smaller index
+///   (the edge that was encountered first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum AttackStrategy {
+    /// * vertices: degree of the vertex
+    /// * edges: product of the degrees of both endpoints
+    Degree,
+    /// * vertices: [`vertex_load(false)`](crate::GenericGraph::vertex_load)
+    /// * edges: [`edge_load()`](crate::GenericGraph::edge_load)
+    Betweenness,
+}
+
+/// # Result of an attack
+/// * `E` is `usize` for vertex attacks and `(usize, usize)` for edge attacks
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct RobustnessCurve<E> {
+    vertex_count: usize,
+    removal_order: Vec<E>,
+    giant_component: Vec<usize>,
+}
+
+impl<E> RobustnessCurve<E> {
+    /// # Order in which the vertices (edges) were removed
+    /// `removal_order()[0]` was removed first
+    pub fn removal_order(&self) -> &[E]
+    {
+        &self.removal_order
+    }
+
+    /// # Size of the giant component
+    /// * `giant_component_sizes()[k]` is the size of the largest connected component
+    ///   after `k` removals, i.e., the first entry corresponds to the original graph
+    /// * length is `self.removal_order().len() + 1`
+    pub fn giant_component_sizes(&self) -> &[usize]
+    {
+        &self.giant_component
+    }
+
+    /// # Fraction of vertices in the giant component
+    /// * same as [`giant_component_sizes`](`Self::giant_component_sizes`),
+    ///   but divided by the number of vertices of the original graph
+    pub fn giant_component_fractions(&self) -> Vec<f64>
+    {
+        let n = self.vertex_count as f64;
+        self.giant_component
+            .iter()
+            .map(|&size| size as f64 / n)
+            .collect()
+    }
+
+    /// returns number of vertices of the attacked graph
+    pub fn vertex_count(&self) -> usize
+    {
+        self.vertex_count
+    }
+
+    /// # Robustness measure R
+    /// > R = 1/Q Σ_{q=1}^{Q} s(q)
+    ///
+    /// where `Q` is the total number of removals and `s(q)` is the fraction of vertices in the
+    /// giant component after `q` removals.
+    /// * for vertex attacks `R` is in `[0, 0.5]`
+    /// * returns `NaN` if nothing was removed
+    pub fn robustness(&self) -> f64
+    {
+        let sum: usize = self.giant_component[1..]
+            .iter()
+            .sum();
+        sum as f64 / (self.vertex_count * self.removal_order.len()) as f64
+    }
+}
+
+/// index of the maximum, ties are broken by the smaller index
+fn arg_max<I>(iter: I) -> Option<usize>
+where I: Iterator<Item=(usize, f64)>
+{
+    iter.fold(
+        None,
+        |max: Option<(usize, f64)>, (index, score)|
+        {
+            match max {
+                Some((_, max_score)) if score.total_cmp(&max_score).is_le() => max,
+                _ => Some((index, score))
+            }
+        }
+    ).map(|(index, _)| index)
+}
+
+/// indices sorted by descending score, stable
+fn descending_order(scores: &[f64]) -> Vec<usize>
+{
+    let mut order: Vec<_> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+    order
+}
+
+impl<T, A> GenericGraph<T, A>
+where T: Node,
+      A: AdjContainer<T>
+{
+    /// # Targeted attack on vertices
+    /// * removes all vertices, one after another, ordered by `strategy`
+    /// * `adaptive`: recalculate the scores after each removal?
+    /// * returns the removal order and the size of the giant component after each removal
+    /// * `self` is not changed, the attack is performed on a copy of the topology
+    /// # Example
+    /// ```
+    /// use net_ensembles::{Graph, EmptyNode, robustness::AttackStrategy};
+    ///
+    /// // star graph
+    /// let mut graph = Graph::<EmptyNode>::new(5);
+    /// for i in 1..5 {
+    ///     graph.add_edge(0, i).unwrap();
+    /// }
+    /// let curve = graph.vertex_attack(AttackStrategy::Degree, false);
+    /// assert_eq!(curve.removal_order()[0], 0);
+    /// assert_eq!(curve.giant_component_sizes(), &[5, 1, 1, 1, 1, 0]);
+    /// assert_eq!(curve.robustness(), 4.0 / 25.0);
+    /// ```
+    pub fn vertex_attack(&self, strategy: AttackStrategy, adaptive: bool) -> RobustnessCurve<usize>
+    {
+        match strategy {
+            AttackStrategy::Degree => self.vertex_attack_helper(
+                adaptive,
+                |graph, _| graph.degree_iter().map(|d| d as f64).collect()
+            ),
+            AttackStrategy::Betweenness => self.vertex_attack_helper(
+                adaptive,
+                |graph, _| graph.vertex_load(false)
+            )
+        }
+    }
+
+    /// # Targeted attack on vertices with custom score
+    /// * same as [`vertex_attack`](`Self::vertex_attack`), but the vertex with the
+    ///   highest `score(graph, index)` is removed first
+    /// * `graph` is the current state of the attacked copy, i.e.,
+    ///   all edges of removed vertices are already gone
+    /// * `score` is only called for vertices that were not removed yet
+    pub fn vertex_attack_by<F>(&self, adaptive: bool, mut score: F) -> RobustnessCurve<usize>
+    where F: FnMut(&Graph<T>, usize) -> f64
+    {
+        self.vertex_attack_helper(
+            adaptive,
+            |graph, removed| {
+                (0..graph.vertex_count())
+                    .map(
+                        |index|
+                        if removed[index] {
+                            f64::NAN
+                        } else {
+                            score(graph, index)
+                        }
+                    ).collect()
+            }
+        )
+    }
+
+    fn vertex_attack_helper<F>(&self, adaptive: bool, mut scores: F) -> RobustnessCurve<usize>
+    where F: FnMut(&Graph<T>, &[bool]) -> Vec<f64>
+    {
+        let mut removed = vec![false; self.vertex_count()];
+        let mut graph = Graph::from(self);
+        let order = if adaptive {
+            let mut order = Vec::with_capacity(self.vertex_count());
+            let mut neighbors = Vec::new();
+            for _ in 0..self.vertex_count() {
+                let score = scores(&graph, &removed);
+                let next = arg_max(
+                    score.into_iter()
+                        .enumerate()
+                        .filter(|(index, _)| !removed[*index])
+                ).unwrap();
+
+                neighbors.clear();
+                neighbors.extend(graph.container(next).neighbors());
+                for &neighbor in neighbors.iter() {
+                    graph.remove_edge(next, neighbor).unwrap();
+                }
+                removed[next] = true;
+                order.push(next);
+            }
+            order
+        } else {
+            descending_order(&scores(&graph, &removed))
+        };
+        self.vertex_removal_curve(order)
+    }
+
+    /// percolation in reverse: add vertices in reverse removal order
+    fn vertex_removal_curve(&self, removal_order: Vec<usize>) -> RobustnessCurve<usize>
+    {
+        let n = self.vertex_count();
+        let mut giant_component = vec![0; n + 1];
+        let mut present = vec![false; n];
+        let mut union_find = UnionFind::new(n);
+        let mut max = 0;
+        for (k, &vertex) in removal_order.iter().enumerate().rev() {
+            present[vertex] = true;
+            max = max.max(1);
+            for &neighbor in self.container(vertex).neighbors() {
+                if present[neighbor] {
+                    max = max.max(union_find.union(vertex, neighbor));
+                }
+            }
+            giant_component[k] = max;
+        }
+        RobustnessCurve{
+            vertex_count: n,
+            removal_order,
+            giant_component
+        }
+    }
+
+    /// # Targeted attack on edges
+    /// * removes all edges, one after another, ordered by `strategy`
+    /// * `adaptive`: recalculate the scores after each removal?
+    /// * returns the removal order and the size of the giant component after each removal
+    /// * `self` is not changed, the attack is performed on a copy of the topology
+    /// * edges are returned as `(i, j)` with `i < j`
+    pub fn edge_attack(&self, strategy: AttackStrategy, adaptive: bool) -> RobustnessCurve<(usize, usize)>
+    {
+        match strategy {
+            AttackStrategy::Degree => self.edge_attack_helper(
+                adaptive,
+                |graph, edges| {
+                    edges.iter()
+                        .map(
+                            |&(i, j)|
+                            (graph.container(i).degree() * graph.container(j).degree()) as f64
+                        ).collect()
+                }
+            ),
+            AttackStrategy::Betweenness => self.edge_attack_helper(
+                adaptive,
+                |graph, edges| {
+                    let load: HashMap<_, _> = graph.edge_load()
+                        .into_iter()
+                        .collect();
+                    edges.iter()
+                        .map(|edge| load[edge])
+                        .collect()
+                }
+            )
+        }
+    }
+
+    /// # Targeted attack on edges with custom score
+    /// * same as [`edge_attack`](`Self::edge_attack`), but the edge `(i, j)` with the
+    ///   highest `score(graph, i, j)` is removed first
+    /// * `graph` is the current state of the attacked copy
+    /// * `score` is only called for edges that were not removed yet, `i < j` is guaranteed
+    pub fn edge_attack_by<F>(&self, adaptive: bool, mut score: F) -> RobustnessCurve<(usize, usize)>
+    where F: FnMut(&Graph<T>, usize, usize) -> f64
+    {
+        self.edge_attack_helper(
+            adaptive,
+            |graph, edges| {
+                edges.iter()
+                    .map(|&(i, j)| score(graph, i, j))
+                    .collect()
+            }
+        )
+    }
+
+    fn edge_attack_helper<F>(&self, adaptive: bool, mut scores: F) -> RobustnessCurve<(usize, usize)>
+    where F: FnMut(&Graph<T>, &[(usize, usize)]) -> Vec<f64>
+    {
+        let mut graph = Graph::from(self);
+        let mut edges = Vec::with_capacity(self.edge_count());
+        for i in 0..self.vertex_count() {
+            for &j in self.container(i).neighbors() {
+                if i < j {
+                    edges.push((i, j));
+                }
+            }
+        }
+
+        let order = if adaptive {
+            let mut order = Vec::with_capacity(edges.len());
+            while !edges.is_empty() {
+                let score = scores(&graph, &edges);
+                let next = arg_max(score.into_iter().enumerate())
+                    .unwrap();
+                let edge = edges.remove(next);
+                graph.remove_edge(edge.0, edge.1).unwrap();
+                order.push(edge);
+            }
+            order
+        } else {
+            descending_order(&scores(&graph, &edges))
+                .into_iter()
+                .map(|index| edges[index])
+                .collect()
+        };
+        self.edge_removal_curve(order)
+    }
+
+    /// percolation in reverse: add edges in reverse removal order
+    fn edge_removal_curve(&self, removal_order: Vec<(usize, usize)>) -> RobustnessCurve<(usize, usize)>
+    {
+        let n = self.vertex_count();
+        let mut giant_component = vec![0; removal_order.len() + 1];
+        let mut union_find = UnionFind::new(n);
+        let mut max = n.min(1);
+        giant_component[removal_order.len()] = max;
+        for (k, &(i, j)) in removal_order.iter().enumerate().rev() {
+            max = max.max(union_find.union(i, j));
+            giant_component[k] = max;
+        }
+        RobustnessCurve{
+            vertex_count: n,
+            removal_order,
+            giant_component
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EmptyNode;
+
+    fn path(n: usize) -> Graph<EmptyNode>
+    {
+        let mut graph = Graph::new(n);
+        for i in 1..n {
+            graph.add_edge(i - 1, i).unwrap();
+        }
+        graph
+    }
+
+    #[test]
+    fn adaptive_betweenness_vertex_attack()
+    {
+        let graph = path(5);
+        let curve = graph.vertex_attack(AttackStrategy::Betweenness, true);
+        assert_eq!(curve.removal_order(), &[2, 0, 1, 3, 4]);
+        assert_eq!(curve.giant_component_sizes(), &[5, 2, 2, 2, 1, 0]);
+        assert_eq!(curve.robustness(), 7.0 / 25.0);
+
+        let custom = graph.vertex_attack_by(
+            true,
+            |g, index| g.vertex_load(false)[index]
+        );
+        assert_eq!(custom.removal_order(), curve.removal_order());
+    }
+
+    #[test]
+    fn edge_attack()
+    {
+        let graph = path(4);
+        let curve = graph.edge_attack(AttackStrategy::Betweenness, true);
+        assert_eq!(curve.removal_order(), &[(1, 2), (0, 1), (2, 3)]);
+        assert_eq!(curve.giant_component_sizes(), &[4, 2, 2, 1]);
+        assert_eq!(curve.robustness(), 5.0 / 12.0);
+
+        let curve = graph.edge_attack(AttackStrategy::Degree, false);
+        assert_eq!(curve.removal_order()[0], (1, 2));
+
+        let curve = graph.edge_attack_by(false, |_, i, _| i as f64);
+        assert_eq!(curve.removal_order(), &[(2, 3), (1, 2), (0, 1)]);
+        assert_eq!(curve.giant_component_sizes(), &[4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn empty_graph_attack()
+    {
+        let graph = Graph::<EmptyNode>::new(0);
+        let curve = graph.vertex_attack(AttackStrategy::Degree, true);
+        assert_eq!(curve.giant_component_sizes(), &[0]);
+        assert!(curve.robustness().is_nan());
+    }
+}
@@ -2,7 +2,65 @@
 //! * contains Simple sampling, WangLandau, entropic sampling, Metropolis, Histograms
 //! * This is just for compatibility reasons, everything was moved into its 
 //! own crate, which you can find at [crates.io/crates/sampling](https://crates.io/crates/sampling)
+//! * with feature `parallel`: [`ParSimpleSample`] for simple sampling with independent replicas
 
 /// Contains traits useful for sampling an ensemble
 /// like MarkovChain or Metropolis etc.
-pub use sampling::*;
\ No newline at end of file
+pub use sampling::*;
+
+#[cfg(feature = "parallel")]
+use {
+    rand::SeedableRng,
+    rayon::prelude::*
+};
+
+/// # Simple sampling with independent replicas in parallel
+/// * requires feature `parallel`
+/// * implemented for every ensemble, which implements `SimpleSample`, `HasRng` and `Clone`
+///   and can be shared between threads
+#[cfg(feature = "parallel")]
+pub trait ParSimpleSample<R>: SimpleSample + HasRng<R> + Clone + Send + Sync
+where R: SeedableRng
+{
+    /// # Simple sampling with one replica per seed
+    /// * the ensemble is cloned once for every seed, the rng of the clone
+    ///   is replaced by `R::seed_from_u64(seed)`
+    /// * every replica performs `simple_sample_vec(times, &f)`, i.e., it is randomized
+    ///   `times` times and `f` is evaluated after each randomization
+    /// * `result[i]` contains the results of the replica seeded with `seeds[i]`,
+    ///   so the result only depends on the seeds, not on the scheduling of the threads
+    /// ```
+    /// use net_ensembles::{EmptyNode, ErEnsembleC, traits::*};
+    /// use net_ensembles::sampling::ParSimpleSample;
+    /// use rand_pcg::Pcg64;
+    /// use net_ensembles::rand::SeedableRng;
+    ///
+    /// let e = ErEnsembleC::<EmptyNode, Pcg64>::new(100, 2.0, Pcg64::seed_from_u64(1));
+    /// let seeds = [10, 11, 12, 13];
+    /// let leafs = e.par_simple_sample(&seeds, 5, |e| e.graph().leaf_count());
+    /// assert_eq!(leafs.len(), 4);
+    /// assert!(leafs.iter().all(|l| l.len() == 5));
+    /// assert_eq!(leafs, e.par_simple_sample(&seeds, 5, |e| e.graph().leaf_count()));
+    /// ```
+    fn par_simple_sample<F, G>(&self, seeds: &[u64], times: usize, f: F) -> Vec<Vec<G>>
+    where F: Fn(&Self) -> G + Sync,
+          G: Send
+    {
+        seeds.par_iter()
+            .map(
+                |&seed|
+                {
+                    let mut replica = self.clone();
+                    let mut rng = R::seed_from_u64(seed);
+                    replica.swap_rng(&mut rng);
+                    replica.simple_sample_vec(times, &f)
+                }
+            ).collect()
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<E, R> ParSimpleSample<R> for E
+where E: SimpleSample + HasRng<R> + Clone + Send + Sync,
+      R: SeedableRng
+{}
@@ -0,0 +1,692 @@
+//! # Binary snapshots of ensembles
+//! Versioned checkpoints, e.g., to save and restore long Wang-Landau runs.
+//!
+//! A snapshot contains
+//! * a header: magic bytes, format version, name and layout version of the ensemble
+//!   and the version of this crate, see [`SnapshotHeader`]
+//! * the topology in compressed sparse row (CSR) form, i.e., offsets and neighbor indices
+//! * the additional data `T` of the vertices, serialized via serde
+//! * the parameters of the ensemble
+//! * the state of the random number generator
+//!
+//! Loading fails with a [`SnapshotError`] instead of restoring garbage, if the file is not
+//! a snapshot, belongs to another ensemble or was written by an incompatible version.
+//! The order of the adjacency lists is preserved, i.e., a restored ensemble continues a
+//! Markov chain exactly like the original would have.
+//!
+//! # Example
+//! ```
+//! use net_ensembles::{ErEnsembleC, EmptyNode, MarkovChain, WithGraph, rand::SeedableRng};
+//! use net_ensembles::snapshot::Snapshot;
+//! use rand_pcg::Pcg64;
+//!
+//! let rng = Pcg64::seed_from_u64(1234);
+//! let mut ensemble = ErEnsembleC::<EmptyNode, _>::new(100, 3.0, rng);
+//! ensemble.m_steps_quiet(10);
+//!
+//! let mut buf = Vec::new();
+//! ensemble.save_snapshot(&mut buf).unwrap();
+//! let mut restored = ErEnsembleC::<EmptyNode, Pcg64>::load_snapshot(buf.as_slice()).unwrap();
+//!
+//! ensemble.m_steps_quiet(10);
+//! restored.m_steps_quiet(10);
+//! assert_eq!(ensemble.graph().edge_count(), restored.graph().edge_count());
+//! ```
+use {
+    crate::{
+        traits::*,
+        GenericGraph
+    },
+    serde::{Serialize, de::DeserializeOwned},
+    std::{
+        fmt,
+        error::Error,
+        io::{Read, Write},
+        marker::PhantomData
+    }
+};
+
+/// Magic bytes at the start of every snapshot
+pub const SNAPSHOT_MAGIC: [u8; 8] = *b"NETENSMB";
+
+/// Version of the snapshot format, i.e., of the header and the section layout
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// # Save and restore ensembles
+/// * implemented for all ensembles of this crate, if the node data `T` and the
+///   random number generator can be serialized
+/// * see [module documentation](crate::snapshot)
+pub trait Snapshot: Sized {
+    /// # Write binary snapshot
+    fn save_snapshot<W: Write>(&self, writer: W) -> Result<(), SnapshotError>;
+
+    /// # Restore ensemble from binary snapshot
+    fn load_snapshot<R: Read>(reader: R) -> Result<Self, SnapshotError>;
+}
+
+/// # Errors of snapshots
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// ### Reading or writing failed
+    Io(std::io::Error),
+    /// ### Magic bytes do not match
+    /// The data is not a snapshot
+    NotASnapshot,
+    /// ### Snapshot format is newer than this crate
+    UnsupportedFormatVersion{
+        /// format version of the snapshot
+        found: u32,
+        /// newest format version supported
+        supported: u32,
+    },
+    /// ### Snapshot belongs to another ensemble
+    WrongEnsemble{
+        /// ensemble that should be loaded
+        expected: String,
+        /// ensemble stored in the snapshot
+        found: String,
+    },
+    /// ### Layout of the ensemble changed
+    UnsupportedEnsembleVersion{
+        /// layout version of the snapshot
+        found: u32,
+        /// layout version of this crate
+        supported: u32,
+    },
+    /// ### Data of the snapshot is inconsistent
+    Corrupt(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "IO error: {}", e),
+            SnapshotError::NotASnapshot => write!(f, "not a snapshot"),
+            SnapshotError::UnsupportedFormatVersion{found, supported} => write!(
+                f,
+                "snapshot format version {} is not supported, newest supported version is {}",
+                found,
+                supported
+            ),
+            SnapshotError::WrongEnsemble{expected, found} => write!(
+                f,
+                "snapshot contains {}, expected {}",
+                found,
+                expected
+            ),
+            SnapshotError::UnsupportedEnsembleVersion{found, supported} => write!(
+                f,
+                "ensemble layout version {} is not supported, expected {}",
+                found,
+                supported
+            ),
+            SnapshotError::Corrupt(message) => write!(f, "corrupt snapshot: {}", message),
+        }
+    }
+}
+
+impl Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SnapshotError::Io(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(e: std::io::Error) -> Self {
+        SnapshotError::Io(e)
+    }
+}
+
+impl From<bincode::Error> for SnapshotError {
+    fn from(e: bincode::Error) -> Self {
+        match *e {
+            bincode::ErrorKind::Io(e) => SnapshotError::Io(e),
+            e => SnapshotError::Corrupt(e.to_string())
+        }
+    }
+}
+
+fn corrupt<S: Into<String>>(message: S) -> SnapshotError {
+    SnapshotError::Corrupt(message.into())
+}
+
+/// # Header of a snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotHeader {
+    /// version of the snapshot format
+    pub format_version: u32,
+    /// name of the ensemble, e.g., `ErEnsembleC`
+    pub ensemble: String,
+    /// version of the layout of the ensemble
+    pub ensemble_version: u32,
+    /// version of `net_ensembles` that wrote the snapshot
+    pub crate_version: String,
+}
+
+pub(crate) fn write_u32<W: Write>(writer: &mut W, value: u32) -> Result<(), std::io::Error> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+pub(crate) fn write_u64<W: Write>(writer: &mut W, value: u64) -> Result<(), std::io::Error> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_str<W: Write>(writer: &mut W, s: &str) -> Result<(), std::io::Error> {
+    write_u32(writer, s.len() as u32)?;
+    writer.write_all(s.as_bytes())
+}
+
+pub(crate) fn read_u32<R: Read>(reader: &mut R) -> Result<u32, std::io::Error> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+pub(crate) fn read_u64<R: Read>(reader: &mut R) -> Result<u64, std::io::Error> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// reads exactly `len` bytes without trusting `len` for the allocation
+pub(crate) fn read_bytes<R: Read>(reader: &mut R, len: u64) -> Result<Vec<u8>, SnapshotError> {
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(SnapshotError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(bytes)
+}
+
+fn read_str<R: Read>(reader: &mut R) -> Result<String, SnapshotError> {
+    let len = read_u32(reader)?;
+    String::from_utf8(read_bytes(reader, len as u64)?)
+        .map_err(|_| corrupt("invalid UTF-8"))
+}
+
+/// # Read header of a snapshot
+/// * checks the magic bytes and the format version
+/// * useful to find out, which ensemble is stored in a snapshot
+pub fn read_snapshot_header<R: Read>(mut reader: R) -> Result<SnapshotHeader, SnapshotError>
+{
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if magic != SNAPSHOT_MAGIC {
+        return Err(SnapshotError::NotASnapshot);
+    }
+    let format_version = read_u32(&mut reader)?;
+    if format_version > SNAPSHOT_FORMAT_VERSION {
+        return Err(SnapshotError::UnsupportedFormatVersion{
+            found: format_version,
+            supported: SNAPSHOT_FORMAT_VERSION
+        });
+    }
+    let ensemble = read_str(&mut reader)?;
+    let ensemble_version = read_u32(&mut reader)?;
+    let crate_version = read_str(&mut reader)?;
+    Ok(SnapshotHeader{
+        format_version,
+        ensemble,
+        ensemble_version,
+        crate_version,
+    })
+}
+
+/// # Adjacency containers that can be stored in snapshots
+/// * everything except the neighbor indices and the node data is stored in `Extra`
+/// * required for the containers of ensembles, which implement [`Snapshot`]
+pub(crate) trait SnapshotContainer<T>: AdjContainer<T> + Sized {
+    /// additional data of each vertex
+    type Extra: Serialize + DeserializeOwned;
+
+    /// additional data of this vertex
+    fn snapshot_extra(&self) -> Self::Extra;
+
+    /// * restores container
+    /// * `None` if `extra` does not fit to `adj`
+    fn from_snapshot(id: usize, node: T, adj: Vec<usize>, extra: Self::Extra) -> Option<Self>;
+}
+
+/// Writes header and length prefixed sections
+pub(crate) struct SnapshotWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    /// writes header
+    pub(crate) fn new(mut writer: W, ensemble: &str, ensemble_version: u32) -> Result<Self, SnapshotError>
+    {
+        writer.write_all(&SNAPSHOT_MAGIC)?;
+        write_u32(&mut writer, SNAPSHOT_FORMAT_VERSION)?;
+        write_str(&mut writer, ensemble)?;
+        write_u32(&mut writer, ensemble_version)?;
+        write_str(&mut writer, env!("CARGO_PKG_VERSION"))?;
+        Ok(Self{writer})
+    }
+
+    fn raw_section(&mut self, bytes: &[u8]) -> Result<(), SnapshotError>
+    {
+        write_u64(&mut self.writer, bytes.len() as u64)?;
+        self.writer.write_all(bytes)?;
+        Ok(())
+    }
+
+    /// serializes `value` into its own section
+    pub(crate) fn section<S: Serialize + ?Sized>(&mut self, value: &S) -> Result<(), SnapshotError>
+    {
+        let bytes = bincode::serialize(value)?;
+        self.raw_section(&bytes)
+    }
+
+    /// writes topology in CSR form, node data and additional data of the containers
+    pub(crate) fn graph<T, A>(&mut self, graph: &GenericGraph<T, A>) -> Result<(), SnapshotError>
+    where T: Node,
+          A: SnapshotContainer<T>
+    {
+        let n = graph.vertex_count();
+        let mut csr = Vec::with_capacity(8 * (2 + n + 2 * graph.edge_count()));
+        write_u64(&mut csr, n as u64)?;
+        let mut offset = 0;
+        write_u64(&mut csr, offset)?;
+        for container in graph.container_iter() {
+            offset += container.degree() as u64;
+            write_u64(&mut csr, offset)?;
+        }
+        for container in graph.container_iter() {
+            for &neighbor in container.neighbors() {
+                write_u64(&mut csr, neighbor as u64)?;
+            }
+        }
+        self.raw_section(&csr)?;
+
+        let nodes: Vec<_> = graph.contained_iter().collect();
+        self.section(&nodes)?;
+        let extra: Vec<_> = graph.container_iter()
+            .map(|container| container.snapshot_extra())
+            .collect();
+        self.section(&extra)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// * checks, that the adjacency lists contain no duplicates and
+///   that `j` is adjacent to `i` **iff** `i` is adjacent to `j`
+/// * `adj(i)` is the adjacency list of vertex `i`, all neighbors have to be less than `n`
+/// * returns the reason as error
+/// * `O(vertices + edges)`
+pub(crate) fn check_adjacency<'a, F>(n: usize, adj: F) -> Result<(), String>
+where F: Fn(usize) -> &'a [usize]
+{
+    // mark[j] == i + 1 <=> j is in adjacency list of i
+    let mut mark = vec![0; n];
+    let mut in_degree = vec![0; n];
+    for i in 0..n {
+        for &j in adj(i) {
+            if mark[j] == i + 1 {
+                return Err(format!("vertex {} is listed twice as neighbor of vertex {}", j, i));
+            }
+            mark[j] = i + 1;
+            in_degree[j] += 1;
+        }
+    }
+    if let Some(i) = (0..n).find(|&i| in_degree[i] != adj(i).len()) {
+        return Err(format!("adjacency list of vertex {} is not symmetric", i));
+    }
+
+    // transposed adjacency lists in CSR form
+    let mut offsets = Vec::with_capacity(n + 1);
+    offsets.push(0);
+    for degree in in_degree {
+        offsets.push(offsets.last().unwrap() + degree);
+    }
+    let mut fill = offsets.clone();
+    let mut transposed = vec![0; *offsets.last().unwrap()];
+    for i in 0..n {
+        for &j in adj(i) {
+            transposed[fill[j]] = i;
+            fill[j] += 1;
+        }
+    }
+
+    // both lists have the same length and no duplicates,
+    // i.e., they are equal if every entry of one is contained in the other
+    mark.iter_mut().for_each(|m| *m = 0);
+    for i in 0..n {
+        for &j in adj(i) {
+            mark[j] = i + 1;
+        }
+        if transposed[offsets[i]..offsets[i + 1]].iter().any(|&j| mark[j] != i + 1) {
+            return Err(format!("adjacency list of vertex {} is not symmetric", i));
+        }
+    }
+    Ok(())
+}
+
+/// Reads header and length prefixed sections
+pub(crate) struct SnapshotReader<R> {
+    reader: R,
+}
+
+impl<R: Read> SnapshotReader<R> {
+    /// reads and checks header
+    pub(crate) fn new(mut reader: R, ensemble: &str, ensemble_version: u32) -> Result<Self, SnapshotError>
+    {
+        let header = read_snapshot_header(&mut reader)?;
+        if header.ensemble != ensemble {
+            return Err(SnapshotError::WrongEnsemble{
+                expected: ensemble.to_owned(),
+                found: header.ensemble
+            });
+        }
+        if header.ensemble_version != ensemble_version {
+            return Err(SnapshotError::UnsupportedEnsembleVersion{
+                found: header.ensemble_version,
+                supported: ensemble_version
+            });
+        }
+        Ok(Self{reader})
+    }
+
+    fn raw_section(&mut self) -> Result<Vec<u8>, SnapshotError>
+    {
+        let len = read_u64(&mut self.reader)?;
+        read_bytes(&mut self.reader, len)
+    }
+
+    /// deserializes the next section
+    pub(crate) fn section<S: DeserializeOwned>(&mut self) -> Result<S, SnapshotError>
+    {
+        let bytes = self.raw_section()?;
+        Ok(bincode::deserialize(&bytes)?)
+    }
+
+    /// reads graph written by [`SnapshotWriter::graph`]
+    pub(crate) fn graph<T, A>(&mut self) -> Result<GenericGraph<T, A>, SnapshotError>
+    where T: Node + DeserializeOwned,
+          A: SnapshotContainer<T>
+    {
+        let csr = self.raw_section()?;
+        let mut csr = csr.as_slice();
+        let n = read_u64(&mut csr)? as usize;
+        // n is not trusted: compare with the number of words instead of multiplying
+        if csr.len() % 8 != 0 {
+            return Err(corrupt("CSR length is not a multiple of 8"));
+        }
+        if n >= csr.len() / 8 {
+            return Err(corrupt("CSR offsets truncated"));
+        }
+        let mut offsets = Vec::with_capacity(n + 1);
+        for _ in 0..=n {
+            offsets.push(read_u64(&mut csr)? as usize);
+        }
+        let total = offsets[n];
+        if offsets[0] != 0 || offsets.windows(2).any(|w| w[0] > w[1]) || total != csr.len() / 8 {
+            return Err(corrupt("invalid CSR offsets"));
+        }
+        if total % 2 != 0 {
+            return Err(corrupt("odd number of adjacency entries"));
+        }
+
+        let nodes: Vec<T> = self.section()?;
+        let extra: Vec<A::Extra> = self.section()?;
+        if nodes.len() != n || extra.len() != n {
+            return Err(corrupt("number of vertices does not match"));
+        }
+
+        let mut adjs = Vec::with_capacity(n);
+        for (id, range) in offsets.windows(2).enumerate() {
+            let mut adj = Vec::with_capacity(range[1] - range[0]);
+            for _ in range[0]..range[1] {
+                let neighbor = read_u64(&mut csr)? as usize;
+                if neighbor >= n || neighbor == id {
+                    return Err(corrupt(format!("invalid neighbor {} of vertex {}", neighbor, id)));
+                }
+                adj.push(neighbor);
+            }
+            adjs.push(adj);
+        }
+        check_adjacency(adjs.len(), |i| &adjs[i]).map_err(corrupt)?;
+
+        let mut vertices = Vec::with_capacity(n);
+        for (id, ((node, extra), adj)) in nodes.into_iter()
+            .zip(extra)
+            .zip(adjs)
+            .enumerate()
+        {
+            let container = A::from_snapshot(id, node, adj, extra)
+                .ok_or_else(|| corrupt(format!("inconsistent data of vertex {}", id)))?;
+            vertices.push(container);
+        }
+
+        Ok(GenericGraph{
+            next_id: n,
+            edge_count: total / 2,
+            vertices,
+            phantom: PhantomData
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        EmptyNode,
+        ErEnsembleC,
+        ErEnsembleM,
+        SwEnsemble,
+        BAensemble,
+        ConfigurationModel,
+        graph::NodeContainer,
+        watts_strogatz::WS,
+        rand::SeedableRng,
+        spacial::SpacialEnsemble
+    };
+    use std::num::NonZeroUsize;
+    use rand_pcg::Pcg64;
+
+    /// saves, restores and checks that both continue identically
+    fn check<E, S, Res, G>(mut ensemble: E)
+    where E: Snapshot + MarkovChain<S, Res> + MeasurableGraphQuantities<G>
+    {
+        let degrees = |e: &E| (0..e.vertex_count())
+            .map(|index| e.degree(index))
+            .collect::<Vec<_>>();
+        let mut buf = Vec::new();
+        ensemble.save_snapshot(&mut buf).unwrap();
+        let mut restored = E::load_snapshot(buf.as_slice()).unwrap();
+        assert_eq!(degrees(&ensemble), degrees(&restored));
+        for _ in 0..10 {
+            ensemble.m_steps_quiet(20);
+            restored.m_steps_quiet(20);
+            assert_eq!(ensemble.edge_count(), restored.edge_count());
+            assert_eq!(degrees(&ensemble), degrees(&restored));
+            assert_eq!(ensemble.transitivity().to_bits(), restored.transitivity().to_bits());
+        }
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        let mut rng = Pcg64::seed_from_u64(9123);
+        let mut new_rng = || Pcg64::from_rng(&mut rng).unwrap();
+        check(ErEnsembleC::<EmptyNode, _>::new(50, 3.0, new_rng()));
+        check(ErEnsembleM::<EmptyNode, _>::new(50, 70, new_rng()));
+        check(SwEnsemble::<EmptyNode, _>::new(50, 0.1, new_rng()));
+        let source = ErEnsembleC::<EmptyNode, _>::new(50, 4.0, new_rng());
+        check(ConfigurationModel::<EmptyNode, _>::from_generic_graph(source.graph(), new_rng()));
+    }
+
+    /// saves, restores and checks that both sample identically
+    fn check_randomize<E, G>(mut ensemble: E)
+    where E: Snapshot + SimpleSample + MeasurableGraphQuantities<G>
+    {
+        let degrees = |e: &E| (0..e.vertex_count())
+            .map(|index| e.degree(index))
+            .collect::<Vec<_>>();
+        let mut buf = Vec::new();
+        ensemble.save_snapshot(&mut buf).unwrap();
+        let mut restored = E::load_snapshot(buf.as_slice()).unwrap();
+        assert_eq!(degrees(&ensemble), degrees(&restored));
+        for _ in 0..5 {
+            ensemble.randomize();
+            restored.randomize();
+            assert_eq!(degrees(&ensemble), degrees(&restored));
+            assert_eq!(ensemble.transitivity().to_bits(), restored.transitivity().to_bits());
+        }
+    }
+
+    #[test]
+    fn round_trips_randomize()
+    {
+        let mut rng = Pcg64::seed_from_u64(732);
+        let mut new_rng = || Pcg64::from_rng(&mut rng).unwrap();
+        check_randomize(BAensemble::<EmptyNode, _>::new(50, new_rng(), 2, 3));
+        let distance = NonZeroUsize::new(2).unwrap();
+        check_randomize(WS::<EmptyNode, _>::new(50, distance, 0.2, new_rng()).unwrap());
+        let config = ConfigurationModel::<EmptyNode, _>::from_const(4, 50, new_rng()).unwrap();
+        check_randomize(config);
+    }
+
+    #[test]
+    fn corrupt_adjacency()
+    {
+        use crate::graph::{Graph, NodeContainer};
+
+        fn reload(graph: &Graph<EmptyNode>) -> Result<Graph<EmptyNode>, SnapshotError>
+        {
+            let mut buf = Vec::new();
+            let mut writer = SnapshotWriter::new(&mut buf, "Graph", 1).unwrap();
+            writer.graph(graph).unwrap();
+            let mut reader = SnapshotReader::new(buf.as_slice(), "Graph", 1).unwrap();
+            reader.graph::<EmptyNode, NodeContainer<EmptyNode>>()
+        }
+
+        let mut graph = Graph::<EmptyNode>::new(6);
+        graph.add_edge(0, 1).unwrap();
+        graph.add_edge(1, 2).unwrap();
+        graph.add_edge(3, 1).unwrap();
+        let restored = reload(&graph).unwrap();
+        assert_eq!(restored.edge_count(), 3);
+
+        // 2 -> 0 without 0 -> 2, but even number of entries
+        let mut asymmetric = graph.clone();
+        asymmetric.vertices[2].adj.push(0);
+        asymmetric.vertices[2].adj.push(3);
+        assert!(matches!(reload(&asymmetric), Err(SnapshotError::Corrupt(_))));
+
+        // directed cycle 0 -> 2 -> 4 -> 5 -> 0: in and out degrees still match
+        let mut asymmetric = graph.clone();
+        asymmetric.vertices[0].adj.push(2);
+        asymmetric.vertices[2].adj.push(4);
+        asymmetric.vertices[4].adj.push(5);
+        asymmetric.vertices[5].adj.push(0);
+        assert!(matches!(reload(&asymmetric), Err(SnapshotError::Corrupt(_))));
+
+        let mut duplicate = graph.clone();
+        duplicate.vertices[0].adj.push(1);
+        duplicate.vertices[1].adj.push(0);
+        assert!(matches!(reload(&duplicate), Err(SnapshotError::Corrupt(_))));
+    }
+
+    #[test]
+    fn corrupt_er_m_edges()
+    {
+        let rng = Pcg64::seed_from_u64(78);
+        let e = ErEnsembleM::<EmptyNode, Pcg64>::new(20, 30, rng);
+        let graph = e.graph();
+        let edges: Vec<(usize, usize)> = (0..graph.vertex_count())
+            .flat_map(|i| graph.container(i).neighbors().filter(move |&&j| i < j).map(move |&j| (i, j)))
+            .collect();
+        let reload = |edges: &[(usize, usize)]| {
+            let mut buf = Vec::new();
+            let mut writer = SnapshotWriter::new(&mut buf, "ErEnsembleM", 2).unwrap();
+            writer.graph(graph).unwrap();
+            writer.section(&(edges.len(), edges)).unwrap();
+            writer.section(&Pcg64::seed_from_u64(1)).unwrap();
+            ErEnsembleM::<EmptyNode, Pcg64>::load_snapshot(buf.as_slice())
+        };
+        assert!(reload(&edges).is_ok());
+
+        let mut reversed = edges.clone();
+        reversed[3] = (edges[3].1, edges[3].0);
+        assert!(matches!(reload(&reversed), Err(SnapshotError::Corrupt(_))));
+
+        let mut duplicate = edges.clone();
+        duplicate[1] = edges[0];
+        assert!(matches!(reload(&duplicate), Err(SnapshotError::Corrupt(_))));
+
+        let missing = (1..graph.vertex_count())
+            .find(|&j| !graph.container(0).is_adjacent(j))
+            .unwrap();
+        let mut not_an_edge = edges.clone();
+        not_an_edge[0] = (0, missing);
+        assert!(matches!(reload(&not_an_edge), Err(SnapshotError::Corrupt(_))));
+    }
+
+    #[test]
+    fn errors()
+    {
+        let rng = Pcg64::seed_from_u64(23);
+        let e = SpacialEnsemble::<EmptyNode, _>::new(30, rng, 0.9, 3.0);
+        let mut buf = Vec::new();
+        e.save_snapshot(&mut buf).unwrap();
+
+        let header = read_snapshot_header(buf.as_slice()).unwrap();
+        assert_eq!(header.ensemble, "SpacialEnsemble");
+        assert_eq!(header.format_version, SNAPSHOT_FORMAT_VERSION);
+        let restored = SpacialEnsemble::<EmptyNode, Pcg64>::load_snapshot(buf.as_slice()).unwrap();
+        for (a, b) in e.as_ref().container_iter().zip(restored.as_ref().container_iter()) {
+            assert_eq!((a.get_x(), a.get_y()), (b.get_x(), b.get_y()));
+        }
+
+        assert!(matches!(
+            ErEnsembleC::<EmptyNode, Pcg64>::load_snapshot(buf.as_slice()),
+            Err(SnapshotError::WrongEnsemble{..})
+        ));
+        assert!(matches!(
+            SpacialEnsemble::<EmptyNode, Pcg64>::load_snapshot(&buf[..buf.len() - 1]),
+            Err(SnapshotError::Io(_))
+        ));
+        let mut not_a_snapshot = buf.clone();
+        not_a_snapshot[0] = b'X';
+        assert!(matches!(
+            SpacialEnsemble::<EmptyNode, Pcg64>::load_snapshot(not_a_snapshot.as_slice()),
+            Err(SnapshotError::NotASnapshot)
+        ));
+        let mut newer = buf.clone();
+        newer[8] = 2;
+        assert!(matches!(
+            SpacialEnsemble::<EmptyNode, Pcg64>::load_snapshot(newer.as_slice()),
+            Err(SnapshotError::UnsupportedFormatVersion{found: 2, ..})
+        ));
+
+        // corrupt CSR headers must not panic
+        let read_csr = |words: &[u64], extra_bytes: usize| {
+            let mut buf = Vec::new();
+            let mut writer = SnapshotWriter::new(&mut buf, "Test", 1).unwrap();
+            let mut csr = Vec::new();
+            for &word in words {
+                write_u64(&mut csr, word).unwrap();
+            }
+            csr.resize(csr.len() + extra_bytes, 0);
+            writer.raw_section(&csr).unwrap();
+            SnapshotReader::new(buf.as_slice(), "Test", 1)
+                .unwrap()
+                .graph::<EmptyNode, NodeContainer<EmptyNode>>()
+        };
+        for (words, extra_bytes) in [
+            (vec![1 << 61, 0], 0),
+            (vec![u64::MAX, 0], 0),
+            (vec![3, 0, 0], 0),
+            (vec![1, 0, 0], 3),
+            (vec![1, 0, u64::MAX], 0),
+        ] {
+            assert!(matches!(read_csr(&words, extra_bytes), Err(SnapshotError::Corrupt(_))));
+        }
+    }
+}
@@ -4,6 +4,12 @@ use super::*;
 use crate::{Node, AdjContainer, Dot};
 use rand_distr::Poisson;
 use std::io::Write;
+#[cfg(feature = "serde_support")]
+use {
+    crate::snapshot::*,
+    serde::{Serialize, de::DeserializeOwned},
+    std::io::Read
+};
 
 pub struct DogEnsemble<T, R>
 {
@@ -198,4 +204,27 @@ where R: Rng
             Some((x,y))
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "serde_support")]
+impl<T, R> Snapshot for DogEnsemble<T, R>
+where T: Node + DeserializeOwned,
+      R: Serialize + DeserializeOwned
+{
+    fn save_snapshot<W: Write>(&self, writer: W) -> Result<(), SnapshotError>
+    {
+        let mut snapshot = SnapshotWriter::new(writer, "DogEnsemble", 1)?;
+        snapshot.graph(&self.graph)?;
+        snapshot.section(&(self.kappa, self.tau, self.lambda))?;
+        snapshot.section(&self.rng)
+    }
+
+    fn load_snapshot<Re: Read>(reader: Re) -> Result<Self, SnapshotError>
+    {
+        let mut snapshot = SnapshotReader::new(reader, "DogEnsemble", 1)?;
+        let graph = snapshot.graph()?;
+        let (kappa, tau, lambda) = snapshot.section()?;
+        let rng = snapshot.section()?;
+        Ok(Self{graph, rng, kappa, tau, lambda})
+    }
+}
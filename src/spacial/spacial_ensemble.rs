@@ -2,7 +2,8 @@ use {
     crate::{
         *, 
         spacial::*, 
-        er_c::draw_two_from_range
+        er_c::draw_two_from_range,
+        connectivity::EdgeChanges
     },
     rand::Rng,
     std::{
@@ -13,6 +14,13 @@ use {
 
 #[cfg(feature = "serde_support")]
 use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde_support")]
+use {
+    crate::snapshot::*,
+    crate::trajectory::RedoStep,
+    serde::de::DeserializeOwned,
+    std::io::Read
+};
 
 
 /// # Implements a special Ensemble
@@ -174,6 +182,16 @@ pub enum SpacialStep {
     Error,
 }
 
+impl EdgeChanges for SpacialStep {
+    fn edge_changes(&self, added: &mut Vec<(usize, usize)>, removed: &mut Vec<(usize, usize)>) {
+        match self {
+            Self::AddedEdge(edge) => added.push(*edge),
+            Self::RemovedEdge(edge) => removed.push(*edge),
+            Self::Nothing | Self::Error => {},
+        }
+    }
+}
+
 impl<T, R> MarkovChain<SpacialStep, SpacialStep> for 
     SpacialEnsemble<T, R>
 where 
@@ -279,6 +297,46 @@ where T: Node
     }
 }
 
+#[cfg(feature = "serde_support")]
+impl<T, R> Snapshot for SpacialEnsemble<T, R>
+where T: Node + DeserializeOwned,
+      R: Serialize + DeserializeOwned
+{
+    fn save_snapshot<W: Write>(&self, writer: W) -> Result<(), SnapshotError>
+    {
+        let mut snapshot = SnapshotWriter::new(writer, "SpacialEnsemble", 1)?;
+        snapshot.graph(&self.graph)?;
+        snapshot.section(&(self.f, self.alpha, self.sqrt_n_pi))?;
+        snapshot.section(&self.rng)
+    }
+
+    fn load_snapshot<Re: Read>(reader: Re) -> Result<Self, SnapshotError>
+    {
+        let mut snapshot = SnapshotReader::new(reader, "SpacialEnsemble", 1)?;
+        let graph = snapshot.graph()?;
+        let (f, alpha, sqrt_n_pi) = snapshot.section()?;
+        let rng = snapshot.section()?;
+        Ok(Self{graph, rng, f, alpha, sqrt_n_pi})
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<T, R> RedoStep<SpacialStep> for SpacialEnsemble<T, R>
+where T: Node
+{
+    fn redo_step(&mut self, step: &SpacialStep) -> bool {
+        let n = self.graph.vertex_count();
+        match *step {
+            SpacialStep::AddedEdge(edge) => crate::trajectory::valid_pair(n, edge)
+                && self.graph.add_edge(edge.0, edge.1).is_ok(),
+            SpacialStep::RemovedEdge(edge) => crate::trajectory::valid_pair(n, edge)
+                && self.graph.remove_edge(edge.0, edge.1).is_ok(),
+            SpacialStep::Nothing |
+            SpacialStep::Error => true,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,6 +120,22 @@ impl<T: Node + SerdeStateConform> AdjContainer<T> for SpacialNodeContainer<T> {
     }
 }
 
+#[cfg(feature = "serde_support")]
+impl<T> crate::snapshot::SnapshotContainer<T> for SpacialNodeContainer<T>
+where T: Node + SerdeStateConform
+{
+    /// coordinates
+    type Extra = (f64, f64);
+
+    fn snapshot_extra(&self) -> Self::Extra {
+        (self.x, self.y)
+    }
+
+    fn from_snapshot(id: usize, node: T, adj: Vec<usize>, (x, y): Self::Extra) -> Option<Self> {
+        Some(Self{adj, id, x, y, node})
+    }
+}
+
 impl<T> SpacialNodeContainer<T> {
 
     fn swap_remove_element(&mut self, elem: usize) {
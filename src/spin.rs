@@ -0,0 +1,617 @@
+//! # Spin models
+//! Ising and q-state Potts models, where the spins are stored in the
+//! additional data `T` of the vertices.
+//!
+//! * implement [`IsingNode`] or [`PottsNode`] for your node, or use
+//!   [`IsingSpin`] and [`PottsSpin`]
+//! * works for every [`GenericGraph`], independent of the `AdjContainer`.
+//!   To use it on an ensemble, call, e.g., `ensemble.graph()`
+//! * single-spin updates: [Metropolis](Ising::metropolis_sweep) and [heat-bath](Ising::heat_bath_sweep)
+//! * cluster updates: [Wolff](Ising::wolff_step)
+//!
+//! # Example
+//! ```
+//! use net_ensembles::{Graph, rand::SeedableRng};
+//! use net_ensembles::spin::{Ising, IsingSpin};
+//! use rand_pcg::Pcg64;
+//!
+//! let mut rng = Pcg64::seed_from_u64(2389);
+//! let mut graph = Graph::<IsingSpin>::complete_graph(20);
+//! let ising = Ising::new(1.0, 0.0);
+//!
+//! // all spins are up initially
+//! assert_eq!(ising.energy(&graph), -190.0);
+//! assert_eq!(Ising::magnetization(&graph), 20);
+//!
+//! for _ in 0..100 {
+//!     ising.metropolis_sweep(&mut graph, 0.01, &mut rng);
+//!     ising.wolff_step(&mut graph, 0.01, &mut rng);
+//! }
+//! assert!(Ising::magnetization(&graph).abs() <= 20);
+//! ```
+//! # Citation
+//! > U. Wolff,
+//! > "Collective Monte Carlo Updating for Spin Systems"
+//! > *Phys. Rev. Lett.*&nbsp;**62**, 361&nbsp;(1989),
+//! > DOI: [10.1103/PhysRevLett.62.361](https://doi.org/10.1103/PhysRevLett.62.361)
+use {
+    crate::{
+        traits::*,
+        GenericGraph
+    },
+    rand::Rng
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Serialize, Deserialize};
+
+/// # Node that contains an Ising spin
+pub trait IsingNode {
+    /// * returns the spin, either `1` or `-1`
+    fn spin(&self) -> i8;
+
+    /// * set the spin, `spin` is either `1` or `-1`
+    fn set_spin(&mut self, spin: i8);
+}
+
+/// # Node that contains a Potts spin
+pub trait PottsNode {
+    /// * returns the spin, i.e., a number in `0..q`
+    fn spin(&self) -> usize;
+
+    /// * set the spin to a number in `0..q`
+    fn set_spin(&mut self, spin: usize);
+}
+
+/// # Node containing only an Ising spin
+/// * initial spin is `1`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct IsingSpin(pub i8);
+
+impl Node for IsingSpin {
+    fn new_from_index(_: usize) -> Self {
+        IsingSpin(1)
+    }
+}
+
+impl IsingNode for IsingSpin {
+    fn spin(&self) -> i8 {
+        self.0
+    }
+
+    fn set_spin(&mut self, spin: i8) {
+        self.0 = spin;
+    }
+}
+
+/// # Node containing only a Potts spin
+/// * initial spin is `0`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct PottsSpin(pub usize);
+
+impl Node for PottsSpin {
+    fn new_from_index(_: usize) -> Self {
+        PottsSpin(0)
+    }
+}
+
+impl PottsNode for PottsSpin {
+    fn spin(&self) -> usize {
+        self.0
+    }
+
+    fn set_spin(&mut self, spin: usize) {
+        self.0 = spin;
+    }
+}
+
+/// grow a cluster of vertices with equal spin, starting at `seed`.
+/// Each edge between vertices of equal spin is added with probability `prob`
+fn wolff_cluster<T, A, R, F>(
+    graph: &GenericGraph<T, A>,
+    seed: usize,
+    prob: f64,
+    rng: &mut R,
+    same_spin: F
+) -> Vec<usize>
+where T: Node,
+      A: AdjContainer<T>,
+      R: Rng,
+      F: Fn(&T, &T) -> bool
+{
+    let mut in_cluster = vec![false; graph.vertex_count()];
+    in_cluster[seed] = true;
+    let mut cluster = vec![seed];
+    let mut stack = vec![seed];
+    while let Some(index) = stack.pop() {
+        let spin = graph.at(index);
+        for &neighbor in graph.container(index).neighbors() {
+            if !in_cluster[neighbor]
+                && same_spin(spin, graph.at(neighbor))
+                && rng.gen::<f64>() < prob
+            {
+                in_cluster[neighbor] = true;
+                cluster.push(neighbor);
+                stack.push(neighbor);
+            }
+        }
+    }
+    cluster
+}
+
+/// # Ising model
+/// * Hamiltonian `H = -J sum_<ij> s_i s_j - h sum_i s_i`,
+///   where the first sum runs over all edges
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Ising {
+    coupling: f64,
+    field: f64,
+}
+
+impl Ising {
+    /// # Create Ising model
+    /// * `coupling`: `J`, positive for ferromagnetic coupling
+    /// * `field`: external field `h`
+    pub fn new(coupling: f64, field: f64) -> Self
+    {
+        Self{
+            coupling,
+            field,
+        }
+    }
+
+    /// # Coupling constant `J`
+    pub fn coupling(&self) -> f64
+    {
+        self.coupling
+    }
+
+    /// # External field `h`
+    pub fn field(&self) -> f64
+    {
+        self.field
+    }
+
+    fn local_field<T, A>(&self, graph: &GenericGraph<T, A>, index: usize) -> f64
+    where T: Node + IsingNode,
+          A: AdjContainer<T>
+    {
+        let sum: i64 = graph.container(index)
+            .neighbors()
+            .map(|&neighbor| graph.at(neighbor).spin() as i64)
+            .sum();
+        self.coupling * sum as f64 + self.field
+    }
+
+    /// # Energy of the current configuration
+    pub fn energy<T, A>(&self, graph: &GenericGraph<T, A>) -> f64
+    where T: Node + IsingNode,
+          A: AdjContainer<T>
+    {
+        let mut bonds = 0;
+        let mut spins = 0;
+        for (index, container) in graph.container_iter().enumerate() {
+            let spin = container.contained().spin() as i64;
+            spins += spin;
+            bonds += container.neighbors()
+                .filter(|&&neighbor| neighbor > index)
+                .map(|&neighbor| spin * graph.at(neighbor).spin() as i64)
+                .sum::<i64>();
+        }
+        -self.coupling * bonds as f64 - self.field * spins as f64
+    }
+
+    /// # Magnetization
+    /// * sum of all spins
+    pub fn magnetization<T, A>(graph: &GenericGraph<T, A>) -> i64
+    where T: Node + IsingNode,
+          A: AdjContainer<T>
+    {
+        graph.contained_iter()
+            .map(|node| node.spin() as i64)
+            .sum()
+    }
+
+    /// # Metropolis sweep
+    /// * `vertex_count` times: choose a vertex uniformly at random and propose to flip its spin.
+    ///   Accept with probability `min(1, exp(-beta * delta_E))`
+    /// * returns number of accepted flips
+    pub fn metropolis_sweep<T, A, R>(&self, graph: &mut GenericGraph<T, A>, beta: f64, rng: &mut R) -> usize
+    where T: Node + IsingNode,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        let n = graph.vertex_count();
+        let mut accepted = 0;
+        for _ in 0..n {
+            let index = rng.gen_range(0..n);
+            let spin = graph.at(index).spin();
+            let delta_e = 2.0 * spin as f64 * self.local_field(graph, index);
+            if delta_e <= 0.0 || rng.gen::<f64>() < (-beta * delta_e).exp() {
+                graph.at_mut(index).set_spin(-spin);
+                accepted += 1;
+            }
+        }
+        accepted
+    }
+
+    /// # Heat-bath sweep
+    /// * `vertex_count` times: choose a vertex uniformly at random and draw its new
+    ///   spin from the conditional Boltzmann distribution, given its neighbors
+    /// * `beta = f64::INFINITY` is the zero temperature limit, i.e., ties are broken uniformly at random
+    pub fn heat_bath_sweep<T, A, R>(&self, graph: &mut GenericGraph<T, A>, beta: f64, rng: &mut R)
+    where T: Node + IsingNode,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        let n = graph.vertex_count();
+        for _ in 0..n {
+            let index = rng.gen_range(0..n);
+            let field = self.local_field(graph, index);
+            // zero field: both spins are equally likely, also for beta = infinity
+            let prob_up = if field == 0.0 {
+                0.5
+            } else {
+                1.0 / (1.0 + (-2.0 * beta * field).exp())
+            };
+            let spin = if rng.gen::<f64>() < prob_up {
+                1
+            } else {
+                -1
+            };
+            graph.at_mut(index).set_spin(spin);
+        }
+    }
+
+    /// # Wolff cluster update
+    /// * grows a cluster of equal spins from a random vertex and flips it
+    /// * for `h != 0` the flip is accepted with probability `min(1, exp(-beta * delta_E_h))`,
+    ///   where `delta_E_h` is the change of the field energy
+    /// * returns the number of flipped spins, i.e., `0` if the flip was rejected
+    /// * **panics** if `J < 0`, the Wolff algorithm requires ferromagnetic coupling
+    pub fn wolff_step<T, A, R>(&self, graph: &mut GenericGraph<T, A>, beta: f64, rng: &mut R) -> usize
+    where T: Node + IsingNode,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        assert!(self.coupling >= 0.0, "Wolff algorithm requires ferromagnetic coupling");
+        if graph.vertex_count() == 0 {
+            return 0;
+        }
+        let seed = rng.gen_range(0..graph.vertex_count());
+        let prob = 1.0 - (-2.0 * beta * self.coupling).exp();
+        let cluster = wolff_cluster(
+            graph,
+            seed,
+            prob,
+            rng,
+            |a, b| a.spin() == b.spin()
+        );
+
+        let spin = graph.at(seed).spin();
+        let delta_e = 2.0 * self.field * spin as f64 * cluster.len() as f64;
+        if delta_e > 0.0 && rng.gen::<f64>() >= (-beta * delta_e).exp() {
+            return 0;
+        }
+        for &index in cluster.iter() {
+            graph.at_mut(index).set_spin(-spin);
+        }
+        cluster.len()
+    }
+}
+
+/// # q-state Potts model
+/// * Hamiltonian `H = -J sum_<ij> delta(s_i, s_j)`,
+///   where the sum runs over all edges
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Potts {
+    q: usize,
+    coupling: f64,
+}
+
+impl Potts {
+    /// # Create Potts model
+    /// * `q`: number of states
+    /// * `coupling`: `J`, positive for ferromagnetic coupling
+    /// * **panics** if `q < 2`
+    pub fn new(q: usize, coupling: f64) -> Self
+    {
+        assert!(q >= 2, "Potts model requires at least 2 states");
+        Self{
+            q,
+            coupling,
+        }
+    }
+
+    /// # Number of states `q`
+    pub fn q(&self) -> usize
+    {
+        self.q
+    }
+
+    /// # Coupling constant `J`
+    pub fn coupling(&self) -> f64
+    {
+        self.coupling
+    }
+
+    /// number of neighbors with spin `spin`
+    fn equal_neighbors<T, A>(graph: &GenericGraph<T, A>, index: usize, spin: usize) -> usize
+    where T: Node + PottsNode,
+          A: AdjContainer<T>
+    {
+        graph.container(index)
+            .neighbors()
+            .filter(|&&neighbor| graph.at(neighbor).spin() == spin)
+            .count()
+    }
+
+    /// draw a spin uniformly from all states except `spin`
+    fn other_spin<R: Rng>(&self, spin: usize, rng: &mut R) -> usize
+    {
+        let new_spin = rng.gen_range(0..self.q - 1);
+        if new_spin >= spin {
+            new_spin + 1
+        } else {
+            new_spin
+        }
+    }
+
+    /// # Energy of the current configuration
+    pub fn energy<T, A>(&self, graph: &GenericGraph<T, A>) -> f64
+    where T: Node + PottsNode,
+          A: AdjContainer<T>
+    {
+        let mut equal = 0_usize;
+        for (index, container) in graph.container_iter().enumerate() {
+            let spin = container.contained().spin();
+            equal += container.neighbors()
+                .filter(|&&neighbor| neighbor > index && graph.at(neighbor).spin() == spin)
+                .count();
+        }
+        -self.coupling * equal as f64
+    }
+
+    /// # Number of vertices in each state
+    /// * `spin_counts()[k]` is the number of vertices with spin `k`
+    pub fn spin_counts<T, A>(&self, graph: &GenericGraph<T, A>) -> Vec<usize>
+    where T: Node + PottsNode,
+          A: AdjContainer<T>
+    {
+        let mut counts = vec![0; self.q];
+        for node in graph.contained_iter() {
+            counts[node.spin()] += 1;
+        }
+        counts
+    }
+
+    /// # Magnetization
+    /// * order parameter `(q * N_max / N - 1) / (q - 1)`, where `N_max` is the number of
+    ///   vertices in the most common state
+    /// * `1` if all spins are equal, `0` if all states are equally common
+    pub fn magnetization<T, A>(&self, graph: &GenericGraph<T, A>) -> f64
+    where T: Node + PottsNode,
+          A: AdjContainer<T>
+    {
+        let max = self.spin_counts(graph)
+            .into_iter()
+            .max()
+            .unwrap();
+        let q = self.q as f64;
+        (q * max as f64 / graph.vertex_count() as f64 - 1.0) / (q - 1.0)
+    }
+
+    /// # Metropolis sweep
+    /// * `vertex_count` times: choose a vertex uniformly at random and propose a new spin,
+    ///   drawn uniformly from the other `q - 1` states.
+    ///   Accept with probability `min(1, exp(-beta * delta_E))`
+    /// * returns number of accepted changes
+    pub fn metropolis_sweep<T, A, R>(&self, graph: &mut GenericGraph<T, A>, beta: f64, rng: &mut R) -> usize
+    where T: Node + PottsNode,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        let n = graph.vertex_count();
+        let mut accepted = 0;
+        for _ in 0..n {
+            let index = rng.gen_range(0..n);
+            let spin = graph.at(index).spin();
+            let new_spin = self.other_spin(spin, rng);
+            let old_equal = Self::equal_neighbors(graph, index, spin) as f64;
+            let new_equal = Self::equal_neighbors(graph, index, new_spin) as f64;
+            let delta_e = -self.coupling * (new_equal - old_equal);
+            if delta_e <= 0.0 || rng.gen::<f64>() < (-beta * delta_e).exp() {
+                graph.at_mut(index).set_spin(new_spin);
+                accepted += 1;
+            }
+        }
+        accepted
+    }
+
+    /// # Heat-bath sweep
+    /// * `vertex_count` times: choose a vertex uniformly at random and draw its new
+    ///   spin from the conditional Boltzmann distribution, given its neighbors
+    /// * `beta = f64::INFINITY` is the zero temperature limit, i.e., ties are broken uniformly at random
+    pub fn heat_bath_sweep<T, A, R>(&self, graph: &mut GenericGraph<T, A>, beta: f64, rng: &mut R)
+    where T: Node + PottsNode,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        let n = graph.vertex_count();
+        let mut counts = vec![0_usize; self.q];
+        let mut weights = vec![0.0; self.q];
+        for _ in 0..n {
+            let index = rng.gen_range(0..n);
+            counts.iter_mut().for_each(|c| *c = 0);
+            for &neighbor in graph.container(index).neighbors() {
+                counts[graph.at(neighbor).spin()] += 1;
+            }
+            // subtract the count of the most likely state for numerical stability.
+            // That state has weight 1, also for beta = infinity
+            let reference = if self.coupling >= 0.0 {
+                *counts.iter().max().unwrap()
+            } else {
+                *counts.iter().min().unwrap()
+            };
+            let mut sum = 0.0;
+            for (weight, &count) in weights.iter_mut().zip(counts.iter()) {
+                *weight = if count == reference || self.coupling == 0.0 {
+                    1.0
+                } else {
+                    (beta * self.coupling * (count as f64 - reference as f64)).exp()
+                };
+                sum += *weight;
+            }
+            let mut which = rng.gen::<f64>() * sum;
+            let mut new_spin = self.q - 1;
+            for (spin, &weight) in weights.iter().enumerate() {
+                if which < weight {
+                    new_spin = spin;
+                    break;
+                }
+                which -= weight;
+            }
+            graph.at_mut(index).set_spin(new_spin);
+        }
+    }
+
+    /// # Wolff cluster update
+    /// * grows a cluster of equal spins from a random vertex and changes all its spins
+    ///   to a new state, drawn uniformly from the other `q - 1` states
+    /// * returns the size of the cluster
+    /// * **panics** if `J < 0`, the Wolff algorithm requires ferromagnetic coupling
+    pub fn wolff_step<T, A, R>(&self, graph: &mut GenericGraph<T, A>, beta: f64, rng: &mut R) -> usize
+    where T: Node + PottsNode,
+          A: AdjContainer<T>,
+          R: Rng
+    {
+        assert!(self.coupling >= 0.0, "Wolff algorithm requires ferromagnetic coupling");
+        if graph.vertex_count() == 0 {
+            return 0;
+        }
+        let seed = rng.gen_range(0..graph.vertex_count());
+        let prob = 1.0 - (-beta * self.coupling).exp();
+        let cluster = wolff_cluster(
+            graph,
+            seed,
+            prob,
+            rng,
+            |a, b| a.spin() == b.spin()
+        );
+        let new_spin = self.other_spin(graph.at(seed).spin(), rng);
+        for &index in cluster.iter() {
+            graph.at_mut(index).set_spin(new_spin);
+        }
+        cluster.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, sw::SwEnsemble};
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    fn ring<T: Node>(n: usize) -> Graph<T>
+    {
+        let mut graph = Graph::new(n);
+        for i in 0..n {
+            graph.add_edge(i, (i + 1) % n).unwrap();
+        }
+        graph
+    }
+
+    #[test]
+    fn ising_updates()
+    {
+        let mut rng = Pcg64::seed_from_u64(2390);
+        let mut graph = ring::<IsingSpin>(10);
+        let ising = Ising::new(1.0, 0.5);
+        assert_eq!(ising.energy(&graph), -15.0);
+        graph.at_mut(0).set_spin(-1);
+        assert_eq!(ising.energy(&graph), -10.0);
+        assert_eq!(Ising::magnetization(&graph), 8);
+
+        // infinite temperature, every flip is accepted
+        assert_eq!(ising.metropolis_sweep(&mut graph, 0.0, &mut rng), 10);
+
+        // zero temperature wolff, whole connected component is flipped
+        let ising = Ising::new(1.0, 0.0);
+        let mut graph = ring::<IsingSpin>(10);
+        assert_eq!(ising.wolff_step(&mut graph, f64::INFINITY, &mut rng), 10);
+        assert_eq!(Ising::magnetization(&graph), -10);
+
+        // zero temperature heat bath, strong field
+        let ising = Ising::new(1.0, 5.0);
+        for _ in 0..20 {
+            ising.heat_bath_sweep(&mut graph, f64::INFINITY, &mut rng);
+        }
+        assert_eq!(Ising::magnetization(&graph), 10);
+
+        // zero temperature heat bath, zero local field: both spins are equally likely
+        let ising = Ising::new(1.0, 0.0);
+        let mut graph = Graph::<IsingSpin>::new(100);
+        for i in 0..100 {
+            graph.at_mut(i).set_spin(-1);
+        }
+        for _ in 0..10 {
+            ising.heat_bath_sweep(&mut graph, f64::INFINITY, &mut rng);
+        }
+        assert!(Ising::magnetization(&graph).abs() < 100);
+    }
+
+    #[test]
+    fn potts_updates()
+    {
+        let mut rng = Pcg64::seed_from_u64(239);
+        let potts = Potts::new(3, 1.0);
+        let mut graph = ring::<PottsSpin>(9);
+        assert_eq!(potts.energy(&graph), -9.0);
+        assert_eq!(potts.magnetization(&graph), 1.0);
+
+        assert_eq!(potts.wolff_step(&mut graph, f64::INFINITY, &mut rng), 9);
+        assert_eq!(potts.magnetization(&graph), 1.0);
+        assert_eq!(potts.spin_counts(&graph)[0], 0);
+
+        for i in 0..9 {
+            graph.at_mut(i).set_spin(i % 3);
+        }
+        assert_eq!(potts.energy(&graph), 0.0);
+        assert_eq!(potts.magnetization(&graph), 0.0);
+        assert_eq!(potts.metropolis_sweep(&mut graph, 0.0, &mut rng), 9);
+
+        for _ in 0..10 {
+            potts.heat_bath_sweep(&mut graph, 1.0, &mut rng);
+        }
+        assert!(potts.spin_counts(&graph).iter().sum::<usize>() == 9);
+
+        // zero temperature heat bath keeps the ground state
+        for i in 0..9 {
+            graph.at_mut(i).set_spin(0);
+        }
+        for _ in 0..10 {
+            potts.heat_bath_sweep(&mut graph, f64::INFINITY, &mut rng);
+        }
+        assert_eq!(potts.spin_counts(&graph)[0], 9);
+    }
+
+    #[test]
+    fn works_with_sw_container()
+    {
+        let rng = Pcg64::seed_from_u64(23);
+        let e = SwEnsemble::<IsingSpin, _>::new(20, 0.1, rng);
+        let mut rng = Pcg64::seed_from_u64(2);
+        let ising = Ising::new(1.0, 0.0);
+        let mut graph = e.graph().clone();
+        assert_eq!(ising.energy(&graph), -(graph.edge_count() as f64));
+        assert_eq!(ising.wolff_step(&mut graph, f64::INFINITY, &mut rng), 20);
+        assert_eq!(Ising::magnetization(&graph), -20);
+        ising.metropolis_sweep(&mut graph, 0.3, &mut rng);
+        assert!(ising.energy(&graph) >= -(graph.edge_count() as f64));
+    }
+}
@@ -14,12 +14,19 @@
 //! > D. J. Watts and S. H. Strogatz, "Collective dynamics on 'small-world' networks,"
 //!   Nature **393**, 440-442 (1998), DOI:&nbsp;[10.1038/30918](https://doi.org/10.1038/30918)
 use {
-    crate::{traits::*, sw_graph::*, iter::*},
+    crate::{traits::*, sw_graph::*, iter::*, connectivity::EdgeChanges},
     std::{borrow::Borrow, io::Write, num::*}
 };
 
 #[cfg(feature = "serde_support")]
 use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde_support")]
+use {
+    crate::snapshot::*,
+    crate::trajectory::RedoStep,
+    serde::de::DeserializeOwned,
+    std::io::Read
+};
 
 const ROOT_EDGES_PER_VERTEX: usize = 2;
 
@@ -79,6 +86,19 @@ impl SwChangeState {
     }
 }
 
+impl EdgeChanges for SwChangeState {
+    fn edge_changes(&self, added: &mut Vec<(usize, usize)>, removed: &mut Vec<(usize, usize)>) {
+        match *self {
+            SwChangeState::Rewire(root, old_to, new_to) |
+            SwChangeState::Reset (root, old_to, new_to) if old_to != new_to => {
+                removed.push((root, old_to));
+                added.push((root, new_to));
+            },
+            _ => {},
+        }
+    }
+}
+
 /// # Implements small-world graph ensemble
 /// * for more details look at [documentation](index.html) of module `sw`
 /// ## Sampling
@@ -548,6 +568,56 @@ where   T: Node + SerdeStateConform,
 }
 
 
+#[cfg(feature = "serde_support")]
+impl<T, R> Snapshot for SwEnsemble<T, R>
+where T: Node + DeserializeOwned,
+      R: Serialize + DeserializeOwned
+{
+    fn save_snapshot<W: Write>(&self, writer: W) -> Result<(), SnapshotError>
+    {
+        let mut snapshot = SnapshotWriter::new(writer, "SwEnsemble", 1)?;
+        snapshot.graph(&self.graph)?;
+        snapshot.section(&self.r_prob)?;
+        snapshot.section(&self.rng)
+    }
+
+    fn load_snapshot<Re: Read>(reader: Re) -> Result<Self, SnapshotError>
+    {
+        let mut snapshot = SnapshotReader::new(reader, "SwEnsemble", 1)?;
+        let graph = snapshot.graph()?;
+        let r_prob = snapshot.section()?;
+        let rng = snapshot.section()?;
+        Ok(Self{graph, r_prob, rng})
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<T, R> RedoStep<SwChangeState> for SwEnsemble<T, R>
+where T: Node
+{
+    fn redo_step(&mut self, step: &SwChangeState) -> bool {
+        match *step {
+            SwChangeState::Rewire(root, old_to, new_to) |
+            SwChangeState::Reset (root, old_to, new_to) => {
+                let n = self.graph.vertex_count();
+                if root >= n || old_to >= n || new_to >= n
+                    || root == old_to || root == new_to || old_to == new_to
+                {
+                    return false;
+                }
+                matches!(
+                    self.graph.rewire_edge(root, old_to, new_to),
+                    SwChangeState::Rewire(..) | SwChangeState::Reset(..)
+                )
+            },
+            SwChangeState::Nothing |
+            SwChangeState::BlockedByExistingEdge |
+            SwChangeState::InvalidAdjecency |
+            SwChangeState::GError(_) => true
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
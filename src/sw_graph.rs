@@ -298,6 +298,32 @@ where T: Node + SerdeStateConform
         }
 }
 
+#[cfg(feature = "serde_support")]
+impl<T> crate::snapshot::SnapshotContainer<T> for SwContainer<T>
+where T: Node + SerdeStateConform
+{
+    /// where the edges originally pointed to
+    type Extra = Vec<Option<usize>>;
+
+    fn snapshot_extra(&self) -> Self::Extra {
+        self.adj
+            .iter()
+            .map(|edge| edge.originally_to)
+            .collect()
+    }
+
+    fn from_snapshot(id: usize, node: T, adj: Vec<usize>, extra: Self::Extra) -> Option<Self> {
+        if adj.len() != extra.len() {
+            return None;
+        }
+        let adj = adj.into_iter()
+            .zip(extra)
+            .map(|(to, originally_to)| SwEdge{to, originally_to})
+            .collect();
+        Some(Self{id, adj, node})
+    }
+}
+
 impl<T: Node + SerdeStateConform> SwContainer<T> {
 
      /// returns iterator over indices of neighbors
@@ -545,6 +571,23 @@ where T: Node + SerdeStateConform {
             ).count()
     }
 
+    /// # Iterate over the root edges
+    /// * yields `(root, edge)`, where `root` is the index of the vertex the edge is rooted at
+    /// * every edge has exactly one root, i.e., every edge of the graph is visited exactly once
+    /// * use [`edges`](#method.edges) if you only need the indices of the vertices
+    pub fn root_edges(&'_ self) -> impl Iterator<Item=(usize, &'_ SwEdge)> + '_
+    {
+        self.vertices
+            .iter()
+            .enumerate()
+            .flat_map(
+                |(index, c)|
+                c.iter_raw_edges()
+                    .filter(|e| e.is_root())
+                    .map(move |e| (index, e))
+            )
+    }
+
     /// # Fraction of long ranging edges in the Graph?
     /// * A long ranging edge is defined as an edge, where [is_at_root](`crate::sw_graph::SwEdge::is_at_root`)
     /// returns false, i.e., which is not in ist original ring configuration
@@ -0,0 +1,95 @@
+//! Markov chains of the ensembles, shared by the tests of the incremental observables
+use {
+    crate::{
+        traits::*,
+        GenericGraph,
+        EmptyNode,
+        ErEnsembleC,
+        ErEnsembleM,
+        ConfigurationModel,
+        SwEnsemble,
+        spacial::SpacialEnsemble,
+        connectivity::EdgeChanges
+    },
+    rand::SeedableRng,
+    rand_pcg::Pcg64
+};
+
+/// Observable, which is tracked during the chains and compared to the graph
+pub(crate) trait TrackedObservable {
+    /// create from the initial graph of the chain
+    fn new_tracked<T, A>(graph: &GenericGraph<T, A>) -> Self
+    where A: AdjContainer<T>;
+
+    /// `step` was performed, or undone, if `undo` is true
+    fn track<T, A, S>(&mut self, graph: &GenericGraph<T, A>, step: &S, undo: bool)
+    where A: AdjContainer<T>,
+          S: EdgeChanges;
+
+    /// panics, if the tracked values do not match `graph`
+    fn assert_tracked<T, A>(&mut self, graph: &GenericGraph<T, A>)
+    where T: Node,
+          A: AdjContainer<T>;
+}
+
+/// `rounds` rounds of 1 to 7 steps. The steps of every third round are undone quietly,
+/// the steps of every other third round are undone with `undo_step`
+fn check_chain<E, S, Res, T, A, O>(ensemble: &mut E, rounds: usize)
+where E: MarkovChain<S, Res> + AsRef<GenericGraph<T, A>>,
+      S: EdgeChanges,
+      T: Node,
+      A: AdjContainer<T>,
+      O: TrackedObservable
+{
+    let mut observable = O::new_tracked(ensemble.as_ref());
+    observable.assert_tracked(ensemble.as_ref());
+    let mut steps = Vec::new();
+    for round in 0..rounds {
+        steps.clear();
+        for _ in 0..1 + round % 7 {
+            let step = ensemble.m_step();
+            observable.track(ensemble.as_ref(), &step, false);
+            steps.push(step);
+        }
+        observable.assert_tracked(ensemble.as_ref());
+        if round % 3 == 2 {
+            continue;
+        }
+        for step in steps.iter().rev() {
+            if round % 3 == 0 {
+                ensemble.undo_step_quiet(step);
+            } else {
+                ensemble.undo_step(step);
+            }
+            observable.track(ensemble.as_ref(), step, true);
+        }
+        observable.assert_tracked(ensemble.as_ref());
+    }
+}
+
+/// runs [`check_chain`] for `ErEnsembleC`, `ErEnsembleM`, `ConfigurationModel`,
+/// `SwEnsemble` and `SpacialEnsemble` with `n` vertices and a mean degree of about `mean_degree`
+pub(crate) fn check_chains<O>(n: usize, mean_degree: f64, rounds: usize, seed: u64)
+where O: TrackedObservable
+{
+    let mut er_c = ErEnsembleC::<EmptyNode, Pcg64>::new(n, mean_degree, Pcg64::seed_from_u64(seed));
+    check_chain::<_, _, _, _, _, O>(&mut er_c, rounds);
+
+    let m = (mean_degree * n as f64 / 2.0).round() as usize;
+    let mut er_m = ErEnsembleM::<EmptyNode, Pcg64>::new(n, m, Pcg64::seed_from_u64(seed + 1));
+    check_chain::<_, _, _, _, _, O>(&mut er_m, rounds);
+
+    // degrees of another Erdős-Rényi graph
+    let degrees = ErEnsembleC::<EmptyNode, Pcg64>::new(n, mean_degree, Pcg64::seed_from_u64(seed + 2));
+    let mut cm = ConfigurationModel::<EmptyNode, Pcg64>::from_generic_graph(
+        degrees.graph(),
+        Pcg64::seed_from_u64(seed + 3)
+    );
+    check_chain::<_, _, _, _, _, O>(&mut cm, rounds);
+
+    let mut sw = SwEnsemble::<EmptyNode, Pcg64>::new(n, 0.2, Pcg64::seed_from_u64(seed + 4));
+    check_chain::<_, _, _, _, _, O>(&mut sw, rounds);
+
+    let mut spacial = SpacialEnsemble::<EmptyNode, Pcg64>::new(n, Pcg64::seed_from_u64(seed + 5), mean_degree, 1.2);
+    check_chain::<_, _, _, _, _, O>(&mut spacial, rounds);
+}
@@ -15,6 +15,7 @@ pub use crate::sampling::traits::HasRng;
 pub use ensemble_traits::WithGraph;
 pub use ensemble_traits::GraphIterators;
 pub use ensemble_traits::GraphIteratorsMut;
+pub(crate) use ensemble_traits::with_ensemble_rng;
 
 pub use crate::sampling::traits::*;
 pub use crate::iter::IterWrapper;
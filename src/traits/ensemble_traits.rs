@@ -5,9 +5,25 @@ use crate::{
     GenericGraph,
     sw_graph::SwContainer,
     graph::NodeContainer,
+    indexed_graph::IndexedNodeContainer,
     generic_graph::{Dfs, DfsWithIndex, Bfs}
 };
 
+/// * temporarily takes the rng out of the ensemble, so that the ensemble
+///   and its rng can be borrowed at the same time
+/// * the rng is put back afterwards
+pub(crate) fn with_ensemble_rng<E, R, F, O>(ensemble: &mut E, f: F) -> O
+where R: rand::SeedableRng,
+      E: HasRng<R>,
+      F: FnOnce(&mut E, &mut R) -> O
+{
+    let mut rng = R::seed_from_u64(0);
+    ensemble.swap_rng(&mut rng);
+    let result = f(ensemble, &mut rng);
+    ensemble.swap_rng(&mut rng);
+    result
+}
+
 /// unify graph ensembles in a trait
 pub trait WithGraph<T, G> {
     /// * access additional information at index
@@ -183,6 +199,51 @@ where
     }
 }
 
+impl<T, E> GraphIterators<T, GenericGraph<T, IndexedNodeContainer<T>>, IndexedNodeContainer<T>> for E
+where
+    T: Node,
+    E: WithGraph<T, GenericGraph<T, IndexedNodeContainer<T>>>,
+{
+    fn contained_iter(&self) -> ContainedIter<'_, T, IndexedNodeContainer<T>>
+    {
+        self.graph().contained_iter()
+    }
+
+    fn contained_iter_neighbors(&self, index: usize) -> NContainedIter<'_, T, IndexedNodeContainer<T>, IterWrapper>
+    {
+        self.graph().contained_iter_neighbors(index)
+    }
+
+    fn container_iter(&self) -> core::slice::Iter<'_, IndexedNodeContainer<T>>
+    {
+        self.graph().container_iter()
+    }
+
+    fn container_iter_neighbors(&self, index: usize) -> NContainerIter<'_, T, IndexedNodeContainer<T>, IterWrapper>
+    {
+        self.graph().container_iter_neighbors(index)
+    }
+
+    fn contained_iter_neighbors_with_index(&self, index: usize) -> NIContainedIter<T, IndexedNodeContainer<T>> {
+        self.graph().contained_iter_neighbors_with_index(index)
+    }
+
+    fn dfs(&self, index: usize) -> Dfs<'_, T, IndexedNodeContainer<T>>
+    {
+        self.graph().dfs(index)
+    }
+
+    fn dfs_with_index(&self, index: usize) -> DfsWithIndex<'_, T, IndexedNodeContainer<T>>
+    {
+        self.graph().dfs_with_index(index)
+    }
+
+    fn bfs_index_depth(&self, index: usize) -> Bfs<'_, T, IndexedNodeContainer<T>>
+    {
+        self.graph().bfs_index_depth(index)
+    }
+}
+
 impl<T, E> GraphIterators<T, GenericGraph<T, SwContainer<T>>, SwContainer<T>> for E
 where
     T: Node,
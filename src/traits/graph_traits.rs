@@ -109,6 +109,31 @@ pub trait AdjContainer<T>
     unsafe fn push(&mut self, other: &mut Self)
         -> Result<(), GraphErrors>;
 
+    /// # What does it do?
+    /// Creates edge in `self` and `other`s adjecency Lists, without checking
+    /// if the edge exists already
+    /// # Why is it unsafe?
+    /// * same as `push`
+    /// * the edge must not exist yet, otherwise the adjecency lists contain duplicates
+    #[doc(hidden)]
+    unsafe fn push_unchecked(&mut self, other: &mut Self)
+    {
+        let _ = self.push(other);
+    }
+
+    /// # What does it do?
+    /// Replaces the adjecency list of `self` by `neighbors`, keeping their order.
+    /// Returns `false` and changes nothing, if the container does not support this
+    /// # Why is it unsafe?
+    /// * the adjecency lists of the neighbors are not changed
+    /// * only intended for copying all adjecency lists of a valid graph
+    #[doc(hidden)]
+    unsafe fn set_adj_unchecked<I>(&mut self, _neighbors: I) -> bool
+    where I: Iterator<Item=usize>
+    {
+        false
+    }
+
     /// # What does it do?
     /// Removes edge in `self` and `other`s adjecency Lists
     /// # Why is it unsafe?
@@ -0,0 +1,497 @@
+//! # Recording and replaying Markov chains
+//! Full history of a Markov chain without storing every graph, e.g., for debugging
+//! or to analyze the configurations of a long run afterwards.
+//!
+//! A [`TrajectoryRecorder`] writes a [snapshot](crate::snapshot) of the initial ensemble,
+//! followed by every Markov step and whether it was accepted.
+//! The steps are stored with variable length integers, i.e., usually only a few bytes per step.
+//!
+//! A [`TrajectoryReplayer`] restores the initial ensemble and applies the recorded steps again,
+//! see [`RedoStep`]. It can stop after any number of steps to rebuild the corresponding
+//! intermediate graph.
+//!
+//! Rejected steps are skipped during the replay, as they were undone in the original chain.
+//! The replayed graph therefore has exactly the same edges as the original graph,
+//! but, like after [`undo_step`](crate::MarkovChain::undo_step), the order of the
+//! adjacency lists may differ.
+//!
+//! # Example
+//! ```
+//! use net_ensembles::{ErEnsembleC, ErStepC, EmptyNode, MarkovChain, WithGraph, rand::SeedableRng};
+//! use net_ensembles::trajectory::{TrajectoryRecorder, TrajectoryReplayer};
+//! use rand_pcg::Pcg64;
+//!
+//! let rng = Pcg64::seed_from_u64(2789);
+//! let mut ensemble = ErEnsembleC::<EmptyNode, _>::new(100, 3.0, rng);
+//!
+//! let mut recorder = TrajectoryRecorder::new(Vec::new(), &ensemble).unwrap();
+//! let mut edge_counts = Vec::new();
+//! for i in 0..100 {
+//!     let step = ensemble.m_step();
+//!     // reject every third step
+//!     let accepted = i % 3 != 0;
+//!     if !accepted {
+//!         ensemble.undo_step_quiet(&step);
+//!     }
+//!     recorder.record(&step, accepted).unwrap();
+//!     edge_counts.push(ensemble.graph().edge_count());
+//! }
+//! let buf = recorder.into_inner().unwrap();
+//!
+//! let mut replayer: TrajectoryReplayer<_, ErEnsembleC<EmptyNode, Pcg64>, ErStepC>
+//!     = TrajectoryReplayer::new(buf.as_slice()).unwrap();
+//! // graph after 50 steps
+//! replayer.replay_until(50).unwrap();
+//! assert_eq!(replayer.ensemble().graph().edge_count(), edge_counts[49]);
+//! assert_eq!(replayer.replay_all().unwrap(), 100);
+//! ```
+use {
+    crate::snapshot::*,
+    bincode::Options,
+    serde::{Serialize, de::DeserializeOwned},
+    std::{
+        fmt,
+        error::Error,
+        io::{ErrorKind, Read, Write},
+        marker::PhantomData
+    }
+};
+
+/// Magic bytes at the start of every trajectory
+pub const TRAJECTORY_MAGIC: [u8; 8] = *b"NETENSTR";
+
+/// Version of the trajectory format
+pub const TRAJECTORY_FORMAT_VERSION: u32 = 1;
+
+/// # Apply recorded Markov steps again
+/// * implemented for all ensembles whose steps contain enough information to be repeated
+/// * used by the [`TrajectoryReplayer`]
+pub trait RedoStep<S> {
+    /// # Repeat a Markov step
+    /// * applies `step`, which was returned by `m_step`, to the ensemble again
+    /// * steps that did not change anything, e.g., errors, do nothing
+    /// * returns `false` if `step` does not fit to the current state of the ensemble.
+    ///   The ensemble is unchanged in this case
+    fn redo_step(&mut self, step: &S) -> bool;
+}
+
+/// `true` if `(i, j)` is a valid edge of a graph with `n` vertices
+pub(crate) fn valid_pair(n: usize, (i, j): (usize, usize)) -> bool
+{
+    i < n && j < n && i != j
+}
+
+/// # Errors of trajectories
+#[derive(Debug)]
+pub enum TrajectoryError {
+    /// ### Reading or writing failed
+    Io(std::io::Error),
+    /// ### Magic bytes do not match
+    /// The data is not a trajectory
+    NotATrajectory,
+    /// ### Trajectory format is newer than this crate
+    UnsupportedFormatVersion{
+        /// format version of the trajectory
+        found: u32,
+        /// newest format version supported
+        supported: u32,
+    },
+    /// ### The initial snapshot could not be written or restored
+    Snapshot(SnapshotError),
+    /// ### Data of the trajectory is inconsistent
+    Corrupt(String),
+    /// ### Recorded step does not fit to the replayed ensemble
+    StepMismatch{
+        /// number of steps replayed before
+        position: u64,
+    },
+    /// ### Replaying can only go forward
+    SeekBackwards{
+        /// number of steps replayed already
+        position: u64,
+        /// requested number of steps
+        target: u64,
+    },
+    /// ### Trajectory is shorter than requested
+    EndOfTrajectory{
+        /// number of recorded steps
+        length: u64,
+    },
+}
+
+impl fmt::Display for TrajectoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrajectoryError::Io(e) => write!(f, "IO error: {}", e),
+            TrajectoryError::NotATrajectory => write!(f, "not a trajectory"),
+            TrajectoryError::UnsupportedFormatVersion{found, supported} => write!(
+                f,
+                "trajectory format version {} is not supported, newest supported version is {}",
+                found,
+                supported
+            ),
+            TrajectoryError::Snapshot(e) => write!(f, "initial snapshot: {}", e),
+            TrajectoryError::Corrupt(message) => write!(f, "corrupt trajectory: {}", message),
+            TrajectoryError::StepMismatch{position} => write!(
+                f,
+                "step {} does not fit to the replayed ensemble",
+                position
+            ),
+            TrajectoryError::SeekBackwards{position, target} => write!(
+                f,
+                "cannot go back to step {}, already replayed {} steps",
+                target,
+                position
+            ),
+            TrajectoryError::EndOfTrajectory{length} => write!(
+                f,
+                "trajectory ended after {} steps",
+                length
+            ),
+        }
+    }
+}
+
+impl Error for TrajectoryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TrajectoryError::Io(e) => Some(e),
+            TrajectoryError::Snapshot(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for TrajectoryError {
+    fn from(e: std::io::Error) -> Self {
+        TrajectoryError::Io(e)
+    }
+}
+
+impl From<SnapshotError> for TrajectoryError {
+    fn from(e: SnapshotError) -> Self {
+        TrajectoryError::Snapshot(e)
+    }
+}
+
+impl From<bincode::Error> for TrajectoryError {
+    fn from(e: bincode::Error) -> Self {
+        match *e {
+            bincode::ErrorKind::Io(e) => TrajectoryError::Io(e),
+            e => TrajectoryError::Corrupt(e.to_string())
+        }
+    }
+}
+
+/// encoding of the steps, variable length integers
+fn step_options() -> impl Options
+{
+    bincode::DefaultOptions::new()
+}
+
+/// # Records a Markov chain
+/// * writes a snapshot of the initial ensemble on creation
+/// * every step is recorded together with the information, whether it was accepted
+/// * see [module documentation](crate::trajectory)
+pub struct TrajectoryRecorder<W, S> {
+    writer: W,
+    length: u64,
+    phantom: PhantomData<S>,
+}
+
+impl<W, S> TrajectoryRecorder<W, S>
+where W: Write,
+      S: Serialize
+{
+    /// # Start recording
+    /// * `ensemble` is the initial state of the Markov chain
+    pub fn new<E: Snapshot>(mut writer: W, ensemble: &E) -> Result<Self, TrajectoryError>
+    {
+        let mut snapshot = Vec::new();
+        ensemble.save_snapshot(&mut snapshot)?;
+        writer.write_all(&TRAJECTORY_MAGIC)?;
+        write_u32(&mut writer, TRAJECTORY_FORMAT_VERSION)?;
+        write_u64(&mut writer, snapshot.len() as u64)?;
+        writer.write_all(&snapshot)?;
+        Ok(Self{
+            writer,
+            length: 0,
+            phantom: PhantomData,
+        })
+    }
+
+    /// # Record a step
+    /// * `step` as returned by `m_step`
+    /// * `accepted` is `false`, if the step was undone afterwards
+    pub fn record(&mut self, step: &S, accepted: bool) -> Result<(), TrajectoryError>
+    {
+        self.writer.write_all(&[accepted as u8])?;
+        step_options().serialize_into(&mut self.writer, step)?;
+        self.length += 1;
+        Ok(())
+    }
+
+    /// # Number of recorded steps
+    pub fn len(&self) -> u64
+    {
+        self.length
+    }
+
+    /// # `true` if no steps were recorded yet
+    pub fn is_empty(&self) -> bool
+    {
+        self.length == 0
+    }
+
+    /// # Flush and return the writer
+    pub fn into_inner(mut self) -> Result<W, TrajectoryError>
+    {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// # A recorded step
+#[derive(Debug, Clone, Copy)]
+pub struct TrajectoryEntry<S> {
+    /// step as returned by `m_step`
+    pub step: S,
+    /// `false` if the step was undone in the original Markov chain
+    pub accepted: bool,
+}
+
+/// # Replays a recorded Markov chain
+/// * restores the initial ensemble from the trajectory
+/// * replaying only goes forward. To go back, create a new replayer
+/// * see [module documentation](crate::trajectory)
+pub struct TrajectoryReplayer<R, E, S> {
+    reader: R,
+    ensemble: E,
+    position: u64,
+    phantom: PhantomData<S>,
+}
+
+impl<R, E, S> TrajectoryReplayer<R, E, S>
+where R: Read,
+      E: Snapshot + RedoStep<S>,
+      S: DeserializeOwned
+{
+    /// # Start replaying
+    /// * reads the header and restores the initial ensemble
+    pub fn new(mut reader: R) -> Result<Self, TrajectoryError>
+    {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != TRAJECTORY_MAGIC {
+            return Err(TrajectoryError::NotATrajectory);
+        }
+        let format_version = read_u32(&mut reader)?;
+        if format_version > TRAJECTORY_FORMAT_VERSION {
+            return Err(TrajectoryError::UnsupportedFormatVersion{
+                found: format_version,
+                supported: TRAJECTORY_FORMAT_VERSION
+            });
+        }
+        let len = read_u64(&mut reader)?;
+        let snapshot = read_bytes(&mut reader, len)?;
+        let ensemble = E::load_snapshot(snapshot.as_slice())?;
+        Ok(Self{
+            reader,
+            ensemble,
+            position: 0,
+            phantom: PhantomData,
+        })
+    }
+
+    /// # Ensemble after the replayed steps
+    pub fn ensemble(&self) -> &E
+    {
+        &self.ensemble
+    }
+
+    /// # Return the ensemble after the replayed steps
+    pub fn into_ensemble(self) -> E
+    {
+        self.ensemble
+    }
+
+    /// # Number of replayed steps
+    pub fn position(&self) -> u64
+    {
+        self.position
+    }
+
+    /// # Replay the next step
+    /// * returns the recorded step
+    /// * `None` at the end of the trajectory
+    pub fn next_step(&mut self) -> Result<Option<TrajectoryEntry<S>>, TrajectoryError>
+    {
+        let mut flag = [0];
+        loop {
+            match self.reader.read(&mut flag) {
+                Ok(0) => return Ok(None),
+                Ok(_) => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let accepted = match flag[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(TrajectoryError::Corrupt(
+                format!("invalid acceptance flag of step {}", self.position)
+            ))
+        };
+        let step: S = step_options().deserialize_from(&mut self.reader)?;
+        if accepted && !self.ensemble.redo_step(&step) {
+            return Err(TrajectoryError::StepMismatch{position: self.position});
+        }
+        self.position += 1;
+        Ok(Some(TrajectoryEntry{step, accepted}))
+    }
+
+    /// # Replay until `target` steps are replayed
+    /// * afterwards [`ensemble`](Self::ensemble) is in the state after step number `target`
+    pub fn replay_until(&mut self, target: u64) -> Result<&E, TrajectoryError>
+    {
+        if target < self.position {
+            return Err(TrajectoryError::SeekBackwards{position: self.position, target});
+        }
+        while self.position < target {
+            if self.next_step()?.is_none() {
+                return Err(TrajectoryError::EndOfTrajectory{length: self.position});
+            }
+        }
+        Ok(&self.ensemble)
+    }
+
+    /// # Replay all remaining steps
+    /// * returns the length of the trajectory
+    pub fn replay_all(&mut self) -> Result<u64, TrajectoryError>
+    {
+        while self.next_step()?.is_some() {}
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        traits::*,
+        ConfigurationModel,
+        EmptyNode,
+        ErEnsembleC,
+        ErEnsembleM,
+        ErStepC,
+        ErStepM,
+        GenericGraph,
+        SwEnsemble,
+        rand::{Rng, SeedableRng},
+        spacial::SpacialEnsemble
+    };
+    use rand_pcg::Pcg64;
+
+    fn edges<T, A>(graph: &GenericGraph<T, A>) -> Vec<(usize, usize)>
+    where A: AdjContainer<T>
+    {
+        let mut edges: Vec<_> = graph.container_iter()
+            .enumerate()
+            .flat_map(
+                |(i, container)| container.neighbors()
+                    .filter(move |&&j| i < j)
+                    .map(move |&j| (i, j))
+            ).collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// records a chain with random rejections and compares the replay with every state
+    fn check<E, S, Res, T, A>(mut ensemble: E, rng: &mut Pcg64)
+    where E: MarkovChain<S, Res> + RedoStep<S> + Snapshot + AsRef<GenericGraph<T, A>>,
+          S: Serialize + DeserializeOwned,
+          A: AdjContainer<T>
+    {
+        let mut recorder = TrajectoryRecorder::new(Vec::new(), &ensemble).unwrap();
+        let mut states = vec![edges(ensemble.as_ref())];
+        for _ in 0..200 {
+            let step = ensemble.m_step();
+            let accepted = rng.gen_bool(0.7);
+            if !accepted {
+                ensemble.undo_step_quiet(&step);
+            }
+            recorder.record(&step, accepted).unwrap();
+            states.push(edges(ensemble.as_ref()));
+        }
+        assert_eq!(recorder.len(), 200);
+        let buf = recorder.into_inner().unwrap();
+
+        let mut replayer = TrajectoryReplayer::<_, E, S>::new(buf.as_slice()).unwrap();
+        assert_eq!(edges(replayer.ensemble().as_ref()), states[0]);
+        for state in &states[1..] {
+            replayer.next_step().unwrap().unwrap();
+            assert_eq!(&edges(replayer.ensemble().as_ref()), state);
+        }
+        assert!(replayer.next_step().unwrap().is_none());
+    }
+
+    #[test]
+    fn replay()
+    {
+        let mut rng = Pcg64::seed_from_u64(31209);
+        let mut new_rng = || Pcg64::from_rng(&mut rng).unwrap();
+        let mut accept_rng = new_rng();
+
+        check(ErEnsembleC::<EmptyNode, _>::new(50, 3.0, new_rng()), &mut accept_rng);
+        check(ErEnsembleM::<EmptyNode, _>::new(50, 70, new_rng()), &mut accept_rng);
+        check(SwEnsemble::<EmptyNode, _>::new(50, 0.2, new_rng()), &mut accept_rng);
+        check(SpacialEnsemble::<EmptyNode, _>::new(50, new_rng(), 0.95, 3.0), &mut accept_rng);
+        let config = ConfigurationModel::<EmptyNode, _>::from_const(4, 50, new_rng()).unwrap();
+        check(config, &mut accept_rng);
+    }
+
+    #[test]
+    fn errors()
+    {
+        type Replayer<'a> = TrajectoryReplayer<&'a [u8], ErEnsembleC<EmptyNode, Pcg64>, ErStepC>;
+        let rng = Pcg64::seed_from_u64(2391);
+        let mut ensemble = ErEnsembleC::<EmptyNode, _>::new(20, 3.0, rng);
+        let mut recorder = TrajectoryRecorder::new(Vec::new(), &ensemble).unwrap();
+        for _ in 0..10 {
+            let step = ensemble.m_step();
+            recorder.record(&step, true).unwrap();
+        }
+        let buf = recorder.into_inner().unwrap();
+
+        let mut replayer = Replayer::new(buf.as_slice()).unwrap();
+        replayer.replay_until(5).unwrap();
+        assert!(matches!(
+            replayer.replay_until(4),
+            Err(TrajectoryError::SeekBackwards{position: 5, target: 4})
+        ));
+        assert!(matches!(replayer.replay_until(11), Err(TrajectoryError::EndOfTrajectory{length: 10})));
+
+        assert!(matches!(Replayer::new(&buf[1..]), Err(TrajectoryError::NotATrajectory)));
+        let mut newer = buf.clone();
+        newer[8] = 2;
+        assert!(matches!(
+            Replayer::new(newer.as_slice()),
+            Err(TrajectoryError::UnsupportedFormatVersion{found: 2, supported: 1})
+        ));
+        assert!(matches!(
+            TrajectoryReplayer::<_, ErEnsembleM<EmptyNode, Pcg64>, ErStepM>::new(buf.as_slice()),
+            Err(TrajectoryError::Snapshot(SnapshotError::WrongEnsemble{..}))
+        ));
+
+        // step, that does not fit to the graph
+        let ensemble = ErEnsembleC::<EmptyNode, _>::new(20, 3.0, Pcg64::seed_from_u64(2));
+        let mut recorder = TrajectoryRecorder::new(Vec::new(), &ensemble).unwrap();
+        recorder.record(&ErStepC::Nothing, true).unwrap();
+        recorder.record(&ErStepC::AddedEdge((3, 20)), false).unwrap();
+        recorder.record(&ErStepC::AddedEdge((3, 20)), true).unwrap();
+        let buf = recorder.into_inner().unwrap();
+        let mut replayer = Replayer::new(buf.as_slice()).unwrap();
+        assert!(matches!(replayer.replay_all(), Err(TrajectoryError::StepMismatch{position: 2})));
+        assert_eq!(replayer.position(), 2);
+    }
+}
@@ -0,0 +1,71 @@
+//! Disjoint set forest, used internally to track connected components
+
+/// # Union-find with path halving and union by size
+/// * `find` and `union` run in amortized `O(α(n))`
+#[derive(Debug, Clone)]
+pub(crate) struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    /// every element is its own set
+    pub(crate) fn new(n: usize) -> Self {
+        Self{
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    /// number of elements
+    pub(crate) fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// adds a new element, which is its own set, returns its index
+    pub(crate) fn push(&mut self) -> usize {
+        let index = self.parent.len();
+        self.parent.push(index);
+        self.size.push(1);
+        index
+    }
+
+    /// size of the set containing `index`
+    pub(crate) fn size(&mut self, index: usize) -> usize {
+        let root = self.find(index);
+        self.size[root]
+    }
+
+    /// * reduces the size of the set containing `index` by `amount`
+    /// * used if elements of the set are no longer counted, e.g., because they were replaced
+    pub(crate) fn shrink(&mut self, index: usize, amount: usize) {
+        let root = self.find(index);
+        self.size[root] -= amount;
+    }
+
+    /// returns representative of the set containing `index`
+    pub(crate) fn find(&mut self, mut index: usize) -> usize {
+        while self.parent[index] != index {
+            let grand_parent = self.parent[self.parent[index]];
+            self.parent[index] = grand_parent;
+            index = grand_parent;
+        }
+        index
+    }
+
+    /// * merges the sets containing `a` and `b`
+    /// * returns the size of the merged set
+    pub(crate) fn union(&mut self, a: usize, b: usize) -> usize {
+        let mut root_a = self.find(a);
+        let mut root_b = self.find(b);
+        if root_a == root_b {
+            return self.size[root_a];
+        }
+        if self.size[root_a] < self.size[root_b] {
+            std::mem::swap(&mut root_a, &mut root_b);
+        }
+        self.parent[root_b] = root_a;
+        self.size[root_a] += self.size[root_b];
+        self.size[root_a]
+    }
+}
@@ -31,6 +31,12 @@ use {
 
 #[cfg(feature = "serde_support")]
 use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde_support")]
+use {
+    crate::snapshot::*,
+    serde::de::DeserializeOwned,
+    std::io::{Read, Write}
+};
 
 /// # Specific [GenericGraph] used for watts-strogatz small World graph
 pub type WSGraph<T> = GenericGraph<T,WSContainer<T>>;
@@ -333,6 +339,29 @@ where   T: Node + SerdeStateConform,
     }
 }
 
+#[cfg(feature = "serde_support")]
+impl<T, R> Snapshot for SmallWorldWS<T, R>
+where T: Node + DeserializeOwned,
+      R: Serialize + DeserializeOwned
+{
+    fn save_snapshot<W: Write>(&self, writer: W) -> Result<(), SnapshotError>
+    {
+        let mut snapshot = SnapshotWriter::new(writer, "SmallWorldWS", 1)?;
+        snapshot.graph(&self.graph)?;
+        snapshot.section(&(self.rewire_prob, self.neighbor_distance))?;
+        snapshot.section(&self.rng)
+    }
+
+    fn load_snapshot<Re: Read>(reader: Re) -> Result<Self, SnapshotError>
+    {
+        let mut snapshot = SnapshotReader::new(reader, "SmallWorldWS", 1)?;
+        let graph = snapshot.graph()?;
+        let (rewire_prob, neighbor_distance) = snapshot.section()?;
+        let rng = snapshot.section()?;
+        Ok(Self{graph, rewire_prob, rng, neighbor_distance})
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,6 +92,21 @@ impl<T> WSContainer<T>{
 
 }
 
+#[cfg(feature = "serde_support")]
+impl<T> crate::snapshot::SnapshotContainer<T> for WSContainer<T>
+{
+    /// edges of the original ring
+    type Extra = Vec<OriginalEdge>;
+
+    fn snapshot_extra(&self) -> Self::Extra {
+        self.original.clone()
+    }
+
+    fn from_snapshot(id: usize, node: T, to: Vec<usize>, original: Self::Extra) -> Option<Self> {
+        Some(Self{id, node, to, original})
+    }
+}
+
 impl<T> AdjContainer<T> for WSContainer<T>
 {
     fn new(id: usize, node: T) -> Self {
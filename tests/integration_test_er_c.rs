@@ -98,7 +98,7 @@ fn monte_continue(){
 fn test_graph_construction() {
     let rng = Pcg64::seed_from_u64(76);
     let e = ErEnsembleC::<EmptyNode, Pcg64>::new(20, 2.7, rng);
-    assert_eq!(e.graph().edge_count(), 28);
+    assert_eq!(e.graph().edge_count(), 24);
     assert_eq!(20, e.graph().vertex_count());
 }
 
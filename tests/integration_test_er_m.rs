@@ -59,6 +59,65 @@ fn step_test() {
 
 }
 
+#[test]
+fn dense_step_test() {
+    // more than half of all possible edges - missing edges are drawn instead
+    let rng = Pcg64::seed_from_u64(90721);
+    let mut e = ErEnsembleM::<EmptyNode, Pcg64>::new(30, 400, rng);
+    assert_eq!(e.graph().edge_count(), 400);
+    let mut e_0 = e.clone();
+    e_0.sort_adj();
+
+    let mut steps = Vec::new();
+    e.m_steps(500, &mut steps);
+    assert_eq!(e.graph().edge_count(), 400);
+    for container in e.graph().container_iter() {
+        let mut neighbors: Vec<_> = container.neighbors().copied().collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        assert_eq!(neighbors.len(), container.degree());
+        assert!(!neighbors.contains(&container.id()));
+    }
+    e.undo_steps_quiet(&steps);
+    e.sort_adj();
+    equal_graphs(e_0.graph(), e.graph());
+}
+
+#[test]
+fn randomize_uniform() {
+    let rng = Pcg64::seed_from_u64(3201);
+    let n = 6;
+    let m = 5;
+    let mut e = ErEnsembleM::<EmptyNode, Pcg64>::new(n, m, rng);
+    let samples = 30000;
+    let mut hits = vec![vec![0_usize; n]; n];
+    for _ in 0..samples {
+        e.randomize();
+        assert_eq!(e.graph().edge_count(), m);
+        for container in e.graph().container_iter() {
+            for &j in container.neighbors() {
+                hits[container.id()][j] += 1;
+            }
+        }
+    }
+    // each of the 15 possible edges is present with probability 1/3
+    let mean = samples as f64 / 3.0;
+    let sigma = (mean * 2.0 / 3.0).sqrt();
+    for (i, row) in hits.iter().enumerate() {
+        for &count in row.iter().skip(i + 1) {
+            assert!((count as f64 - mean).abs() < 5.0 * sigma);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn step_on_complete_graph() {
+    let rng = Pcg64::seed_from_u64(7567526);
+    let mut e = ErEnsembleM::<EmptyNode, Pcg64>::new(5, 10, rng);
+    e.m_step();
+}
+
 #[test]
 #[should_panic]
 fn to_many_edges() {
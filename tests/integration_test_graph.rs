@@ -3,6 +3,9 @@ use net_ensembles::*;
 use std::fs::File;
 use std::io::prelude::*;
 use net_ensembles::dot_constants::*;
+use rand::SeedableRng;
+use rand_pcg::Pcg64;
+mod common;
 
 fn create_graph_1() -> Graph<EmptyNode> {
     let mut graph: Graph<EmptyNode> = Graph::new(20);
@@ -134,6 +137,29 @@ fn dot_labeled() {
     assert_eq!(data, s);
 }
 
+#[test]
+fn dot_labeled_round_trip() {
+    let mut graph = create_graph_1();
+    let mut read_in = File::open("TestData/label_test.dot").expect("unable to open file");
+    let mut data = String::new();
+    read_in.read_to_string(&mut data).expect("unable to read file");
+
+    let mut labels = Vec::new();
+    let mut parsed: Graph<EmptyNode> = io::read_dot(
+        data.as_bytes(),
+        |_, attributes| {
+            labels.push(attributes["label"].to_string());
+            EmptyNode{}
+        }
+    ).unwrap();
+    for (index, label) in labels.iter().enumerate() {
+        assert_eq!(label, &format!("Hey {}!", index));
+    }
+    graph.sort_adj();
+    parsed.sort_adj();
+    common::equal_graphs(&graph, &parsed);
+}
+
 #[test]
 #[allow(deprecated)]
 fn dot() {
@@ -145,6 +171,15 @@ fn dot() {
     assert_eq!(data, s);
 }
 
+#[test]
+fn dot_round_trip() {
+    let mut graph = create_graph_1();
+    let mut parsed: Graph<EmptyNode> = io::read_graph("TestData/dotTest.dot").unwrap();
+    graph.sort_adj();
+    parsed.sort_adj();
+    common::equal_graphs(&graph, &parsed);
+}
+
 #[test]
 fn average_neighbor_count () {
     let mut graph: Graph<EmptyNode> = Graph::new(20);
@@ -235,6 +270,26 @@ fn bi_test2() {
     assert_eq!(vec![5], graph.vertex_biconnected_components(false));
 }
 
+#[test]
+fn bi_test_adjacency_order() {
+    // the result must not depend on the order of the adjacency lists
+    let mut graph: Graph<EmptyNode> = Graph::new(6);
+    graph.add_edge(0, 1).unwrap();
+    graph.add_edge(1, 2).unwrap();
+    graph.add_edge(2, 4).unwrap();
+    graph.add_edge(4, 0).unwrap();
+    graph.add_edge(4, 5).unwrap();
+    graph.add_edge(5, 2).unwrap();
+    graph.add_edge(1, 3).unwrap();
+
+    let mut rng = Pcg64::seed_from_u64(8923);
+    for _ in 0..100 {
+        graph.shuffle_adjs(&mut rng);
+        assert_eq!(graph.clone().vertex_biconnected_components(false), vec![5, 2]);
+        assert_eq!(CsrGraph::from(&graph).vertex_biconnected_components(false), vec![5, 2]);
+    }
+}
+
 #[test]
 fn vertex_load() {
     let mut graph: Graph<EmptyNode> = Graph::new(4);
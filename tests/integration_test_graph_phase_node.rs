@@ -47,6 +47,38 @@ fn phase_test() {
     assert_eq!(test_data, dot);
 }
 
+#[test]
+fn phase_dot_round_trip() {
+    let mut graph: Graph<PhaseNode> = Graph::new(4);
+    for i in 0..4 {
+        graph.add_edge(i, (i + 1) % 4).unwrap();
+        graph.at_mut(i).set_phase(i as f64 * 0.5);
+    }
+
+    // parse phases back from the labels
+    let file = File::open("TestData/phase_test.dot").expect("unable to open file");
+    let mut parsed: Graph<PhaseNode> = io::read_dot(
+        file,
+        |index, attributes| {
+            let label = attributes["label"].to_string();
+            let phase = label.strip_prefix("Phase: ")
+                .and_then(|rest| rest.split(' ').next())
+                .unwrap()
+                .parse()
+                .unwrap();
+            let mut node = PhaseNode::new_from_index(index);
+            node.set_phase(phase);
+            node
+        }
+    ).unwrap();
+    for i in 0..4 {
+        assert_eq!(parsed.at(i).get_phase(), graph.at(i).get_phase());
+    }
+    graph.sort_adj();
+    parsed.sort_adj();
+    equal_graphs(&graph, &parsed);
+}
+
 #[cfg(feature = "serde_support")]
 #[test]
 #[ignore]